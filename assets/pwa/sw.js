@@ -0,0 +1,64 @@
+// Serves the app offline. `CACHE_VERSION` and `PRECACHE_URLS` are filled in with the
+// hashed Trunk outputs by scripts/precache.sh after every build.
+const CACHE_PREFIX = "meditation-";
+const CACHE_VERSION = "dev";
+const PRECACHE_URLS = [];
+const PRECACHE = CACHE_PREFIX + CACHE_VERSION;
+// Files fetched on demand, such as guided meditation audio; kept across versions.
+const RUNTIME_CACHE = "runtime";
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(caches.open(PRECACHE).then((cache) => cache.addAll(PRECACHE_URLS)));
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((names) =>
+        Promise.all(
+          names
+            .filter((name) => name.startsWith(CACHE_PREFIX) && name !== PRECACHE)
+            .map((name) => caches.delete(name)),
+        ),
+      )
+      .then(() => self.clients.claim()),
+  );
+});
+
+// A new build waits until the page accepts the update prompt.
+self.addEventListener("message", (event) => {
+  if (event.data === "SKIP_WAITING") {
+    self.skipWaiting();
+  }
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  if (request.method !== "GET" || new URL(request.url).origin !== self.location.origin) {
+    return;
+  }
+
+  // Routes are resolved by the app, so every page is the cached index.html.
+  if (request.mode === "navigate") {
+    event.respondWith(
+      caches.match("/index.html", { cacheName: PRECACHE }).then((cached) => cached || fetch(request)),
+    );
+    return;
+  }
+
+  event.respondWith(
+    caches.match(request).then(
+      (cached) =>
+        cached ||
+        fetch(request).then((response) => {
+          // Partial responses to range requests can't be cached.
+          if (response.status === 200 && !request.headers.has("range")) {
+            const copy = response.clone();
+            caches.open(RUNTIME_CACHE).then((cache) => cache.put(request, copy));
+          }
+          return response;
+        }),
+    ),
+  );
+});
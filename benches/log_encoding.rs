@@ -0,0 +1,71 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use yew_app::utils::database::{
+    log_envelope::{decode_log, encode_log_as, LogEncoding},
+    meditation_log::meditation_data_builder::MeditationData,
+};
+
+const CATEGORIES: [&str; 4] = ["Mindfulness", "Relaxation", "Healing", "Sleep"];
+const SPEAKERS: [&str; 3] = ["Alice", "Bob", "Charlie"];
+
+/// Three sessions a day, every tenth with a note and some with moods and tags.
+fn synthetic_meditations(count: usize) -> Vec<MeditationData> {
+    (0..count)
+        .map(|i| MeditationData {
+            datetime: 1_600_000_000 + (i / 3) as i64 * 86_400 + (i % 3) as i64 * 14_400,
+            duration: 300 + (i % 12) as i32 * 150,
+            category: CATEGORIES[i % CATEGORIES.len()].to_string(),
+            speaker: SPEAKERS[i % SPEAKERS.len()].to_string(),
+            notes: (i % 10 == 0).then(|| format!("Session {} felt calm", i)),
+            mood_after: (i % 2 == 0).then_some((i % 5) as u8 + 1),
+            tags: if i % 4 == 0 {
+                vec!["morning".to_string()]
+            } else {
+                Vec::new()
+            },
+            id: format!("a1b2c3d4-{:x}", i),
+            modified_at: 1_600_000_000_000 + i as i64 * 1_000,
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn log_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stored log");
+    group.sample_size(10);
+
+    for count in [10_000, 100_000] {
+        let log = synthetic_meditations(count);
+        let json = encode_log_as(&log, LogEncoding::Json).unwrap();
+        let compact = encode_log_as(&log, LogEncoding::Compact).unwrap();
+        // The size is what the compact encoding is for; the timings show what it costs.
+        println!(
+            "{} sessions: JSON {} bytes, compact {} bytes ({:.1}%)",
+            count,
+            json.len(),
+            compact.len(),
+            compact.len() as f64 * 100.0 / json.len() as f64
+        );
+        assert_eq!(decode_log::<MeditationData>(&compact).unwrap(), log);
+
+        for (name, encoding, stored) in [
+            ("json", LogEncoding::Json, &json),
+            ("compact", LogEncoding::Compact, &compact),
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("write {}", name), count),
+                &log,
+                |b, log| b.iter(|| encode_log_as(black_box(log), encoding).unwrap()),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("read {}", name), count),
+                stored,
+                |b, stored| b.iter(|| decode_log::<MeditationData>(black_box(stored)).unwrap()),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, log_encoding);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+use chrono::NaiveDate;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use yew_app::utils::database::meditation_log::{
+    calculate_meditation_stats::StatsBuilder, meditation_data_builder::MeditationData,
+    stats_aggregator::StatsAggregator,
+};
+
+const SESSIONS: usize = 100_000;
+const CATEGORIES: [&str; 4] = ["Mindfulness", "Relaxation", "Healing", "Sleep"];
+const SPEAKERS: [&str; 3] = ["Alice", "Bob", "Charlie"];
+
+/// Three sessions a day with one rest day a week, ending on 2024-02-10.
+fn synthetic_meditations(count: usize) -> Vec<MeditationData> {
+    let days = (count / 3) as i64 * 7 / 6;
+    let start = 1707575913 - days * 86_400;
+    (0..count)
+        .map(|i| MeditationData {
+            datetime: start + (i / 3) as i64 * 7 / 6 * 86_400 + (i % 3) as i64 * 14_400,
+            duration: 300 + (i % 12) as i32 * 150,
+            category: CATEGORIES[i % CATEGORIES.len()].to_string(),
+            speaker: SPEAKERS[i % SPEAKERS.len()].to_string(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn stats_aggregation(c: &mut Criterion) {
+    let today = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+    let log = synthetic_meditations(SESSIONS);
+    let (history, latest) = log.split_at(SESSIONS - 1);
+    let latest = &latest[0];
+    let mut aggregator = StatsAggregator::from_meditations(history);
+
+    let mut group = c.benchmark_group("log one session over 100k");
+
+    // What the stats page did before: clone the whole log and rebuild from scratch.
+    group.bench_function("rebuild from log", |b| {
+        b.iter(|| {
+            StatsBuilder::new(black_box(&log).clone())
+                .today(today)
+                .build()
+        })
+    });
+
+    // Recording the same session repeatedly keeps the per-iteration cost constant.
+    group.bench_function("fold into aggregator", |b| {
+        b.iter(|| {
+            aggregator.record(black_box(latest));
+            aggregator.stats(today)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, stats_aggregation);
+criterion_main!(benches);
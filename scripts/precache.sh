@@ -0,0 +1,19 @@
+#!/bin/sh
+# Writes this build's files into the service worker's precache list. Trunk runs it as a
+# post_build hook, when every asset is in the staging directory. Audio is left out of
+# the precache and cached the first time it's played.
+set -eu
+
+cd "$TRUNK_STAGING_DIR"
+[ -f sw.js ] || exit 0
+
+files=$(find . -type f ! -name sw.js ! -path './audio/*' | sed 's|^\./||' | LC_ALL=C sort)
+urls=$(printf '%s\n' "$files" | sed 's|.*|"/&"|' | paste -sd, -)
+# Trunk hashes the wasm and js names, so the version changes whenever the build does.
+version=$(cat $files | cksum | cut -d ' ' -f 1)
+
+sed -i.bak \
+    -e "s|^const CACHE_VERSION = .*|const CACHE_VERSION = \"$version\";|" \
+    -e "s|^const PRECACHE_URLS = .*|const PRECACHE_URLS = [$urls];|" \
+    sw.js
+rm sw.js.bak
@@ -1,24 +1,37 @@
-use crate::pages::{
-    landing_page::LandingPage, test_page::TestPage, user_stats_page::UserStatsPage,
+use crate::{
+    components::{
+        features::{sync_agent::SyncAgent, unlock_gate::UnlockGate},
+        ui::{
+            pwa_banner::PwaBanner, reminder_banner::ReminderBanner, storage_banner::StorageBanner,
+        },
+    },
+    pages::{
+        breathing_page::BreathingPage, history_page::HistoryPage, landing_page::LandingPage,
+        privacy_page::PrivacyPage, profiles_page::ProfilesPage, session_form_page::SessionFormPage,
+        settings_page::SettingsPage, sync_page::SyncPage, tags_page::TagsPage, test_page::TestPage,
+        timer_page::TimerPage, user_stats_page::UserStatsPage,
+    },
+    routes::Route,
+    utils::hooks::use_profiles::ProfilesProvider,
 };
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-#[derive(Clone, Routable, PartialEq)]
-enum Route {
-    #[at("/")]
-    Home,
-    #[at("/user-stats")]
-    UserStats,
-    #[at("/test")] // Add a route for the Test page
-    Test,
-    // Add more routes here if needed
-}
-
 fn switch(routes: &Route) -> Html {
     match routes {
         Route::Home => html! { <LandingPage /> },
         Route::UserStats => html! { <UserStatsPage/> },
+        Route::Timer => html! { <TimerPage/> },
+        Route::GuidedTimer { id } => html! { <TimerPage guided={Some(id.clone())} /> },
+        Route::Breathing => html! { <BreathingPage/> },
+        Route::History => html! { <HistoryPage/> },
+        Route::NewSession => html! { <SessionFormPage /> },
+        Route::EditSession { id } => html! { <SessionFormPage id={Some(id.clone())} /> },
+        Route::Tags => html! { <TagsPage/> },
+        Route::Sync => html! { <SyncPage/> },
+        Route::Privacy => html! { <PrivacyPage/> },
+        Route::Profiles => html! { <ProfilesPage/> },
+        Route::Settings => html! { <SettingsPage/> },
         Route::Test => html! { <TestPage  /> }, // Handle the Test route
                                                 // Handle other routes as needed
     }
@@ -27,8 +40,20 @@ fn switch(routes: &Route) -> Html {
 #[function_component(App)]
 pub fn app() -> Html {
     html! {
-        <BrowserRouter>
-            <Switch<Route> render={|routes: Route| switch(&routes)} />
-        </BrowserRouter>
+        <>
+            <PwaBanner/>
+            // Nothing may read the stored sessions before they are unlocked.
+            <UnlockGate>
+                <BrowserRouter>
+                    // Everything below is mounted afresh for each profile.
+                    <ProfilesProvider>
+                        <SyncAgent/>
+                        <StorageBanner/>
+                        <ReminderBanner/>
+                        <Switch<Route> render={|routes: Route| switch(&routes)} />
+                    </ProfilesProvider>
+                </BrowserRouter>
+            </UnlockGate>
+        </>
     }
 }
@@ -0,0 +1,79 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    services::file_upload::FileUpload,
+    utils::{
+        database::meditation_log::read_write_backup::{export_backup, restore_backup},
+        hooks::use_meditation_state::use_reload_meditations,
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct BackupPanelProps {}
+
+/// Saves the whole log to a backup file, and merges a backup file back in.
+#[function_component]
+pub fn BackupPanel(props: &BackupPanelProps) -> Html {
+    let BackupPanelProps {} = props;
+
+    let message = use_state(|| None::<String>);
+    let reload_meditations = use_reload_meditations();
+
+    let on_export = {
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Err(error) = export_backup() {
+                message.set(Some(format!("Backup failed: {:?}", error)));
+            }
+        })
+    };
+
+    let on_restore = {
+        let message = message.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Clear the input so picking the same file again restores it again.
+            input.set_value("");
+            let message = message.clone();
+            let reload_meditations = reload_meditations.clone();
+            FileUpload::read_text(&file, move |result| match result {
+                Ok(json) => match restore_backup(&json) {
+                    Ok(0) => message.set(Some(
+                        "Everything in the backup is already here.".to_string(),
+                    )),
+                    Ok(changed) => {
+                        reload_meditations.emit(());
+                        message.set(Some(format!("Restored {} sessions", changed)));
+                    }
+                    Err(error) => message.set(Some(format!("Restore failed: {}", error))),
+                },
+                Err(_) => message.set(Some("Restore failed: the file can't be read".to_string())),
+            });
+        })
+    };
+
+    let button = "px-4 py-2 rounded-lg font-semibold";
+
+    html! {
+        <Card>
+            <div class="flex flex-col gap-3 w-72 text-sm">
+                <div>{"A backup holds every session, including deletions. Restoring merges it with this device, so newer changes are kept."}</div>
+                <div class="flex gap-2">
+                    <button class={classes!(button, "bg-emerald-600", "text-white")} onclick={on_export}>{"Export Backup"}</button>
+                    <label class={classes!(button, "bg-gray-200", "cursor-pointer")}>
+                        {"Restore…"}
+                        <input class="hidden" type="file" accept="application/json,.json" onchange={on_restore} />
+                    </label>
+                </div>
+                if let Some(message) = (*message).clone() {
+                    <div class="text-gray-600">{message}</div>
+                }
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::Utc;
+use gloo_timers::callback::Interval;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        breathing::breathing_pattern::{BreathPhase, BreathPosition, BreathingPattern},
+        database::meditation_log::read_write_meditation_data::log_meditation_data,
+        hooks::use_meditation_state::use_record_meditation,
+        shared::format_clock,
+    },
+};
+
+const BREATHING_CATEGORY: &str = "Breathing";
+const DEFAULT_CYCLES: u32 = 6;
+const TICK_MILLIS: u32 = 100;
+
+fn now_ms() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+/// Size of the pacer circle: it grows while breathing in and shrinks while breathing out.
+fn pacer_scale(position: &BreathPosition) -> f32 {
+    match position.phase {
+        BreathPhase::Inhale => 0.5 + 0.5 * position.progress,
+        BreathPhase::HoldIn => 1.0,
+        BreathPhase::Exhale => 1.0 - 0.5 * position.progress,
+        BreathPhase::HoldOut => 0.5,
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct BreathingExerciseProps {}
+
+#[function_component]
+pub fn BreathingExercise(props: &BreathingExerciseProps) -> Html {
+    let BreathingExerciseProps {} = props;
+
+    let presets = BreathingPattern::presets();
+    // `None` selects the custom pattern.
+    let preset = use_state(|| Some(0usize));
+    let custom = use_state(|| [4u32, 2, 6, 0]);
+    let cycles = use_state(|| DEFAULT_CYCLES);
+    let started_at = use_state(|| None::<i64>);
+    let now = use_state(now_ms);
+    let message = use_state(|| None::<String>);
+    let record_meditation = use_record_meditation();
+
+    let pattern = match *preset {
+        Some(index) => presets[index].clone(),
+        None => BreathingPattern::custom(
+            "Custom breathing",
+            custom[0],
+            custom[1],
+            custom[2],
+            custom[3],
+        ),
+    };
+    let total_ms = pattern.total_seconds(*cycles) as i64 * 1000;
+    let elapsed_ms = started_at
+        .map(|start| (*now - start).clamp(0, total_ms))
+        .unwrap_or(0);
+
+    {
+        let now = now.clone();
+        use_effect_with(started_at.is_some(), move |running| {
+            let interval = running.then(|| Interval::new(TICK_MILLIS, move || now.set(now_ms())));
+            move || drop(interval)
+        });
+    }
+
+    // Log the session once all cycles are done.
+    {
+        let started_at = started_at.clone();
+        let message = message.clone();
+        let pattern = pattern.clone();
+        let cycles = *cycles;
+        use_effect_with(*now, move |now| {
+            if let Some(start) = *started_at {
+                if *now - start >= total_ms {
+                    started_at.set(None);
+                    let seconds = pattern.total_seconds(cycles) as i32;
+                    match log_meditation_data(
+                        seconds,
+                        BREATHING_CATEGORY.to_string(),
+                        pattern.name.clone(),
+                    ) {
+                        Ok(meditation) => {
+                            record_meditation.emit(meditation);
+                            message.set(Some(format!(
+                                "Logged {} of breathing",
+                                format_clock(seconds as i64)
+                            )));
+                        }
+                        Err(error) => message.set(Some(format!("Session not logged: {}", error))),
+                    }
+                }
+            }
+        });
+    }
+
+    let on_start = {
+        let started_at = started_at.clone();
+        let now = now.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let at = now_ms();
+            message.set(None);
+            now.set(at);
+            started_at.set(Some(at));
+        })
+    };
+
+    let on_stop = {
+        let started_at = started_at.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            started_at.set(None);
+            message.set(Some("Stopped early, nothing was logged".to_string()));
+        })
+    };
+
+    let on_preset = {
+        let preset = preset.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            preset.set(input.value().parse::<usize>().ok());
+        })
+    };
+
+    let on_cycles = {
+        let cycles = cycles.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<u32>() {
+                cycles.set(value.clamp(1, 100));
+            }
+        })
+    };
+
+    let on_custom = |step: usize| {
+        let custom = custom.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<u32>() {
+                let mut next = *custom;
+                next[step] = value.min(30);
+                custom.set(next);
+            }
+        })
+    };
+
+    let running = started_at.is_some();
+    let position = running.then(|| pattern.position_at(elapsed_ms)).flatten();
+    let scale = position.as_ref().map(pacer_scale).unwrap_or(0.5);
+    let button = "px-4 py-2 rounded-lg font-semibold";
+
+    html! {
+        <Card>
+            <div class="flex flex-col items-center gap-3 w-72">
+                <div class="flex items-center justify-center w-48 h-48">
+                    <div
+                        class="flex items-center justify-center w-48 h-48 rounded-full bg-sky-200 transition-transform duration-100 ease-linear"
+                        style={format!("transform: scale({:.3})", scale)}
+                    >
+                        <span class="text-lg font-semibold text-sky-900">
+                            {
+                                match &position {
+                                    Some(position) => format!("{} {}", position.phase.label(), position.remaining_seconds),
+                                    None => "Ready".to_string(),
+                                }
+                            }
+                        </span>
+                    </div>
+                </div>
+                <div class="text-sm text-gray-500">
+                    {
+                        match &position {
+                            Some(position) => format!("Cycle {} of {} · {} left", position.cycle + 1, *cycles, format_clock((total_ms - elapsed_ms) / 1000)),
+                            None => format!("{} cycles · {}", *cycles, format_clock(total_ms / 1000)),
+                        }
+                    }
+                </div>
+
+                <div class="grid w-full grid-cols-2 gap-2 text-sm">
+                    <label for="breathing-pattern">{"Pattern"}</label>
+                    <select id="breathing-pattern" class="px-2 border rounded" disabled={running} onchange={on_preset}>
+                        {
+                            presets.iter().enumerate().map(|(index, pattern)| html! {
+                                <option value={index.to_string()} selected={*preset == Some(index)}>{pattern.name.clone()}</option>
+                            }).collect::<Html>()
+                        }
+                        <option value="custom" selected={preset.is_none()}>{"Custom"}</option>
+                    </select>
+                    if preset.is_none() {
+                        {
+                            ["In (s)", "Hold (s)", "Out (s)", "Hold after out (s)"].iter().enumerate().map(|(step, label)| html! {
+                                <>
+                                    <label>{*label}</label>
+                                    <input class="px-2 border rounded" type="number" min="0" max="30"
+                                        value={custom[step].to_string()} disabled={running} onchange={on_custom(step)} />
+                                </>
+                            }).collect::<Html>()
+                        }
+                    }
+                    <label for="breathing-cycles">{"Cycles"}</label>
+                    <input id="breathing-cycles" class="px-2 border rounded" type="number" min="1" max="100"
+                        value={cycles.to_string()} disabled={running} onchange={on_cycles} />
+                </div>
+
+                if running {
+                    <button class={classes!(button, "bg-gray-200")} onclick={on_stop}>{"Stop"}</button>
+                } else {
+                    <button class={classes!(button, "bg-sky-600", "text-white")}
+                        disabled={total_ms == 0} onclick={on_start}>{"Start"}</button>
+                }
+
+                if let Some(message) = (*message).clone() {
+                    <div class="text-sm text-gray-600">{message}</div>
+                }
+            </div>
+        </Card>
+    }
+}
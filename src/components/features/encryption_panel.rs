@@ -0,0 +1,157 @@
+use gloo_timers::callback::Timeout;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::database::{
+        encryption::{EncryptionError, MIN_PASSPHRASE_LEN},
+        protected_storage::{lock, lock_state, remove_passphrase, set_passphrase, LockState},
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct EncryptionPanelProps {}
+
+fn describe(error: EncryptionError) -> String {
+    match error {
+        EncryptionError::WrongPassphrase => "The current passphrase is not correct.".to_string(),
+        other => format!("Nothing was changed: {}", other),
+    }
+}
+
+/// Turns passphrase encryption of the stored profile data on and off, and changes the passphrase.
+#[function_component]
+pub fn EncryptionPanel(props: &EncryptionPanelProps) -> Html {
+    let EncryptionPanelProps {} = props;
+
+    let state = use_state(lock_state);
+    let current = use_state(String::new);
+    let new = use_state(String::new);
+    let confirm = use_state(String::new);
+    let busy = use_state(|| false);
+    let message = use_state(|| None::<String>);
+
+    let bind = |field: &UseStateHandle<String>| {
+        let field = field.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            field.set(input.value());
+        })
+    };
+
+    // Runs a slow key derivation after the busy state has rendered, then resets the form.
+    let run = {
+        let state = state.clone();
+        let current = current.clone();
+        let new = new.clone();
+        let confirm = confirm.clone();
+        let busy = busy.clone();
+        let message = message.clone();
+        move |task: Box<dyn FnOnce() -> Result<String, EncryptionError>>| {
+            busy.set(true);
+            message.set(None);
+            let state = state.clone();
+            let current = current.clone();
+            let new = new.clone();
+            let confirm = confirm.clone();
+            let busy = busy.clone();
+            let message = message.clone();
+            Timeout::new(0, move || {
+                message.set(Some(task().unwrap_or_else(describe)));
+                for field in [&current, &new, &confirm] {
+                    field.set(String::new());
+                }
+                busy.set(false);
+                state.set(lock_state());
+            })
+            .forget();
+        }
+    };
+
+    let on_set = {
+        let run = run.clone();
+        let state = state.clone();
+        let current = current.clone();
+        let new = new.clone();
+        let confirm = confirm.clone();
+        let message = message.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if *new != *confirm {
+                message.set(Some("The new passphrases don't match.".to_string()));
+                return;
+            }
+            let encrypted = *state != LockState::Unencrypted;
+            let current = (*current).clone();
+            let new = (*new).clone();
+            run(Box::new(move || {
+                set_passphrase(encrypted.then_some(current.as_str()), &new)?;
+                Ok(if encrypted {
+                    "Passphrase changed.".to_string()
+                } else {
+                    "Your sessions are now encrypted.".to_string()
+                })
+            }));
+        })
+    };
+
+    let on_remove = {
+        let current = current.clone();
+        Callback::from(move |_: MouseEvent| {
+            let current = (*current).clone();
+            run(Box::new(move || {
+                remove_passphrase(&current)?;
+                Ok("Encryption is off.".to_string())
+            }));
+        })
+    };
+
+    let on_lock = Callback::from(|_: MouseEvent| {
+        lock();
+        // Reloading shows the passphrase prompt again.
+        if let Some(window) = web_sys::window() {
+            let _ = window.location().reload();
+        }
+    });
+
+    let button = "px-4 py-2 rounded-lg font-semibold";
+    let encrypted = *state != LockState::Unencrypted;
+
+    html! {
+        <Card>
+            <form class="flex flex-col gap-2 w-72 text-sm" onsubmit={on_set}>
+                if encrypted {
+                    <div>{"Your sessions, settings and reminders are encrypted with your passphrase. Profile names and backup files are not."}</div>
+                    <label for="current-passphrase">{"Current passphrase"}</label>
+                    <input id="current-passphrase" class="px-2 border rounded" type="password"
+                        autocomplete="current-password" value={(*current).clone()} oninput={bind(&current)} />
+                } else {
+                    <div>{format!("Encrypt your sessions, notes, moods, settings and reminders on this device with a passphrase of at least {} characters. Profile names stay readable. If you forget it, your data can't be recovered.", MIN_PASSPHRASE_LEN)}</div>
+                }
+                <label for="new-passphrase">{ if encrypted { "New passphrase" } else { "Passphrase" } }</label>
+                <input id="new-passphrase" class="px-2 border rounded" type="password"
+                    autocomplete="new-password" value={(*new).clone()} oninput={bind(&new)} />
+                <label for="confirm-passphrase">{"Repeat passphrase"}</label>
+                <input id="confirm-passphrase" class="px-2 border rounded" type="password"
+                    autocomplete="new-password" value={(*confirm).clone()} oninput={bind(&confirm)} />
+                <button type="submit" class={classes!(button, "bg-emerald-600", "text-white")} disabled={*busy}>
+                    { match (*busy, encrypted) {
+                        (true, _) => "Encrypting…",
+                        (false, true) => "Change Passphrase",
+                        (false, false) => "Turn On Encryption",
+                    } }
+                </button>
+                if encrypted {
+                    <div class="flex gap-2">
+                        <button type="button" class={classes!(button, "bg-gray-200")} disabled={*busy} onclick={on_remove}>{"Turn Off"}</button>
+                        <button type="button" class={classes!(button, "bg-gray-200")} onclick={on_lock}>{"Lock Now"}</button>
+                    </div>
+                }
+                if let Some(message) = (*message).clone() {
+                    <div class="text-gray-600">{message}</div>
+                }
+            </form>
+        </Card>
+    }
+}
@@ -0,0 +1,70 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    routes::Route,
+    utils::{
+        library::guided_meditations::{library_categories, LIBRARY},
+        shared::format_duration,
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct GuidedLibraryProps {}
+
+/// Browses the built-in guided meditations; each one opens the timer pre-filled with it.
+#[function_component]
+pub fn GuidedLibrary(props: &GuidedLibraryProps) -> Html {
+    let GuidedLibraryProps {} = props;
+    let category = use_state(|| None::<&'static str>);
+
+    let filter_button = |label: &'static str, value: Option<&'static str>| {
+        let selected = *category == value;
+        let onclick = {
+            let category = category.clone();
+            Callback::from(move |_: MouseEvent| category.set(value))
+        };
+        html! {
+            <button class={classes!("px-2", "py-1", "rounded-full", "text-xs",
+                if selected { "bg-emerald-600 text-white" } else { "bg-gray-200" })}
+                {onclick}>{label}</button>
+        }
+    };
+
+    html! {
+        <Card>
+            <div class="flex flex-col gap-3 w-80">
+                <div class="text-lg font-semibold">{"Guided Meditations"}</div>
+                <div class="flex flex-wrap gap-1">
+                    {filter_button("All", None)}
+                    { library_categories().into_iter().map(|name| filter_button(name, Some(name))).collect::<Html>() }
+                </div>
+                <ul class="flex flex-col gap-2">
+                    {
+                        LIBRARY.iter()
+                            .filter(|meditation| category.is_none_or(|name| meditation.category == name))
+                            .map(|meditation| html! {
+                                <li class="p-2 border rounded-lg">
+                                    <div class="flex items-baseline justify-between">
+                                        <span class="font-semibold">{meditation.title}</span>
+                                        <span class="text-xs text-gray-500">{format_duration(meditation.length_seconds)}</span>
+                                    </div>
+                                    <div class="text-xs text-gray-500">
+                                        {meditation.speaker}{" · "}{meditation.category}
+                                        if meditation.audio_url.is_some() {
+                                            {" · Audio"}
+                                        }
+                                    </div>
+                                    <div class="text-sm">{meditation.description}</div>
+                                    <Link<Route> to={Route::GuidedTimer { id: meditation.id.to_string() }}
+                                        classes="text-sm text-emerald-700 underline">{"Start"}</Link<Route>>
+                                </li>
+                            })
+                            .collect::<Html>()
+                    }
+                </ul>
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,346 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        database::meditation_log::{
+            catalog::{Catalog, CatalogKind},
+            meditation_data_builder::{
+                MeditationData, MeditationDataBuilder, MeditationDataError, MeditationField,
+                SCORE_RANGE,
+            },
+            read_write_catalog::read_catalog,
+            read_write_meditation_data::{save_meditation, update_meditation},
+        },
+        hooks::{
+            use_meditation_state::{
+                use_record_meditation, use_reload_meditations, use_stats_aggregator,
+            },
+            use_settings::use_settings,
+        },
+        settings::app_settings::Settings,
+    },
+};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
+/// The raw text of the form inputs, kept as typed so invalid values can be corrected.
+#[derive(Clone, Debug, PartialEq)]
+struct FormValues {
+    date: String,
+    time: String,
+    minutes: String,
+    category: String,
+    speaker: String,
+    notes: String,
+    mood_before: String,
+    mood_after: String,
+    rating: String,
+    /// Comma-separated.
+    tags: String,
+}
+
+impl FormValues {
+    /// An empty form for a session starting now, with the default length and category.
+    fn new_session(settings: &Settings) -> Self {
+        let now = Local::now();
+        FormValues {
+            date: now.format(DATE_FORMAT).to_string(),
+            time: now.format(TIME_FORMAT).to_string(),
+            minutes: settings.default_session_minutes.to_string(),
+            category: settings.default_category.clone(),
+            speaker: String::new(),
+            notes: String::new(),
+            mood_before: String::new(),
+            mood_after: String::new(),
+            rating: String::new(),
+            tags: String::new(),
+        }
+    }
+
+    fn from_meditation(meditation: &MeditationData) -> Self {
+        let start = Local
+            .timestamp_opt(meditation.datetime, 0)
+            .single()
+            .unwrap_or_else(Local::now);
+        FormValues {
+            date: start.format(DATE_FORMAT).to_string(),
+            time: start.format(TIME_FORMAT).to_string(),
+            minutes: format!("{}", meditation.duration as f32 / 60.0),
+            category: meditation.category.clone(),
+            speaker: meditation.speaker.clone(),
+            notes: meditation.notes.clone().unwrap_or_default(),
+            mood_before: score_text(meditation.mood_before),
+            mood_after: score_text(meditation.mood_after),
+            rating: score_text(meditation.rating),
+            tags: meditation.tags.join(", "),
+        }
+    }
+
+    /// Converts the inputs into a builder; unparsable values are left unset so
+    /// validation reports them against their field.
+    fn to_builder(&self) -> MeditationDataBuilder {
+        let mut builder = MeditationDataBuilder::new();
+
+        let date = NaiveDate::parse_from_str(&self.date, DATE_FORMAT).ok();
+        let time = NaiveTime::parse_from_str(&self.time, TIME_FORMAT).ok();
+        if let (Some(date), Some(time)) = (date, time) {
+            if let Some(start) = Local
+                .from_local_datetime(&NaiveDateTime::new(date, time))
+                .earliest()
+            {
+                builder.datetime_at(start.timestamp());
+            }
+        }
+        if let Ok(minutes) = self.minutes.trim().parse::<f32>() {
+            builder.duration((minutes * 60.0).round() as i32);
+        }
+        builder
+            .category(self.category.trim())
+            .speaker(self.speaker.trim())
+            .notes(self.notes.trim())
+            .tags(self.tags.split(','));
+        if let Ok(mood) = self.mood_before.parse::<u8>() {
+            builder.mood_before(mood);
+        }
+        if let Ok(mood) = self.mood_after.parse::<u8>() {
+            builder.mood_after(mood);
+        }
+        if let Ok(rating) = self.rating.parse::<u8>() {
+            builder.rating(rating);
+        }
+        builder
+    }
+}
+
+/// Values used so far, most used first, followed by catalog entries not used yet.
+fn suggestions(used: Vec<String>, catalog: &Catalog, kind: CatalogKind) -> Vec<String> {
+    let unused: Vec<String> = catalog
+        .entries(kind)
+        .iter()
+        .filter(|entry| !used.contains(&entry.name))
+        .map(|entry| entry.name.clone())
+        .collect();
+    used.into_iter().chain(unused).collect()
+}
+
+fn score_text(score: Option<u8>) -> String {
+    score.map(|score| score.to_string()).unwrap_or_default()
+}
+
+fn error_message(error: &MeditationDataError) -> &'static str {
+    match error {
+        MeditationDataError::InvalidDatetime => "Enter a valid date and time",
+        MeditationDataError::InvalidDuration => "Enter a length longer than zero minutes",
+        MeditationDataError::Missing(MeditationField::Category)
+        | MeditationDataError::Blank(MeditationField::Category) => "Enter a category",
+        MeditationDataError::Missing(MeditationField::Speaker)
+        | MeditationDataError::Blank(MeditationField::Speaker) => "Enter a speaker",
+        MeditationDataError::Missing(_) | MeditationDataError::Blank(_) => "Enter a value",
+        MeditationDataError::OutOfRange(_) => "Choose a score from 1 to 5",
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct MeditationFormProps {
+    /// The current values of the session to edit, saved back by its id; `None` adds a new
+    /// session.
+    #[prop_or_default]
+    pub editing: Option<MeditationData>,
+    /// Called with the session after it was saved.
+    #[prop_or_default]
+    pub on_saved: Callback<MeditationData>,
+}
+
+#[function_component]
+pub fn MeditationForm(props: &MeditationFormProps) -> Html {
+    let MeditationFormProps { editing, on_saved } = props;
+
+    let settings = use_settings().settings;
+    let values = {
+        let editing = editing.clone();
+        let settings = settings.clone();
+        use_state(move || match &editing {
+            Some(meditation) => FormValues::from_meditation(meditation),
+            None => FormValues::new_session(&settings),
+        })
+    };
+    let errors = use_state(Vec::<MeditationDataError>::new);
+    let catalog = use_state(read_catalog);
+    let message = use_state(|| None::<String>);
+    let aggregator = use_stats_aggregator();
+    let record_meditation = use_record_meditation();
+    let reload_meditations = use_reload_meditations();
+
+    let on_input = |set: fn(&mut FormValues, String)| {
+        let values = values.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*values).clone();
+            set(&mut next, input.value());
+            values.set(next);
+        })
+    };
+
+    let on_submit = {
+        let values = values.clone();
+        let errors = errors.clone();
+        let message = message.clone();
+        let editing = editing.clone();
+        let on_saved = on_saved.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let mut builder = values.to_builder();
+            if let Some(plan) = editing
+                .as_ref()
+                .and_then(|meditation| meditation.plan.clone())
+            {
+                builder.plan(plan);
+            }
+
+            let found = builder.validate();
+            if !found.is_empty() {
+                errors.set(found);
+                message.set(None);
+                return;
+            }
+            errors.set(Vec::new());
+
+            let saved = match &editing {
+                Some(meditation) => update_meditation(&meditation.id, &builder).map(|updated| {
+                    if updated.is_some() {
+                        reload_meditations.emit(());
+                    }
+                    updated
+                }),
+                None => save_meditation(&builder).map(|meditation| {
+                    record_meditation.emit(meditation.clone());
+                    Some(meditation)
+                }),
+            };
+            match saved {
+                Ok(Some(meditation)) => {
+                    if editing.is_none() {
+                        // Keep the category and speaker, they are often the same for the next entry.
+                        values.set(FormValues {
+                            category: values.category.clone(),
+                            speaker: values.speaker.clone(),
+                            ..FormValues::new_session(&settings)
+                        });
+                    }
+                    message.set(Some("Session saved".to_string()));
+                    on_saved.emit(meditation);
+                }
+                Ok(None) => message.set(Some("This session no longer exists".to_string())),
+                Err(error) => errors.set(vec![error]),
+            }
+        })
+    };
+
+    let field_error = |field: MeditationField| -> Html {
+        match errors.iter().find(|error| error.field() == field) {
+            Some(error) => html! {
+                <div class="col-span-2 text-xs text-red-600">{error_message(error)}</div>
+            },
+            None => html! {},
+        }
+    };
+    let input_class = |field: MeditationField| {
+        classes!(
+            "px-2",
+            "border",
+            "rounded",
+            errors
+                .iter()
+                .any(|error| error.field() == field)
+                .then_some("border-red-500")
+        )
+    };
+    let datalist = |id: &'static str, options: Vec<String>| {
+        html! {
+            <datalist id={id}>
+                { options.into_iter().map(|option| html! { <option value={option} /> }).collect::<Html>() }
+            </datalist>
+        }
+    };
+
+    let score_select = |id: &'static str, value: &str, oninput: Callback<InputEvent>| {
+        html! {
+            <select id={id} class="px-2 border rounded" {oninput}>
+                <option value="" selected={value.is_empty()}>{"–"}</option>
+                {
+                    SCORE_RANGE.map(|score| score.to_string()).map(|score| html! {
+                        <option value={score.clone()} selected={score == value}>{score.clone()}</option>
+                    }).collect::<Html>()
+                }
+            </select>
+        }
+    };
+
+    html! {
+        <Card>
+            <form class="flex flex-col items-center gap-3 w-72" onsubmit={on_submit} novalidate={true}>
+                <div class="grid w-full grid-cols-2 gap-2 text-sm">
+                    <label for="session-date">{"Date"}</label>
+                    <input id="session-date" class={input_class(MeditationField::Datetime)} type="date"
+                        value={values.date.clone()} oninput={on_input(|values, value| values.date = value)} />
+                    <label for="session-time">{"Start time"}</label>
+                    <input id="session-time" class={input_class(MeditationField::Datetime)} type="time"
+                        value={values.time.clone()} oninput={on_input(|values, value| values.time = value)} />
+                    {field_error(MeditationField::Datetime)}
+
+                    <label for="session-minutes">{"Length (min)"}</label>
+                    <input id="session-minutes" class={input_class(MeditationField::Duration)} type="number"
+                        min="0" step="any" value={values.minutes.clone()}
+                        oninput={on_input(|values, value| values.minutes = value)} />
+                    {field_error(MeditationField::Duration)}
+
+                    <label for="session-category">{"Category"}</label>
+                    <input id="session-category" class={input_class(MeditationField::Category)} type="text"
+                        list="session-categories" value={values.category.clone()}
+                        oninput={on_input(|values, value| values.category = value)} />
+                    {field_error(MeditationField::Category)}
+
+                    <label for="session-speaker">{"Speaker"}</label>
+                    <input id="session-speaker" class={input_class(MeditationField::Speaker)} type="text"
+                        list="session-speakers" value={values.speaker.clone()}
+                        oninput={on_input(|values, value| values.speaker = value)} />
+                    {field_error(MeditationField::Speaker)}
+
+                    <label for="session-mood-before">{"Mood before (1–5)"}</label>
+                    {score_select("session-mood-before", &values.mood_before,
+                        on_input(|values, value| values.mood_before = value))}
+                    {field_error(MeditationField::MoodBefore)}
+                    <label for="session-mood-after">{"Mood after (1–5)"}</label>
+                    {score_select("session-mood-after", &values.mood_after,
+                        on_input(|values, value| values.mood_after = value))}
+                    {field_error(MeditationField::MoodAfter)}
+                    <label for="session-rating">{"Rating (1–5)"}</label>
+                    {score_select("session-rating", &values.rating,
+                        on_input(|values, value| values.rating = value))}
+                    {field_error(MeditationField::Rating)}
+
+                    <label for="session-tags">{"Tags"}</label>
+                    <input id="session-tags" class="px-2 border rounded" type="text" placeholder="calm, morning"
+                        value={values.tags.clone()} oninput={on_input(|values, value| values.tags = value)} />
+                    <label for="session-notes" class="col-span-2">{"Notes"}</label>
+                    <textarea id="session-notes" class="col-span-2 px-2 border rounded" rows="3"
+                        value={values.notes.clone()} oninput={on_input(|values, value| values.notes = value)} />
+                </div>
+                {datalist("session-categories", suggestions(aggregator.categories_by_use(), &catalog, CatalogKind::Category))}
+                {datalist("session-speakers", suggestions(aggregator.speakers_by_use(), &catalog, CatalogKind::Speaker))}
+
+                <button type="submit" class="px-4 py-2 rounded-lg font-semibold bg-emerald-600 text-white">
+                    { if editing.is_some() { "Save Changes" } else { "Add Session" } }
+                </button>
+
+                if let Some(message) = (*message).clone() {
+                    <div class="text-sm text-gray-600">{message}</div>
+                }
+            </form>
+        </Card>
+    }
+}
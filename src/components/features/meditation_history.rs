@@ -0,0 +1,304 @@
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use web_sys::{wasm_bindgen::JsValue, HtmlInputElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    routes::Route,
+    services::file_download::FileDownload,
+    utils::{
+        database::meditation_log::{
+            csv_export::to_csv,
+            meditation_data_builder::MeditationData,
+            meditation_query::{MeditationQuery, SortOrder},
+            read_write_meditation_data::{delete_meditation, read_meditation_data},
+        },
+        hooks::use_meditation_state::{use_reload_meditations, use_stats_aggregator},
+        shared::{format_date_time, format_duration},
+    },
+};
+
+const SESSIONS_PER_PAGE: usize = 20;
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The filter inputs as typed; empty strings mean "any".
+#[derive(Clone, Debug, Default, PartialEq)]
+struct HistoryFilters {
+    from_date: String,
+    to_date: String,
+    category: String,
+    speaker: String,
+    tag: String,
+    sort: SortOrder,
+}
+
+/// Local midnight at the start of `date`, as a UNIX timestamp.
+fn local_midnight(date: NaiveDate) -> Option<i64> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|datetime| datetime.timestamp())
+}
+
+impl HistoryFilters {
+    fn to_query(&self) -> MeditationQuery {
+        let date = |value: &str| NaiveDate::parse_from_str(value, DATE_FORMAT).ok();
+        let text = |value: &str| (!value.is_empty()).then(|| value.to_string());
+        MeditationQuery {
+            from: date(&self.from_date).and_then(local_midnight),
+            // The "to" date is inclusive, so stop at the start of the next day.
+            until: date(&self.to_date).and_then(|date| local_midnight(date + Duration::days(1))),
+            category: text(&self.category),
+            speaker: text(&self.speaker),
+            tags: text(&self.tag).into_iter().collect(),
+            sort: self.sort,
+        }
+    }
+}
+
+/// Mood, rating, tags and notes of a session, or nothing if none were recorded.
+fn journal_summary(meditation: &MeditationData) -> Html {
+    let mood = match (meditation.mood_before, meditation.mood_after) {
+        (Some(before), Some(after)) => Some(format!("Mood {} → {}", before, after)),
+        (Some(before), None) => Some(format!("Mood before {}", before)),
+        (None, Some(after)) => Some(format!("Mood after {}", after)),
+        (None, None) => None,
+    };
+    let details: Vec<String> = mood
+        .into_iter()
+        .chain(
+            meditation
+                .rating
+                .map(|rating| format!("Rated {}/5", rating)),
+        )
+        .collect();
+
+    html! {
+        <>
+            if !details.is_empty() || !meditation.tags.is_empty() {
+                <div class="flex flex-wrap items-center gap-1 text-xs text-gray-500">
+                    {details.join(" · ")}
+                    {
+                        meditation.tags.iter().map(|tag| html! {
+                            <span class="px-1 rounded bg-gray-100">{tag.clone()}</span>
+                        }).collect::<Html>()
+                    }
+                </div>
+            }
+            if let Some(notes) = &meditation.notes {
+                <div class="text-xs italic text-gray-500 truncate" title={notes.clone()}>{notes.clone()}</div>
+            }
+        </>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct MeditationHistoryProps {}
+
+#[function_component]
+pub fn MeditationHistory(props: &MeditationHistoryProps) -> Html {
+    let MeditationHistoryProps {} = props;
+
+    let log = use_state(read_meditation_data);
+    let filters = use_state(HistoryFilters::default);
+    let page = use_state(|| 0usize);
+    let confirm_delete = use_state(|| None::<String>);
+    let aggregator = use_stats_aggregator();
+    let reload_meditations = use_reload_meditations();
+
+    let on_filter = |set: fn(&mut HistoryFilters, String)| {
+        let filters = filters.clone();
+        let page = page.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*filters).clone();
+            set(&mut next, input.value());
+            filters.set(next);
+            page.set(0);
+        })
+    };
+
+    let on_clear = {
+        let filters = filters.clone();
+        let page = page.clone();
+        Callback::from(move |_: MouseEvent| {
+            filters.set(HistoryFilters::default());
+            page.set(0);
+        })
+    };
+
+    let on_delete = |id: String| {
+        let log = log.clone();
+        let confirm_delete = confirm_delete.clone();
+        let reload_meditations = reload_meditations.clone();
+        Callback::from(move |_: MouseEvent| {
+            delete_meditation(&id);
+            confirm_delete.set(None);
+            log.set(read_meditation_data());
+            reload_meditations.emit(());
+        })
+    };
+
+    let set_confirm = |id: Option<String>| {
+        let confirm_delete = confirm_delete.clone();
+        Callback::from(move |_: MouseEvent| confirm_delete.set(id.clone()))
+    };
+
+    // Exports what the filters currently show, in the chosen order.
+    let on_export = {
+        let log = log.clone();
+        let filters = filters.clone();
+        Callback::from(move |_: MouseEvent| {
+            let sessions: Vec<_> = filters
+                .to_query()
+                .run(&log)
+                .into_iter()
+                .map(|(_, meditation)| meditation.clone())
+                .collect();
+            if let Err(err) =
+                FileDownload::save_text("meditations.csv", "text/csv", &to_csv(&sessions))
+            {
+                web_sys::console::log_1(&JsValue::from_str(&format!("Export failed: {:?}", err)));
+            }
+        })
+    };
+
+    let go_to = |target: usize| {
+        let page = page.clone();
+        Callback::from(move |_: MouseEvent| page.set(target))
+    };
+
+    if log.is_empty() {
+        return html! {
+            <Card>
+                <div class="flex flex-col items-center gap-2 text-sm text-gray-600 w-72">
+                    <div>{"No sessions logged yet."}</div>
+                    <div class="flex gap-4">
+                        <Link<Route> to={Route::Timer} classes="text-emerald-700 underline">{"Start a meditation"}</Link<Route>>
+                        <Link<Route> to={Route::NewSession} classes="text-emerald-700 underline">{"Add a session"}</Link<Route>>
+                    </div>
+                </div>
+            </Card>
+        };
+    }
+
+    let results = filters.to_query().page(&log, *page, SESSIONS_PER_PAGE);
+    let select = |id: &'static str,
+                  value: &str,
+                  options: Vec<String>,
+                  onchange: Callback<Event>| {
+        html! {
+            <select id={id} class="px-2 border rounded" {onchange}>
+                <option value="" selected={value.is_empty()}>{"Any"}</option>
+                {
+                    options.into_iter().map(|option| html! {
+                        <option value={option.clone()} selected={option == value}>{option.clone()}</option>
+                    }).collect::<Html>()
+                }
+            </select>
+        }
+    };
+    let button = "px-3 py-1 rounded-lg font-semibold bg-gray-200 disabled:opacity-50";
+
+    html! {
+        <Card>
+            <div class="flex flex-col gap-3 w-72">
+                <div class="grid w-full grid-cols-2 gap-2 text-sm">
+                    <label for="history-from">{"From"}</label>
+                    <input id="history-from" class="px-2 border rounded" type="date" value={filters.from_date.clone()}
+                        onchange={on_filter(|filters, value| filters.from_date = value)} />
+                    <label for="history-to">{"To"}</label>
+                    <input id="history-to" class="px-2 border rounded" type="date" value={filters.to_date.clone()}
+                        onchange={on_filter(|filters, value| filters.to_date = value)} />
+                    <label for="history-category">{"Category"}</label>
+                    {select("history-category", &filters.category, aggregator.categories_by_use(),
+                        on_filter(|filters, value| filters.category = value))}
+                    <label for="history-speaker">{"Speaker"}</label>
+                    {select("history-speaker", &filters.speaker, aggregator.speakers_by_use(),
+                        on_filter(|filters, value| filters.speaker = value))}
+                    <label for="history-tag">{"Tag"}</label>
+                    {select("history-tag", &filters.tag, aggregator.tags_by_use(),
+                        on_filter(|filters, value| filters.tag = value))}
+                    <label for="history-sort">{"Sort"}</label>
+                    <select id="history-sort" class="px-2 border rounded"
+                        onchange={on_filter(|filters, value| {
+                            filters.sort = value.parse::<usize>().ok()
+                                .and_then(|index| SortOrder::ALL.get(index).copied())
+                                .unwrap_or_default()
+                        })}>
+                        {
+                            SortOrder::ALL.iter().enumerate().map(|(index, sort)| html! {
+                                <option value={index.to_string()} selected={*sort == filters.sort}>{sort.label()}</option>
+                            }).collect::<Html>()
+                        }
+                    </select>
+                </div>
+
+                <div class="flex justify-between text-xs text-gray-500">
+                    <span>{format!("{} sessions", results.total)}</span>
+                    <div class="flex gap-3">
+                        if *filters != HistoryFilters::default() {
+                            <button class="underline" onclick={on_clear.clone()}>{"Clear filters"}</button>
+                        }
+                        if results.total > 0 {
+                            <button class="underline" onclick={on_export}>{"Export CSV"}</button>
+                        }
+                    </div>
+                </div>
+
+                if results.items.is_empty() {
+                    <div class="flex flex-col items-center gap-1 py-4 text-sm text-gray-600">
+                        <div>{"No sessions match these filters."}</div>
+                        <button class="underline" onclick={on_clear}>{"Clear filters"}</button>
+                    </div>
+                }
+                <ul class="flex flex-col divide-y">
+                    {
+                        results.items.iter().map(|(index, meditation)| {
+                            let start = Local
+                                .timestamp_opt(meditation.datetime, 0)
+                                .single()
+                                .map(|datetime| format_date_time(datetime.naive_local()))
+                                .unwrap_or_default();
+                            html! {
+                                <li key={*index} class="flex items-center justify-between gap-2 py-2 text-sm">
+                                    <div class="min-w-0">
+                                        <div class="font-semibold">{start}</div>
+                                        <div class="text-gray-600 truncate">
+                                            {format_duration(meditation.duration as i64)}{" · "}
+                                            {meditation.category.clone()}{" · "}{meditation.speaker.clone()}
+                                        </div>
+                                        {journal_summary(meditation)}
+                                    </div>
+                                    <div class="flex gap-2 text-xs shrink-0">
+                                        // Sessions are found by id, which they get once tracked for sync.
+                                        if meditation.id.is_empty() {
+                                            <></>
+                                        } else if confirm_delete.as_ref() == Some(&meditation.id) {
+                                            <button class="text-red-600 font-semibold" onclick={on_delete(meditation.id.clone())}>{"Delete"}</button>
+                                            <button class="text-gray-600" onclick={set_confirm(None)}>{"Keep"}</button>
+                                        } else {
+                                            <Link<Route> to={Route::EditSession { id: meditation.id.clone() }} classes="text-emerald-700">{"Edit"}</Link<Route>>
+                                            <button class="text-gray-600" onclick={set_confirm(Some(meditation.id.clone()))}>{"Delete"}</button>
+                                        }
+                                    </div>
+                                </li>
+                            }
+                        }).collect::<Html>()
+                    }
+                </ul>
+
+                if results.page_count > 1 {
+                    <div class="flex items-center justify-between text-sm">
+                        <button class={button} disabled={results.page == 0}
+                            onclick={go_to(results.page.saturating_sub(1))}>{"Previous"}</button>
+                        <span>{format!("Page {} of {}", results.page + 1, results.page_count)}</span>
+                        <button class={button} disabled={results.page + 1 >= results.page_count}
+                            onclick={go_to(results.page + 1)}>{"Next"}</button>
+                    </div>
+                }
+            </div>
+        </Card>
+    }
+}
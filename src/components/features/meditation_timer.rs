@@ -0,0 +1,332 @@
+use chrono::Utc;
+use gloo_timers::callback::Interval;
+use web_sys::{wasm_bindgen::JsValue, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    services::bell_sound::BellSound,
+    utils::{
+        database::meditation_log::{
+            meditation_data_builder::MeditationDataError,
+            read_write_meditation_data::save_meditation, session_plan::SessionPlan,
+        },
+        hooks::{use_meditation_state::use_record_meditation, use_settings::use_settings},
+        library::guided_meditations::GuidedMeditation,
+        shared::format_clock,
+        timer::{
+            read_write_timer_state::{read_timer_state, save_timer_state},
+            timer_state::{TimerState, TimerStatus},
+        },
+    },
+};
+
+const TICK_MILLIS: u32 = 250;
+
+fn now_ms() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+fn ring(strikes: u32) {
+    if BellSound::ring(strikes).is_err() {
+        web_sys::console::log_1(&JsValue::from_str(
+            "Web Audio is not available, skipping bell",
+        ));
+    }
+}
+
+/// Sets the sitting length, category and speaker of an idle timer from a library meditation,
+/// so the session is logged against it.
+fn prefill(timer: &mut TimerState, meditation: &GuidedMeditation) {
+    if timer.status() == TimerStatus::Idle {
+        timer.plan.main_seconds = meditation.length_seconds;
+        timer.category = meditation.category.to_string();
+        timer.speaker = meditation.speaker.to_string();
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct MeditationTimerProps {
+    /// A library meditation to pre-fill the timer with.
+    #[prop_or_default]
+    pub guided: Option<&'static GuidedMeditation>,
+}
+
+#[function_component]
+pub fn MeditationTimer(props: &MeditationTimerProps) -> Html {
+    let MeditationTimerProps { guided } = props;
+
+    // A session that was running before a reload picks up where it left off.
+    let settings = use_settings().settings;
+    let timer = {
+        let guided = *guided;
+        use_state(move || {
+            let mut timer = read_timer_state().unwrap_or_else(|| {
+                TimerState::new(
+                    SessionPlan::simple(settings.default_session_minutes as i64 * 60),
+                    &settings.default_category,
+                    "",
+                )
+            });
+            if let Some(meditation) = guided {
+                prefill(&mut timer, meditation);
+            }
+            timer
+        })
+    };
+    let now = use_state(now_ms);
+    // Elapsed time at the last bell check; starts at the current position so a restored
+    // session doesn't replay the bells it already rang.
+    let bells_checked_ms = use_mut_ref(|| timer.elapsed_ms(now_ms()));
+    let message = use_state(|| None::<String>);
+    let record_meditation = use_record_meditation();
+
+    // Re-render a few times a second while running; the displayed time is always
+    // recomputed from timestamps, so missed ticks never cause drift.
+    {
+        let now = now.clone();
+        use_effect_with(timer.status(), move |status| {
+            let interval = (*status == TimerStatus::Running)
+                .then(|| Interval::new(TICK_MILLIS, move || now.set(now_ms())));
+            move || drop(interval)
+        });
+    }
+
+    // Every change is written through straight away, so a reload never loses a session.
+    let set_timer = {
+        let timer = timer.clone();
+        Callback::from(move |next: TimerState| {
+            save_timer_state(&next);
+            timer.set(next);
+        })
+    };
+
+    let update = {
+        let timer = timer.clone();
+        let set_timer = set_timer.clone();
+        let now = now.clone();
+        move |change: fn(&mut TimerState, i64)| {
+            let timer = timer.clone();
+            let now = now.clone();
+            let set_timer = set_timer.clone();
+            Callback::from(move |_: MouseEvent| {
+                let mut next = (*timer).clone();
+                let at = now_ms();
+                change(&mut next, at);
+                now.set(at);
+                set_timer.emit(next);
+            })
+        }
+    };
+
+    let finish = {
+        let timer = timer.clone();
+        let now = now.clone();
+        let message = message.clone();
+        let set_timer = set_timer.clone();
+        Callback::from(move |_: ()| {
+            let mut next = (*timer).clone();
+            let at = now_ms();
+            // A session that can't be logged pauses the timer, so it can be corrected and
+            // finished again. It is saved with the datetime the timer gave it.
+            match next.finish_with(at, |session| save_meditation(session)) {
+                Some(Ok((seconds, meditation))) => {
+                    record_meditation.emit(meditation);
+                    message.set(Some(format!(
+                        "Logged {} of meditation",
+                        format_clock(seconds)
+                    )));
+                }
+                // No field can fix a session that is too short: it can only be resumed or discarded.
+                Some(Err(MeditationDataError::InvalidDuration)) => message.set(Some(
+                    "Session not logged: it is shorter than a second. Resume it, or press \
+                     Discard to reset the timer."
+                        .to_string(),
+                )),
+                Some(Err(error)) => message.set(Some(format!(
+                    "Session not logged yet: {}. Correct it and press Finish again.",
+                    error
+                ))),
+                None => {}
+            }
+            now.set(at);
+            set_timer.emit(next);
+        })
+    };
+
+    {
+        let finish = finish.clone();
+        let timer = timer.clone();
+        let bells_checked_ms = bells_checked_ms.clone();
+        use_effect_with(*now, move |now| {
+            if timer.status() == TimerStatus::Running {
+                let elapsed = timer.elapsed_ms(*now);
+                let previous = bells_checked_ms.replace(elapsed);
+                for bell in timer.plan.bells_between(previous, elapsed) {
+                    ring(bell.kind.strikes());
+                }
+            }
+            if timer.is_complete(*now) {
+                finish.emit(());
+            }
+        });
+    }
+
+    let on_start = {
+        let start = update(|timer, now| timer.start(now));
+        let bells_checked_ms = bells_checked_ms.clone();
+        Callback::from(move |e: MouseEvent| {
+            // Audio may only be started from a click, so unlock it for the bells to come.
+            if BellSound::unlock().is_err() {
+                web_sys::console::log_1(&JsValue::from_str(
+                    "Web Audio is not available, bells are disabled",
+                ));
+            }
+            // Just before zero, so a bell at the very start of the plan rings on the first tick.
+            *bells_checked_ms.borrow_mut() = -1;
+            start.emit(e);
+        })
+    };
+
+    let on_minutes = |set: fn(&mut SessionPlan, i64), min: i64| {
+        let timer = timer.clone();
+        let set_timer = set_timer.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(minutes) = input.value().parse::<i64>() {
+                let mut next = (*timer).clone();
+                set(&mut next.plan, minutes.clamp(min, 180) * 60);
+                set_timer.emit(next);
+            }
+        })
+    };
+
+    let on_text = |set: fn(&mut TimerState, String)| {
+        let timer = timer.clone();
+        let set_timer = set_timer.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*timer).clone();
+            set(&mut next, input.value());
+            set_timer.emit(next);
+        })
+    };
+
+    let on_reset = {
+        let message = message.clone();
+        let reset = update(|timer, _| timer.reset());
+        Callback::from(move |e: MouseEvent| {
+            message.set(None);
+            reset.emit(e);
+        })
+    };
+
+    let status = timer.status();
+    let editable = status == TimerStatus::Idle;
+    // The category and speaker can still be corrected until the session is logged.
+    let fields_editable = status != TimerStatus::Finished;
+    let field_errors = timer.field_errors();
+    let button = "px-4 py-2 rounded-lg font-semibold";
+    let phase = timer.plan.phase_at(timer.elapsed_ms(*now) / 1000);
+    let plan_input = |id: &'static str,
+                      label: &'static str,
+                      seconds: i64,
+                      min: i64,
+                      onchange: Callback<Event>| {
+        html! {
+            <>
+                <label for={id}>{label}</label>
+                <input id={id} class="px-2 border rounded" type="number" min={min.to_string()} max="180"
+                    value={(seconds / 60).to_string()} disabled={!editable} {onchange} />
+            </>
+        }
+    };
+
+    html! {
+        <Card>
+            <div class="flex flex-col items-center gap-3 w-72">
+                if let Some(meditation) = guided {
+                    <div class="text-sm text-center text-gray-600">{meditation.description}</div>
+                    if let Some(audio_url) = meditation.audio_url {
+                        <audio class="w-full" controls={true} preload="none" src={audio_url} />
+                    }
+                }
+                <div class="text-5xl font-semibold tabular-nums">
+                    {format_clock(timer.remaining_ms(*now) / 1000)}
+                </div>
+                <div class="text-sm text-gray-500">
+                    {"Elapsed "}{format_clock(timer.elapsed_ms(*now) / 1000)}
+                </div>
+                {
+                    match (status, phase) {
+                        (TimerStatus::Running | TimerStatus::Paused, Some(phase)) => html! {
+                            <div class="text-sm font-semibold">
+                                {phase.kind.label()}{" · "}{format_clock(phase.remaining_seconds)}{" left"}
+                            </div>
+                        },
+                        _ => html! {
+                            <div class="text-sm text-gray-500">{timer.plan.summary()}</div>
+                        },
+                    }
+                }
+
+                <div class="grid w-full grid-cols-2 gap-2 text-sm">
+                    {plan_input("timer-preparation", "Preparation (min)", timer.plan.preparation_seconds, 0,
+                        on_minutes(|plan, seconds| plan.preparation_seconds = seconds, 0))}
+                    {plan_input("timer-length", "Sitting (min)", timer.plan.main_seconds, 1,
+                        on_minutes(|plan, seconds| plan.main_seconds = seconds, 1))}
+                    {plan_input("timer-interval", "Bell every (min, 0 = off)", timer.plan.interval_bell_seconds, 0,
+                        on_minutes(|plan, seconds| plan.interval_bell_seconds = seconds, 0))}
+                    {plan_input("timer-closing", "Closing (min)", timer.plan.closing_seconds, 0,
+                        on_minutes(|plan, seconds| plan.closing_seconds = seconds, 0))}
+                    <label for="timer-category">{"Category"}</label>
+                    <input id="timer-category" class="px-2 border rounded" type="text"
+                        value={timer.category.clone()}
+                        disabled={!fields_editable} onchange={on_text(|timer, value| timer.category = value)} />
+                    <label for="timer-speaker">{"Speaker"}</label>
+                    <input id="timer-speaker" class="px-2 border rounded" type="text"
+                        value={timer.speaker.clone()}
+                        disabled={!fields_editable} onchange={on_text(|timer, value| timer.speaker = value)} />
+                </div>
+
+                <div class="flex gap-2">
+                    {
+                        match status {
+                            TimerStatus::Idle => html! {
+                                <button class={classes!(button, "bg-emerald-600", "text-white")}
+                                    disabled={!field_errors.is_empty()} onclick={on_start.clone()}>{"Start"}</button>
+                            },
+                            TimerStatus::Running => html! {
+                                <button class={classes!(button, "bg-gray-200")}
+                                    onclick={update(|timer, now| timer.pause(now))}>{"Pause"}</button>
+                            },
+                            TimerStatus::Paused => html! {
+                                <button class={classes!(button, "bg-emerald-600", "text-white")}
+                                    onclick={update(|timer, now| timer.resume(now))}>{"Resume"}</button>
+                            },
+                            TimerStatus::Finished => html! {
+                                <button class={classes!(button, "bg-gray-200")}
+                                    onclick={on_reset.clone()}>{"New Session"}</button>
+                            },
+                        }
+                    }
+                    if matches!(status, TimerStatus::Running | TimerStatus::Paused) {
+                        <button class={classes!(button, "bg-indigo-600", "text-white")}
+                            onclick={finish.reform(|_: MouseEvent| ())}>{"Finish"}</button>
+                        <button class={classes!(button, "bg-gray-200")}
+                            onclick={on_reset}>{"Discard"}</button>
+                    }
+                </div>
+
+                if fields_editable {
+                    { field_errors.iter().map(|error| html! {
+                        <div class="text-sm text-red-600">{format!("The {} can't be empty", error.field())}</div>
+                    }).collect::<Html>() }
+                }
+                if let Some(message) = (*message).clone() {
+                    <div class="text-sm text-gray-600">{message}</div>
+                }
+            </div>
+        </Card>
+    }
+}
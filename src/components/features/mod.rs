@@ -1 +1,20 @@
+pub mod backup_panel;
+pub mod breathing_exercise;
+pub mod encryption_panel;
+pub mod guided_library;
+pub mod meditation_form;
+pub mod meditation_history;
+pub mod meditation_timer;
+pub mod mood_impact;
+pub mod practice_heatmap;
+pub mod profile_manager;
+pub mod profile_switcher;
+pub mod settings_form;
+pub mod stats_charts;
+pub mod storage_panel;
+pub mod sync_agent;
+pub mod sync_panel;
+pub mod tag_manager;
+pub mod tag_stats;
+pub mod unlock_gate;
 pub mod user_stats;
@@ -0,0 +1,81 @@
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        database::meditation_log::mood_analysis::MoodGroup,
+        hooks::use_meditation_state::use_meditation_data,
+    },
+};
+
+/// Groups shown per breakdown; the rest are usually too small to say much.
+const MAX_GROUPS: usize = 5;
+
+fn delta_class(delta: f32) -> &'static str {
+    if delta > 0.0 {
+        "text-emerald-700"
+    } else if delta < 0.0 {
+        "text-rose-700"
+    } else {
+        "text-gray-500"
+    }
+}
+
+fn breakdown(title: &str, groups: &[MoodGroup]) -> Html {
+    if groups.is_empty() {
+        return html! {};
+    }
+    html! {
+        <div class="px-4 py-1 text-sm border-t">
+            <div class="font-semibold">{title}</div>
+            {
+                groups.iter().take(MAX_GROUPS).map(|group| html! {
+                    <div class="flex justify-between gap-2">
+                        <span class="truncate">{group.label.clone()}</span>
+                        <span class="shrink-0">
+                            <span class={delta_class(group.average_delta)}>{format!("{:+.1}", group.average_delta)}</span>
+                            <span class="text-xs text-gray-500">{format!(" ({})", group.sessions)}</span>
+                        </span>
+                    </div>
+                }).collect::<Html>()
+            }
+        </div>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct MoodImpactProps {}
+
+/// Shows how much sessions lift the mood, and which practices help most.
+#[function_component]
+pub fn MoodImpact(props: &MoodImpactProps) -> Html {
+    let MoodImpactProps {} = props;
+
+    let mood = use_meditation_data().mood;
+
+    html! {
+        <Card>
+            <div class="w-80">
+                <div class="pb-1 text-lg font-semibold border-b">{"Mood Impact"}</div>
+                {
+                    match mood.overall_delta {
+                        Some(delta) => html! {
+                            <div class="px-4 py-1 text-sm">
+                                <span class={classes!("text-2xl", "font-semibold", delta_class(delta))}>{format!("{:+.1}", delta)}</span>
+                                {format!(" mood points on average over {} sessions", mood.sessions)}
+                            </div>
+                        },
+                        None => html! {
+                            <div class="px-4 py-1 text-sm text-gray-500">
+                                {"Record your mood before and after sessions to see which practices help you most."}
+                            </div>
+                        },
+                    }
+                }
+                {breakdown("By Category", &mood.by_category)}
+                {breakdown("By Speaker", &mood.by_speaker)}
+                {breakdown("By Length (min)", &mood.by_duration)}
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,92 @@
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        database::meditation_log::practice_calendar::{build_practice_calendar, CalendarDay},
+        hooks::use_meditation_state::use_stats_aggregator,
+    },
+};
+
+/// Tailwind classes for each intensity bucket, spelled out so the JIT compiler picks them up.
+fn intensity_class(intensity: u8) -> &'static str {
+    match intensity {
+        0 => "bg-gray-100",
+        1 => "bg-emerald-200",
+        2 => "bg-emerald-400",
+        3 => "bg-emerald-600",
+        _ => "bg-emerald-800",
+    }
+}
+
+fn day_cell(day: &Option<CalendarDay>) -> Html {
+    match day {
+        Some(day) => {
+            let tooltip = format!(
+                "{} min on {}",
+                day.minutes.round() as i64,
+                day.date.format("%a %-d %b %Y")
+            );
+            html! {
+                <div class={classes!("w-2", "h-2", "rounded-sm", intensity_class(day.intensity))} title={tooltip}></div>
+            }
+        }
+        None => html! { <div class="w-2 h-2"></div> },
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct PracticeHeatmapProps {}
+
+#[function_component]
+pub fn PracticeHeatmap(props: &PracticeHeatmapProps) -> Html {
+    let PracticeHeatmapProps {} = props;
+
+    let aggregator = use_stats_aggregator();
+    let week_start = aggregator.calendar().week_start;
+    let weeks = build_practice_calendar(aggregator.daily_minutes(), aggregator.today(), week_start);
+    // Every other day is labelled, from the first day of the week.
+    let weekday_labels = std::iter::successors(Some(week_start.weekday()), |day| Some(day.succ()))
+        .take(7)
+        .enumerate()
+        .map(|(row, day)| {
+            if row % 2 == 0 {
+                day.to_string()
+            } else {
+                String::new()
+            }
+        });
+
+    html! {
+        <Card>
+            <div class="pb-1 text-lg font-semibold border-b">{"Practice History"}</div>
+            <div class="flex gap-1 pt-2 overflow-x-auto text-[8px] text-gray-500">
+                <div class="flex flex-col gap-px">
+                    <div class="h-2"></div>
+                    {
+                        weekday_labels.map(|label| html! {
+                            <div class="h-2 leading-[8px]">{label}</div>
+                        }).collect::<Html>()
+                    }
+                </div>
+                {
+                    weeks.iter().map(|week| html! {
+                        <div class="flex flex-col gap-px">
+                            <div class="h-2 leading-[8px] whitespace-nowrap">
+                                {week.month_label.clone().unwrap_or_default()}
+                            </div>
+                            { week.days.iter().map(day_cell).collect::<Html>() }
+                        </div>
+                    }).collect::<Html>()
+                }
+            </div>
+            <div class="flex items-center justify-end gap-px pt-1 text-[8px] text-gray-500">
+                <span class="pr-1">{"Less"}</span>
+                { (0..=4).map(|intensity| html! {
+                    <div class={classes!("w-2", "h-2", "rounded-sm", intensity_class(intensity))}></div>
+                }).collect::<Html>() }
+                <span class="pl-1">{"More"}</span>
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,208 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        hooks::use_profiles::use_profiles,
+        profile::{
+            profile_list::{AvatarColour, Profile, ProfileError},
+            read_write_profiles::{add_profile, edit_profile, remove_profile},
+        },
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct ProfileManagerProps {}
+
+/// A profile being renamed or recoloured.
+#[derive(Clone, PartialEq)]
+struct Draft {
+    id: String,
+    name: String,
+    colour: AvatarColour,
+}
+
+fn bind(field: &UseStateHandle<String>) -> Callback<InputEvent> {
+    let field = field.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        field.set(input.value());
+    })
+}
+
+/// Adds, renames, recolours and removes the profiles on this device.
+#[function_component]
+pub fn ProfileManager(props: &ProfileManagerProps) -> Html {
+    let ProfileManagerProps {} = props;
+    let context = use_profiles();
+
+    let new_name = use_state(String::new);
+    let draft = use_state(|| None::<Draft>);
+    let draft_name = use_state(String::new);
+    let confirm_remove = use_state(|| None::<String>);
+    let message = use_state(|| None::<String>);
+
+    // Shows the outcome of a change and re-reads the profiles if it succeeded.
+    let finish = {
+        let refresh = context.refresh.clone();
+        let message = message.clone();
+        move |result: Result<(), ProfileError>| match result {
+            Ok(()) => {
+                message.set(None);
+                refresh.emit(());
+            }
+            Err(error) => message.set(Some(format!("Nothing was changed: {}.", error))),
+        }
+    };
+
+    let on_add = {
+        let finish = finish.clone();
+        let new_name = new_name.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let result = add_profile(&new_name).map(|_| ());
+            if result.is_ok() {
+                new_name.set(String::new());
+            }
+            finish(result);
+        })
+    };
+
+    let on_save = {
+        let finish = finish.clone();
+        let draft = draft.clone();
+        let draft_name = draft_name.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let Some(editing) = (*draft).clone() else {
+                return;
+            };
+            let result = edit_profile(&editing.id, &draft_name, editing.colour);
+            if result.is_ok() {
+                draft.set(None);
+            }
+            finish(result);
+        })
+    };
+
+    let start_edit = |profile: &Profile| {
+        let draft = draft.clone();
+        let draft_name = draft_name.clone();
+        let confirm_remove = confirm_remove.clone();
+        let editing = Draft {
+            id: profile.id.clone(),
+            name: profile.name.clone(),
+            colour: profile.colour,
+        };
+        Callback::from(move |_: MouseEvent| {
+            draft_name.set(editing.name.clone());
+            draft.set(Some(editing.clone()));
+            confirm_remove.set(None);
+        })
+    };
+
+    let pick_colour = |colour: AvatarColour| {
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(editing) = (*draft).clone() {
+                draft.set(Some(Draft { colour, ..editing }));
+            }
+        })
+    };
+
+    let set_confirm_remove = |id: Option<String>| {
+        let confirm_remove = confirm_remove.clone();
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            draft.set(None);
+            confirm_remove.set(id.clone());
+        })
+    };
+
+    let on_remove = |id: String| {
+        let finish = finish.clone();
+        let confirm_remove = confirm_remove.clone();
+        Callback::from(move |_: MouseEvent| {
+            confirm_remove.set(None);
+            finish(remove_profile(&id));
+        })
+    };
+
+    let avatar = |colour: AvatarColour, initial: char| {
+        html! {
+            <span class={classes!("flex", "items-center", "justify-center", "w-8", "h-8", "rounded-full",
+                "text-white", "font-semibold", colour.class())}>{initial}</span>
+        }
+    };
+
+    let button = "px-3 py-1 rounded-lg";
+    let active = context.profiles.active().id.clone();
+    let rows = context
+        .profiles
+        .profiles()
+        .iter()
+        .map(|profile| match (*draft).clone() {
+            Some(editing) if editing.id == profile.id => html! {
+                <li>
+                    <form class="flex flex-col gap-2 p-2 border rounded-lg" onsubmit={on_save.clone()}>
+                        <label for="profile-name">{"Name"}</label>
+                        <input id="profile-name" class="px-2 border rounded" value={(*draft_name).clone()}
+                            oninput={bind(&draft_name)} />
+                        <div class="flex gap-2">
+                            { AvatarColour::ALL.into_iter().map(|colour| html! {
+                                <button type="button" onclick={pick_colour(colour)}
+                                    class={classes!("w-6", "h-6", "rounded-full", colour.class(),
+                                        (colour == editing.colour).then_some("ring-2 ring-offset-2 ring-gray-700"))} />
+                            }).collect::<Html>() }
+                        </div>
+                        <div class="flex gap-2">
+                            <button type="submit" class={classes!(button, "bg-emerald-600", "text-white")}>{"Save"}</button>
+                            <button type="button" class={classes!(button, "bg-gray-200")}
+                                onclick={{ let draft = draft.clone(); Callback::from(move |_: MouseEvent| draft.set(None)) }}>{"Cancel"}</button>
+                        </div>
+                    </form>
+                </li>
+            },
+            _ => html! {
+                <li class="flex flex-col gap-2 p-2 border rounded-lg">
+                    <div class="flex items-center gap-2">
+                        {avatar(profile.colour, profile.initial())}
+                        <span class="font-semibold">{profile.name.clone()}</span>
+                        if profile.id == active {
+                            <span class="text-xs text-gray-500">{"In use"}</span>
+                        }
+                        <button class={classes!(button, "ml-auto", "bg-gray-200")} onclick={start_edit(profile)}>{"Edit"}</button>
+                        <button class={classes!(button, "bg-gray-200")} onclick={set_confirm_remove(Some(profile.id.clone()))}>{"Delete"}</button>
+                    </div>
+                    if confirm_remove.as_deref() == Some(profile.id.as_str()) {
+                        <div class="flex flex-col gap-2 p-2 border border-red-300 rounded">
+                            <div>{format!("Delete {} and all their sessions from this device?", profile.name)}</div>
+                            <div class="flex gap-2">
+                                <button class={classes!(button, "bg-red-600", "text-white")} onclick={on_remove(profile.id.clone())}>{"Delete"}</button>
+                                <button class={classes!(button, "bg-gray-200")} onclick={set_confirm_remove(None)}>{"Cancel"}</button>
+                            </div>
+                        </div>
+                    }
+                </li>
+            },
+        })
+        .collect::<Html>();
+
+    html! {
+        <Card>
+            <div class="flex flex-col gap-3 w-80 text-sm">
+                <div>{"Everyone who uses this device can have their own profile, with their own sessions and stats."}</div>
+                <ul class="flex flex-col gap-2">{rows}</ul>
+                <form class="flex gap-2" onsubmit={on_add}>
+                    <input class="flex-1 px-2 border rounded" placeholder="Name" aria-label="New profile name"
+                        value={(*new_name).clone()} oninput={bind(&new_name)} />
+                    <button type="submit" class={classes!(button, "bg-emerald-600", "text-white")}>{"Add Profile"}</button>
+                </form>
+                if let Some(message) = (*message).clone() {
+                    <div class="text-gray-600">{message}</div>
+                }
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,59 @@
+use web_sys::wasm_bindgen::JsValue;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{
+    routes::Route,
+    utils::{hooks::use_profiles::ProfilesContext, profile::read_write_profiles::switch_profile},
+};
+
+#[derive(PartialEq, Properties)]
+pub struct ProfileSwitcherProps {}
+
+/// Shows an avatar for every profile; tapping one switches to it.
+///
+/// Renders nothing outside a `ProfilesProvider`, e.g. while the stored data is locked.
+#[function_component]
+pub fn ProfileSwitcher(props: &ProfileSwitcherProps) -> Html {
+    let ProfileSwitcherProps {} = props;
+    let Some(context) = use_context::<ProfilesContext>() else {
+        return html! {};
+    };
+    let active = context.profiles.active().id.clone();
+
+    let avatars = context
+        .profiles
+        .profiles()
+        .iter()
+        .map(|profile| {
+            let selected = profile.id == active;
+            let onclick = {
+                let id = profile.id.clone();
+                let refresh = context.refresh.clone();
+                Callback::from(move |_: MouseEvent| match switch_profile(&id) {
+                    Ok(()) => refresh.emit(()),
+                    Err(error) => web_sys::console::log_1(&JsValue::from_str(&format!(
+                        "Profile not switched: {}",
+                        error
+                    ))),
+                })
+            };
+            html! {
+                <button title={profile.name.clone()} disabled={selected} {onclick}
+                    class={classes!("w-8", "h-8", "rounded-full", "text-white", "font-semibold",
+                        profile.colour.class(), if selected { "ring-2 ring-offset-2 ring-gray-700" } else { "opacity-60" })}>
+                    {profile.initial()}
+                </button>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="flex items-center gap-2 p-2 border-b">
+            {avatars}
+            <Link<Route> to={Route::Profiles} classes="ml-auto text-sm text-emerald-700 underline">
+                {context.profiles.active().name.clone()}
+            </Link<Route>>
+        </div>
+    }
+}
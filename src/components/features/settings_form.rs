@@ -0,0 +1,208 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        database::meditation_log::{catalog::CatalogKind, read_write_catalog::read_catalog},
+        hooks::use_settings::use_settings,
+        settings::{
+            app_settings::{
+                DurationUnits, ReminderTime, Settings, Theme, TimeZoneSetting, WeekStart,
+                MAX_REMINDERS, SESSION_MINUTES_RANGE,
+            },
+            read_write_settings::save_settings,
+        },
+    },
+};
+
+/// The fixed offsets offered besides the device time zone: whole and half hours.
+const UTC_OFFSET_STEP_MINUTES: i32 = 30;
+
+#[derive(PartialEq, Properties)]
+pub struct SettingsFormProps {}
+
+fn timezone_value(timezone: TimeZoneSetting) -> String {
+    match timezone {
+        TimeZoneSetting::Device => "device".to_string(),
+        TimeZoneSetting::UtcOffset(minutes) => minutes.to_string(),
+    }
+}
+
+/// Edits the active profile's settings; changes apply once saved.
+#[function_component]
+pub fn SettingsForm(props: &SettingsFormProps) -> Html {
+    let SettingsFormProps {} = props;
+    let context = use_settings();
+
+    let draft = use_state(|| context.settings.clone());
+    let reminder = use_state(String::new);
+    let message = use_state(|| None::<String>);
+    let catalog = use_state(read_catalog);
+
+    let on_input = |set: fn(&mut Settings, String)| {
+        let draft = draft.clone();
+        let message = message.clone();
+        Callback::from(move |e: InputEvent| {
+            // Selects have the same `value` as inputs.
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = (*draft).clone();
+            set(&mut next, input.value());
+            draft.set(next);
+            message.set(None);
+        })
+    };
+
+    let on_reminder = {
+        let reminder = reminder.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            reminder.set(input.value());
+        })
+    };
+
+    let on_add_reminder = {
+        let draft = draft.clone();
+        let reminder = reminder.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Ok(time) = ReminderTime::try_from((*reminder).clone()) else {
+                message.set(Some("Choose a time first.".to_string()));
+                return;
+            };
+            let mut next = (*draft).clone();
+            next.reminder_times.push(time);
+            next.normalise();
+            draft.set(next);
+            reminder.set(String::new());
+        })
+    };
+
+    let remove_reminder = |time: ReminderTime| {
+        let draft = draft.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*draft).clone();
+            next.reminder_times.retain(|other| *other != time);
+            draft.set(next);
+        })
+    };
+
+    let on_save = {
+        let draft = draft.clone();
+        let message = message.clone();
+        let changed = context.changed.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            match save_settings(&draft) {
+                Ok(stored) => {
+                    draft.set(stored.clone());
+                    changed.emit(stored);
+                    message.set(Some("Settings saved.".to_string()));
+                }
+                Err(error) => message.set(Some(format!("Nothing was changed: {}.", error))),
+            }
+        })
+    };
+
+    let option = |value: String, label: String, selected: bool| {
+        html! { <option {value} {selected}>{label}</option> }
+    };
+    let offsets = (-12 * 60..=14 * 60)
+        .step_by(UTC_OFFSET_STEP_MINUTES as usize)
+        .map(TimeZoneSetting::UtcOffset);
+
+    let button = "px-3 py-1 rounded-lg";
+    html! {
+        <Card>
+            <form class="flex flex-col gap-2 w-72 text-sm" onsubmit={on_save}>
+                <label for="settings-timezone">{"Time zone"}</label>
+                <select id="settings-timezone" class="px-2 border rounded"
+                    oninput={on_input(|settings, value| {
+                        settings.timezone = value.parse().map_or(TimeZoneSetting::Device, TimeZoneSetting::UtcOffset);
+                    })}>
+                    { [TimeZoneSetting::Device].into_iter().chain(offsets).map(|timezone| {
+                        option(timezone_value(timezone), timezone.to_string(), timezone == draft.timezone)
+                    }).collect::<Html>() }
+                </select>
+
+                <label for="settings-week-start">{"Week starts on"}</label>
+                <select id="settings-week-start" class="px-2 border rounded"
+                    oninput={on_input(|settings, value| {
+                        if let Some(day) = WeekStart::ALL.into_iter().find(|day| day.label() == value) {
+                            settings.week_start = day;
+                        }
+                    })}>
+                    { WeekStart::ALL.into_iter().map(|day| {
+                        option(day.label().to_string(), day.label().to_string(), day == draft.week_start)
+                    }).collect::<Html>() }
+                </select>
+
+                <label for="settings-session-minutes">{"Default session length (minutes)"}</label>
+                <input id="settings-session-minutes" class="px-2 border rounded" type="number"
+                    min={SESSION_MINUTES_RANGE.start().to_string()} max={SESSION_MINUTES_RANGE.end().to_string()}
+                    value={draft.default_session_minutes.to_string()}
+                    oninput={on_input(|settings, value| {
+                        if let Ok(minutes) = value.parse() {
+                            settings.default_session_minutes = minutes;
+                        }
+                    })} />
+
+                <label for="settings-category">{"Default category"}</label>
+                <input id="settings-category" class="px-2 border rounded" type="text" list="settings-categories"
+                    value={draft.default_category.clone()}
+                    oninput={on_input(|settings, value| settings.default_category = value)} />
+                <datalist id="settings-categories">
+                    { catalog.entries(CatalogKind::Category).iter().map(|entry| html! { <option value={entry.name.clone()} /> }).collect::<Html>() }
+                </datalist>
+
+                <label for="settings-units">{"Show time in"}</label>
+                <select id="settings-units" class="px-2 border rounded"
+                    oninput={on_input(|settings, value| {
+                        if let Some(units) = DurationUnits::ALL.into_iter().find(|units| units.label() == value) {
+                            settings.units = units;
+                        }
+                    })}>
+                    { DurationUnits::ALL.into_iter().map(|units| {
+                        option(units.label().to_string(), units.label().to_string(), units == draft.units)
+                    }).collect::<Html>() }
+                </select>
+
+                <label for="settings-theme">{"Theme"}</label>
+                <select id="settings-theme" class="px-2 border rounded"
+                    oninput={on_input(|settings, value| {
+                        if let Some(theme) = Theme::ALL.into_iter().find(|theme| theme.label() == value) {
+                            settings.theme = theme;
+                        }
+                    })}>
+                    { Theme::ALL.into_iter().map(|theme| {
+                        option(theme.label().to_string(), theme.label().to_string(), theme == draft.theme)
+                    }).collect::<Html>() }
+                </select>
+
+                <div>{"Reminders"}</div>
+                <div class="text-xs text-gray-500">{"Shown while the app is open."}</div>
+                <ul class="flex flex-wrap gap-2">
+                    { draft.reminder_times.iter().map(|time| html! {
+                        <li class="flex items-center gap-1 px-2 py-1 rounded-full bg-gray-200">
+                            {time.to_string()}
+                            <button type="button" aria-label={format!("Remove {}", time)}
+                                onclick={remove_reminder(*time)}>{"×"}</button>
+                        </li>
+                    }).collect::<Html>() }
+                </ul>
+                if draft.reminder_times.len() < MAX_REMINDERS {
+                    <div class="flex gap-2">
+                        <input class="flex-1 px-2 border rounded" type="time" aria-label="Reminder time"
+                            value={(*reminder).clone()} oninput={on_reminder} />
+                        <button type="button" class={classes!(button, "bg-gray-200")} onclick={on_add_reminder}>{"Add"}</button>
+                    </div>
+                }
+
+                <button type="submit" class={classes!(button, "bg-emerald-600", "text-white")}>{"Save"}</button>
+                if let Some(message) = (*message).clone() {
+                    <div class="text-gray-600">{message}</div>
+                }
+            </form>
+        </Card>
+    }
+}
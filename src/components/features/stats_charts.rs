@@ -0,0 +1,106 @@
+use yew::prelude::*;
+
+use crate::{
+    components::ui::{
+        card::Card,
+        charts::{
+            bar_chart::BarChart, donut_chart::DonutChart, line_chart::LineChart, SeriesPoint,
+            PALETTE,
+        },
+    },
+    utils::{
+        database::meditation_log::{
+            catalog::CatalogKind,
+            read_write_catalog::read_catalog,
+            stats_aggregator::DURATION_BUCKET_LABELS,
+            trend_analysis::{minutes_per_day, minutes_per_week},
+        },
+        hooks::use_meditation_state::use_stats_aggregator,
+    },
+};
+
+#[derive(PartialEq, Properties)]
+struct ChartCardProps {
+    title: AttrValue,
+    children: Html,
+}
+
+#[function_component]
+fn ChartCard(props: &ChartCardProps) -> Html {
+    let ChartCardProps { title, children } = props;
+    html! {
+        <Card>
+            <div class="w-80">
+                <div class="pb-1 mb-2 text-lg font-semibold border-b">{title}</div>
+                {children.clone()}
+            </div>
+        </Card>
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct StatsChartsProps {}
+
+#[function_component]
+pub fn StatsCharts(props: &StatsChartsProps) -> Html {
+    let StatsChartsProps {} = props;
+
+    let aggregator = use_stats_aggregator();
+    let today = aggregator.today();
+
+    let per_day: Vec<SeriesPoint> = minutes_per_day(aggregator.daily_minutes(), today, 14)
+        .into_iter()
+        .map(|(date, minutes)| SeriesPoint::new(date.format("%-d %b").to_string(), minutes))
+        .collect();
+
+    let per_week: Vec<SeriesPoint> = minutes_per_week(
+        aggregator.daily_minutes(),
+        today,
+        12,
+        aggregator.calendar().week_start,
+    )
+    .into_iter()
+    .map(|(date, minutes)| SeriesPoint::new(date.format("%-d %b").to_string(), minutes))
+    .collect();
+
+    let mut categories: Vec<(&String, &u32)> = aggregator.category_counts().iter().collect();
+    categories.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let catalog = use_state(read_catalog);
+    let category_colours: Vec<String> = categories
+        .iter()
+        .enumerate()
+        .map(|(i, (category, _))| {
+            catalog
+                .colour(CatalogKind::Category, category)
+                .map(str::to_string)
+                .unwrap_or_else(|| PALETTE[i % PALETTE.len()].to_string())
+        })
+        .collect();
+    let category_share: Vec<SeriesPoint> = categories
+        .into_iter()
+        .map(|(category, count)| SeriesPoint::new(category.clone(), *count))
+        .collect();
+
+    let durations: Vec<SeriesPoint> = DURATION_BUCKET_LABELS
+        .iter()
+        .zip(aggregator.duration_buckets().iter())
+        .map(|(label, count)| SeriesPoint::new(format!("{} min", label), *count))
+        .collect();
+
+    html! {
+        <>
+            <ChartCard title="Minutes per Day">
+                <BarChart series={per_day} unit="min" />
+            </ChartCard>
+            <ChartCard title="Minutes per Week">
+                <LineChart series={per_week} unit="min" />
+            </ChartCard>
+            <ChartCard title="Categories">
+                <DonutChart series={category_share} colours={category_colours} unit="sessions" />
+            </ChartCard>
+            <ChartCard title="Session Length">
+                <BarChart series={durations} unit="sessions" />
+            </ChartCard>
+        </>
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{Duration, Utc};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        database::{
+            log_envelope::LogEncoding,
+            meditation_log::{
+                read_write_meditation_data::{
+                    meditation_log_encoding, pending_meditation_count, save_pending_meditations,
+                    set_meditation_log_encoding,
+                },
+                read_write_storage::{archive_meditations_before, storage_usage},
+            },
+            storage_quota::UsageLevel,
+        },
+        hooks::use_meditation_state::use_reload_meditations,
+    },
+};
+
+/// Sessions older than this are offered for archiving.
+const ARCHIVE_AFTER_DAYS: i64 = 365;
+
+#[derive(PartialEq, Properties)]
+pub struct StoragePanelProps {}
+
+/// Shows how full storage is and frees space by compressing the log or archiving old sessions.
+#[function_component]
+pub fn StoragePanel(props: &StoragePanelProps) -> Html {
+    let StoragePanelProps {} = props;
+
+    let encoding = use_state(meditation_log_encoding);
+    let usage = use_state(storage_usage);
+    let pending = use_state(pending_meditation_count);
+    let message = use_state(|| None::<String>);
+    let reload_meditations = use_reload_meditations();
+
+    // After space was freed, stores waiting sessions and shows the new usage.
+    let refresh = {
+        let encoding = encoding.clone();
+        let usage = usage.clone();
+        let pending = pending.clone();
+        let reload_meditations = reload_meditations.clone();
+        move || {
+            if let Ok(saved) = save_pending_meditations() {
+                if saved > 0 {
+                    reload_meditations.emit(());
+                }
+            }
+            encoding.set(meditation_log_encoding());
+            usage.set(storage_usage());
+            pending.set(pending_meditation_count());
+        }
+    };
+
+    let on_compact = {
+        let refresh = refresh.clone();
+        let message = message.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let encoding = if input.checked() {
+                LogEncoding::Compact
+            } else {
+                LogEncoding::Json
+            };
+            message.set(
+                set_meditation_log_encoding(encoding)
+                    .err()
+                    .map(|error| format!("Storage not changed: {}", error)),
+            );
+            refresh();
+        })
+    };
+
+    let on_archive = {
+        let refresh = refresh.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let cutoff = (Utc::now() - Duration::days(ARCHIVE_AFTER_DAYS)).timestamp();
+            message.set(Some(match archive_meditations_before(cutoff) {
+                Ok(0) => "No sessions are older than a year.".to_string(),
+                Ok(archived) => {
+                    reload_meditations.emit(());
+                    format!("Archived {} sessions to a file.", archived)
+                }
+                Err(error) => format!("Archive failed: {:?}", error),
+            }));
+            refresh();
+        })
+    };
+
+    let on_retry = Callback::from(move |_: MouseEvent| refresh());
+
+    let button = "px-4 py-2 rounded-lg font-semibold bg-gray-200";
+
+    html! {
+        <Card>
+            <div class="flex flex-col gap-2 w-72 text-sm">
+                if let Some(usage) = *usage {
+                    <div class="flex justify-between">
+                        <span class="text-gray-500">{"Storage used"}</span>
+                        <span>{format!("{:.0} of {:.0} KB", usage.used_bytes as f64 / 1024.0, usage.quota_bytes as f64 / 1024.0)}</span>
+                    </div>
+                    <div class="h-2 rounded bg-gray-200">
+                        <div class={classes!("h-2", "rounded", match usage.level() {
+                                UsageLevel::Normal => "bg-emerald-600",
+                                UsageLevel::Warning => "bg-amber-500",
+                                UsageLevel::Critical => "bg-red-600",
+                            })}
+                            style={format!("width: {:.0}%", (usage.fraction() * 100.0).min(100.0))}></div>
+                    </div>
+                }
+                if *pending > 0 {
+                    <div class="flex items-center justify-between gap-2 text-red-600">
+                        <span>{format!("{} finished sessions are waiting for space.", *pending)}</span>
+                        <button class={button} onclick={on_retry}>{"Save"}</button>
+                    </div>
+                }
+                <label class="flex items-center gap-2">
+                    <input type="checkbox" checked={*encoding == LogEncoding::Compact} onchange={on_compact} />
+                    {"Compact storage"}
+                </label>
+                <div class="text-gray-600">{"Stores a long history in a fraction of the space. Older versions of the app can't read it."}</div>
+                <button class={button} onclick={on_archive}>{"Archive Sessions Older Than a Year"}</button>
+                <div class="text-gray-600">{"Saves them to a file and removes them from this app and your synced devices."}</div>
+                if let Some(message) = (*message).clone() {
+                    <div class="text-gray-600">{message}</div>
+                }
+            </div>
+        </Card>
+    }
+}
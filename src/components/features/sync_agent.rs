@@ -0,0 +1,62 @@
+use std::{cell::RefCell, rc::Rc};
+
+use gloo_timers::callback::Timeout;
+use web_sys::{js_sys::Math, wasm_bindgen::JsValue};
+use yew::prelude::*;
+
+use crate::utils::{
+    hooks::use_meditation_state::use_reload_meditations,
+    sync::{
+        backoff::Backoff,
+        sync_runner::{run_sync, SyncError},
+    },
+};
+
+/// Time between syncs while everything works.
+const SYNC_INTERVAL_MS: u32 = 5 * 60 * 1000;
+const RETRY_BASE_MS: u32 = 5 * 1000;
+const RETRY_MAX_MS: u32 = SYNC_INTERVAL_MS;
+
+#[derive(PartialEq, Properties)]
+pub struct SyncAgentProps {}
+
+/// Syncs in the background while sync is on: at start-up, then every few minutes, and
+/// sooner with growing pauses after a failure. Renders nothing.
+#[function_component]
+pub fn SyncAgent(props: &SyncAgentProps) -> Html {
+    let SyncAgentProps {} = props;
+
+    // Each attempt schedules the next one by bumping this counter.
+    let attempt = use_state(|| 0_u32);
+    let backoff = use_mut_ref(|| Backoff::new(RETRY_BASE_MS, RETRY_MAX_MS));
+    let reload_meditations = use_reload_meditations();
+
+    use_effect_with(*attempt, move |current| {
+        let next = *current + 1;
+        let timeout: Rc<RefCell<Option<Timeout>>> = Rc::default();
+        let pending = timeout.clone();
+        run_sync(move |result| {
+            let delay = match result {
+                Ok(report) => {
+                    backoff.borrow_mut().succeed();
+                    if report.changed > 0 {
+                        reload_meditations.emit(());
+                    }
+                    SYNC_INTERVAL_MS
+                }
+                Err(SyncError::Disabled) => SYNC_INTERVAL_MS,
+                Err(error) => {
+                    web_sys::console::log_1(&JsValue::from_str(&format!(
+                        "Sync failed, retrying: {}",
+                        error
+                    )));
+                    backoff.borrow_mut().fail(Math::random())
+                }
+            };
+            *pending.borrow_mut() = Some(Timeout::new(delay, move || attempt.set(next)));
+        });
+        move || drop(timeout.take())
+    });
+
+    html! {}
+}
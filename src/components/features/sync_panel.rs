@@ -0,0 +1,160 @@
+use chrono::{Local, TimeZone};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        database::meditation_log::read_write_log_crdt::try_track_meditation_log,
+        hooks::use_meditation_state::use_reload_meditations,
+        sync::{
+            read_write_sync_state::{read_sync_state, save_sync_state, try_read_sync_state},
+            sync_runner::run_sync,
+        },
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct SyncPanelProps {}
+
+/// Turns sync with a self-hosted server on and off, and syncs on demand.
+#[function_component]
+pub fn SyncPanel(props: &SyncPanelProps) -> Html {
+    let SyncPanelProps {} = props;
+
+    let state = use_state(read_sync_state);
+    let endpoint = use_state(String::new);
+    let syncing = use_state(|| false);
+    let message = use_state(|| None::<String>);
+    let reload_meditations = use_reload_meditations();
+
+    let sync_now = {
+        let state = state.clone();
+        let syncing = syncing.clone();
+        let message = message.clone();
+        Callback::from(move |_: ()| {
+            syncing.set(true);
+            message.set(None);
+            let state = state.clone();
+            let syncing = syncing.clone();
+            let message = message.clone();
+            let reload_meditations = reload_meditations.clone();
+            run_sync(move |result| {
+                match result {
+                    Ok(report) => {
+                        if report.changed > 0 {
+                            reload_meditations.emit(());
+                        }
+                        message.set(Some(format!(
+                            "Sent {} changes, received {}",
+                            report.pushed, report.changed
+                        )));
+                    }
+                    Err(error) => message.set(Some(format!("Sync failed: {}", error))),
+                }
+                syncing.set(false);
+                state.set(read_sync_state());
+            });
+        })
+    };
+
+    let on_endpoint = {
+        let endpoint = endpoint.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            endpoint.set(input.value());
+        })
+    };
+
+    let on_enable = {
+        let state = state.clone();
+        let endpoint = endpoint.clone();
+        let message = message.clone();
+        let sync_now = sync_now.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let url = endpoint.trim().trim_end_matches('/').to_string();
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                message.set(Some(
+                    "Enter the server's address, starting with https://".to_string(),
+                ));
+                return;
+            }
+            let stored = try_track_meditation_log().and_then(|log| {
+                let mut next = try_read_sync_state()?;
+                next.enable(url, &log);
+                save_sync_state(&next).map(|_| next)
+            });
+            match stored {
+                Ok(next) => {
+                    state.set(next);
+                    sync_now.emit(());
+                }
+                Err(error) => message.set(Some(format!("Sync not turned on: {}", error))),
+            }
+        })
+    };
+
+    let on_disable = {
+        let state = state.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let stored = try_read_sync_state().and_then(|mut next| {
+                next.disable();
+                save_sync_state(&next).map(|_| next)
+            });
+            match stored {
+                Ok(next) => {
+                    state.set(next);
+                    message.set(Some(
+                        "Sync is off. Your sessions stay on this device.".to_string(),
+                    ));
+                }
+                Err(error) => message.set(Some(format!("Sync not turned off: {}", error))),
+            }
+        })
+    };
+
+    let button = "px-4 py-2 rounded-lg font-semibold";
+    let last_synced = state
+        .last_synced_at
+        .and_then(|at| Local.timestamp_millis_opt(at).single())
+        .map_or("Never".to_string(), |at| {
+            at.format("%Y-%m-%d %H:%M").to_string()
+        });
+
+    html! {
+        <Card>
+            <div class="flex flex-col gap-3 w-72 text-sm">
+                if let Some(url) = state.endpoint.clone() {
+                    <div class="grid grid-cols-2 gap-1">
+                        <span class="text-gray-500">{"Server"}</span>
+                        <span class="truncate">{url}</span>
+                        <span class="text-gray-500">{"Waiting to send"}</span>
+                        <span>{state.outbox.len()}</span>
+                        <span class="text-gray-500">{"Last synced"}</span>
+                        <span>{last_synced}</span>
+                    </div>
+                    <div class="flex gap-2">
+                        <button class={classes!(button, "bg-emerald-600", "text-white")} disabled={*syncing}
+                            onclick={sync_now.reform(|_: MouseEvent| ())}>
+                            { if *syncing { "Syncing…" } else { "Sync Now" } }
+                        </button>
+                        <button class={classes!(button, "bg-gray-200")} onclick={on_disable}>{"Turn Off"}</button>
+                    </div>
+                } else {
+                    <form class="flex flex-col gap-2" onsubmit={on_enable}>
+                        <div>{"Sync your sessions between devices through your own server. Changes made offline are sent the next time you're online."}</div>
+                        <label for="sync-endpoint">{"Server address"}</label>
+                        <input id="sync-endpoint" class="px-2 border rounded" type="url"
+                            placeholder="https://sync.example.com/api" value={(*endpoint).clone()} oninput={on_endpoint} />
+                        <button type="submit" class={classes!(button, "bg-emerald-600", "text-white")}>{"Turn On Sync"}</button>
+                    </form>
+                }
+                if let Some(message) = (*message).clone() {
+                    <div class="text-gray-600">{message}</div>
+                }
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,157 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    utils::{
+        database::meditation_log::{
+            read_write_meditation_data::change_meditations,
+            tag_manager::{remove_tag, rename_tag},
+        },
+        hooks::use_meditation_state::{use_reload_meditations, use_stats_aggregator},
+    },
+};
+
+/// What is being done to a tag, if anything.
+#[derive(Clone, Debug, PartialEq)]
+enum TagAction {
+    /// Renaming the tag, with the new name as typed so far.
+    Rename(String, String),
+    /// Waiting for confirmation to remove the tag from every session.
+    Remove(String),
+}
+
+#[derive(PartialEq, Properties)]
+pub struct TagManagerProps {}
+
+/// Lists every tag in the log and renames, merges or removes them across all sessions.
+#[function_component]
+pub fn TagManager(props: &TagManagerProps) -> Html {
+    let TagManagerProps {} = props;
+
+    let aggregator = use_stats_aggregator();
+    let reload_meditations = use_reload_meditations();
+    let action = use_state(|| None::<TagAction>);
+    let message = use_state(|| None::<String>);
+    let summaries = aggregator.tag_summaries();
+    let known_tags = aggregator.tags_by_use();
+
+    let set_action = |next: Option<TagAction>| {
+        let action = action.clone();
+        Callback::from(move |_: MouseEvent| action.set(next.clone()))
+    };
+
+    let on_draft = {
+        let action = action.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(TagAction::Rename(tag, _)) = &*action {
+                action.set(Some(TagAction::Rename(tag.clone(), input.value())));
+            }
+        })
+    };
+
+    let on_apply = {
+        let action = action.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let result = match (*action).clone() {
+                Some(TagAction::Rename(tag, draft)) => {
+                    let to = draft.trim().to_string();
+                    if to.is_empty() || to == tag {
+                        None
+                    } else {
+                        let changed =
+                            change_meditations(|meditations| rename_tag(meditations, &tag, &to));
+                        let verb = if known_tags.contains(&to) {
+                            "Merged"
+                        } else {
+                            "Renamed"
+                        };
+                        Some(format!(
+                            "{} \"{}\" into \"{}\" in {} sessions",
+                            verb, tag, to, changed
+                        ))
+                    }
+                }
+                Some(TagAction::Remove(tag)) => {
+                    let changed = change_meditations(|meditations| remove_tag(meditations, &tag));
+                    Some(format!("Removed \"{}\" from {} sessions", tag, changed))
+                }
+                None => None,
+            };
+            action.set(None);
+            if let Some(result) = result {
+                reload_meditations.emit(());
+                message.set(Some(result));
+            }
+        })
+    };
+
+    if summaries.is_empty() {
+        return html! {
+            <Card>
+                <div class="text-sm text-gray-600 w-72">
+                    {"No tags yet. Add tags to a session to group your practice, e.g. \"morning\" or \"walking\"."}
+                </div>
+            </Card>
+        };
+    }
+
+    html! {
+        <Card>
+            <div class="flex flex-col gap-2 w-72 text-sm">
+                <div class="text-xs text-gray-500">
+                    {"Renaming a tag to one that already exists merges the two."}
+                </div>
+                <ul class="flex flex-col divide-y">
+                    {
+                        summaries.iter().map(|summary| {
+                            let tag = summary.tag.clone();
+                            let row = match &*action {
+                                Some(TagAction::Rename(editing, draft)) if *editing == tag => html! {
+                                    <div class="flex items-center gap-2">
+                                        <input class="min-w-0 px-2 border rounded grow" type="text" list="known-tags"
+                                            value={draft.clone()} oninput={on_draft.clone()} />
+                                        <button class="font-semibold text-emerald-700" onclick={on_apply.clone()}>{"Save"}</button>
+                                        <button class="text-gray-600" onclick={set_action(None)}>{"Cancel"}</button>
+                                    </div>
+                                },
+                                Some(TagAction::Remove(removing)) if *removing == tag => html! {
+                                    <div class="flex items-center justify-between gap-2">
+                                        <span>{format!("Remove \"{}\" from {} sessions?", tag, summary.sessions)}</span>
+                                        <span class="flex gap-2 shrink-0">
+                                            <button class="font-semibold text-red-600" onclick={on_apply.clone()}>{"Remove"}</button>
+                                            <button class="text-gray-600" onclick={set_action(None)}>{"Keep"}</button>
+                                        </span>
+                                    </div>
+                                },
+                                _ => html! {
+                                    <div class="flex items-center justify-between gap-2">
+                                        <span class="truncate">
+                                            <span class="px-1 rounded bg-gray-100">{tag.clone()}</span>
+                                            <span class="text-xs text-gray-500">{format!(" {} sessions", summary.sessions)}</span>
+                                        </span>
+                                        <span class="flex gap-2 text-xs shrink-0">
+                                            <button class="text-emerald-700"
+                                                onclick={set_action(Some(TagAction::Rename(tag.clone(), tag.clone())))}>{"Rename"}</button>
+                                            <button class="text-gray-600"
+                                                onclick={set_action(Some(TagAction::Remove(tag.clone())))}>{"Remove"}</button>
+                                        </span>
+                                    </div>
+                                },
+                            };
+                            html! { <li key={summary.tag.clone()} class="py-2">{row}</li> }
+                        }).collect::<Html>()
+                    }
+                </ul>
+                <datalist id="known-tags">
+                    { summaries.iter().map(|summary| html! { <option value={summary.tag.clone()} /> }).collect::<Html>() }
+                </datalist>
+                if let Some(message) = (*message).clone() {
+                    <div class="text-gray-600">{message}</div>
+                }
+            </div>
+        </Card>
+    }
+}
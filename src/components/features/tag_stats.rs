@@ -0,0 +1,108 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{
+    components::ui::card::Card,
+    routes::Route,
+    utils::{
+        database::meditation_log::{
+            meditation_query::MeditationQuery, read_write_meditation_data::read_meditation_data,
+            stats_aggregator::StatsAggregator,
+        },
+        hooks::use_meditation_state::use_stats_aggregator,
+        shared::format_duration,
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct TagStatsProps {}
+
+/// Practice per tag, with the full stats for one selected tag.
+#[function_component]
+pub fn TagStats(props: &TagStatsProps) -> Html {
+    let TagStatsProps {} = props;
+
+    let aggregator = use_stats_aggregator();
+    let selected = use_state(String::new);
+    let summaries = aggregator.tag_summaries();
+
+    // Only the selected tag's sessions are read back from the log, once per selection;
+    // the shared aggregator changes too, so a newly logged session is picked up.
+    let tag_stats = use_memo(((*selected).clone(), aggregator.clone()), |(tag, _)| {
+        (!tag.is_empty()).then(|| {
+            let meditations = read_meditation_data();
+            let query = MeditationQuery {
+                tags: vec![tag.clone()],
+                ..Default::default()
+            };
+            let mut tagged = StatsAggregator::with_calendar(aggregator.calendar());
+            for (_, meditation) in query.run(&meditations) {
+                tagged.record(meditation);
+            }
+            tagged.stats(tagged.today())
+        })
+    });
+
+    let on_select = {
+        let selected = selected.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            selected.set(input.value());
+        })
+    };
+
+    html! {
+        <Card>
+            <div class="w-80 text-sm">
+                <div class="flex items-baseline justify-between pb-1 mb-2 border-b">
+                    <span class="text-lg font-semibold">{"Tags"}</span>
+                    <Link<Route> to={Route::Tags} classes="text-xs text-emerald-700 underline">{"Manage"}</Link<Route>>
+                </div>
+                if summaries.is_empty() {
+                    <div class="text-gray-500">{"Tag your sessions to compare kinds of practice."}</div>
+                } else {
+                    <div class="grid grid-cols-4 gap-x-2">
+                        <span class="font-semibold">{"Tag"}</span>
+                        <span class="font-semibold text-right">{"Sessions"}</span>
+                        <span class="font-semibold text-right">{"Time"}</span>
+                        <span class="font-semibold text-right">{"Mood"}</span>
+                        {
+                            summaries.iter().map(|summary| html! {
+                                <>
+                                    <span class="truncate">{summary.tag.clone()}</span>
+                                    <span class="text-right">{summary.sessions}</span>
+                                    <span class="text-right">{format_duration((summary.total_minutes * 60.0) as i64)}</span>
+                                    <span class="text-right">
+                                        {summary.average_mood_delta.map(|delta| format!("{:+.1}", delta)).unwrap_or_else(|| "–".to_string())}
+                                    </span>
+                                </>
+                            }).collect::<Html>()
+                        }
+                    </div>
+
+                    <div class="flex items-center gap-2 pt-2 mt-2 border-t">
+                        <label for="tag-stats-tag">{"Stats for"}</label>
+                        <select id="tag-stats-tag" class="px-2 border rounded grow" onchange={on_select}>
+                            <option value="" selected={selected.is_empty()}>{"Choose a tag"}</option>
+                            {
+                                summaries.iter().map(|summary| html! {
+                                    <option value={summary.tag.clone()} selected={*selected == summary.tag}>{summary.tag.clone()}</option>
+                                }).collect::<Html>()
+                            }
+                        </select>
+                    </div>
+                    if let Some(stats) = &*tag_stats {
+                        <div class="px-2 py-1">
+                            <div>{"Sessions: "}{stats.total_meditation_sessions}</div>
+                            <div>{"Total Time: "}{format_duration((stats.total_hours_meditated * 3600.0) as i64)}</div>
+                            <div>{"Average Duration: "}{format_duration(stats.average_duration_per_meditation as i64)}</div>
+                            <div>{"Streak: "}{stats.days_meditated_in_row}{" days"}</div>
+                            <div>{"Favourite Category: "}{stats.favorite_category.clone()}</div>
+                        </div>
+                    }
+                }
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,120 @@
+use gloo_timers::callback::Timeout;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::{
+    components::{
+        layouts::primary_layout::PrimaryLayout,
+        ui::{card::Card, title_banner::TitleBanner},
+    },
+    utils::database::{
+        encryption::EncryptionError,
+        protected_storage::{erase_protected_data, lock_state, unlock, LockState},
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct UnlockGateProps {
+    pub children: Html,
+}
+
+/// Asks for the passphrase while stored data is encrypted and locked, and shows the app once
+/// it is unlocked.
+#[function_component]
+pub fn UnlockGate(props: &UnlockGateProps) -> Html {
+    let UnlockGateProps { children } = props;
+
+    let state = use_state(lock_state);
+    let passphrase = use_state(String::new);
+    let unlocking = use_state(|| false);
+    let error = use_state(|| None::<String>);
+    let confirm_erase = use_state(|| false);
+
+    let on_passphrase = {
+        let passphrase = passphrase.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            passphrase.set(input.value());
+        })
+    };
+
+    let on_unlock = {
+        let state = state.clone();
+        let passphrase = passphrase.clone();
+        let unlocking = unlocking.clone();
+        let error = error.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            unlocking.set(true);
+            error.set(None);
+            let state = state.clone();
+            let passphrase = passphrase.clone();
+            let unlocking = unlocking.clone();
+            let error = error.clone();
+            // Deriving the key takes a moment; let the "Unlocking…" state render first.
+            Timeout::new(0, move || {
+                match unlock(&passphrase) {
+                    Ok(()) => state.set(lock_state()),
+                    Err(EncryptionError::WrongPassphrase) => error.set(Some(
+                        "That passphrase is not correct. Try again.".to_string(),
+                    )),
+                    Err(other) => error.set(Some(format!("Can't unlock: {}", other))),
+                }
+                passphrase.set(String::new());
+                unlocking.set(false);
+            })
+            .forget();
+        })
+    };
+
+    let set_confirm_erase = |value: bool| {
+        let confirm_erase = confirm_erase.clone();
+        Callback::from(move |_: MouseEvent| confirm_erase.set(value))
+    };
+
+    let on_erase = {
+        let state = state.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| match erase_protected_data() {
+            Ok(()) => state.set(lock_state()),
+            Err(_) => error.set(Some("Can't erase the stored data".to_string())),
+        })
+    };
+
+    if *state != LockState::Locked {
+        return children.clone();
+    }
+
+    let button = "px-4 py-2 rounded-lg font-semibold";
+
+    html! {
+        <PrimaryLayout>
+            <TitleBanner title={"Locked"} />
+            <Card>
+                <form class="flex flex-col gap-3 w-72 text-sm" onsubmit={on_unlock}>
+                    <div>{"Your sessions are encrypted. Enter your passphrase to open them."}</div>
+                    <label for="unlock-passphrase">{"Passphrase"}</label>
+                    <input id="unlock-passphrase" class="px-2 border rounded" type="password"
+                        autocomplete="current-password" value={(*passphrase).clone()} oninput={on_passphrase} />
+                    <button type="submit" class={classes!(button, "bg-emerald-600", "text-white")} disabled={*unlocking}>
+                        { if *unlocking { "Unlocking…" } else { "Unlock" } }
+                    </button>
+                    if let Some(error) = (*error).clone() {
+                        <div class="text-red-600">{error}</div>
+                    }
+                    if *confirm_erase {
+                        <div class="flex flex-col gap-2 p-2 border border-red-300 rounded">
+                            <div>{"Without the passphrase your sessions can't be recovered. Erase them from this device and start over?"}</div>
+                            <div class="flex gap-2">
+                                <button type="button" class={classes!(button, "bg-red-600", "text-white")} onclick={on_erase}>{"Erase"}</button>
+                                <button type="button" class={classes!(button, "bg-gray-200")} onclick={set_confirm_erase(false)}>{"Cancel"}</button>
+                            </div>
+                        </div>
+                    } else {
+                        <button type="button" class="text-gray-600 underline" onclick={set_confirm_erase(true)}>{"Forgot your passphrase?"}</button>
+                    }
+                </form>
+            </Card>
+        </PrimaryLayout>
+    }
+}
@@ -1,6 +1,9 @@
 use yew::prelude::*;
 
-use crate::{components::ui::card::Card, utils::hooks::use_meditation_state::use_meditation_data};
+use crate::{
+    components::ui::card::Card,
+    utils::hooks::{use_meditation_state::use_meditation_data, use_settings::use_settings},
+};
 
 #[derive(PartialEq, Properties)]
 pub struct UserStatsProps {}
@@ -10,6 +13,8 @@ pub fn UserStats(props: &UserStatsProps) -> Html {
     let UserStatsProps {} = props;
 
     let meditation_data = use_meditation_data();
+    let trends = &meditation_data.trends;
+    let settings = use_settings().settings;
 
     html! {
         <Card>
@@ -21,6 +26,13 @@ pub fn UserStats(props: &UserStatsProps) -> Html {
                 <div class="">{"Favourite Category: "}{meditation_data.favorite_category.to_string()}</div>
                 <div class="">{"Favourite Speaker: "}{meditation_data.favorite_speaker.to_string()}</div>
             </div>
+            <div id="trends-grid" class="px-4 py-1 text-sm border-t">
+                <div class="">{"This Week: "}{settings.format_minutes(trends.week.current_minutes as i64)}{" "}{trends.week.summary("last week")}</div>
+                <div class="">{"This Month: "}{settings.format_minutes(trends.month.current_minutes as i64)}{" "}{trends.month.summary("last month")}</div>
+                <div class="">{"7-Day Average: "}{format!("{:.1}", trends.rolling_7_day_average)}{" min/day"}</div>
+                <div class="">{"30-Day Average: "}{format!("{:.1}", trends.rolling_30_day_average)}{" min/day"}</div>
+                <div class="">{"Daily Trend: "}{format!("{:+.1}", trends.daily_minutes_trend)}{" min/day"}</div>
+            </div>
         </Card>
 
     }
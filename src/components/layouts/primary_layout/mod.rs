@@ -1,5 +1,10 @@
 use yew::prelude::*;
 
+use crate::{
+    components::features::profile_switcher::ProfileSwitcher,
+    utils::{hooks::use_settings::SettingsContext, settings::app_settings::Theme},
+};
+
 #[derive(PartialEq, Properties)]
 pub struct PrimaryLayoutProps {
     pub children: Html,
@@ -8,8 +13,12 @@ pub struct PrimaryLayoutProps {
 #[function_component]
 pub fn PrimaryLayout(props: &PrimaryLayoutProps) -> Html {
     let PrimaryLayoutProps { children } = props;
+    // Outside a `SettingsProvider`, e.g. while the stored data is locked, follow the device.
+    let theme =
+        use_context::<SettingsContext>().map_or(Theme::System, |context| context.settings.theme);
     html! {
-        <div class="h-[844px] w-[390px] max-h:h-[844px] max-w:w-[390px] border">
+        <div class={classes!("h-[844px]", "w-[390px]", "max-h:h-[844px]", "max-w:w-[390px]", "border", "overflow-y-auto", theme.class())}>
+            <ProfileSwitcher/>
             {children}
         </div>
     }
@@ -0,0 +1,69 @@
+use yew::prelude::*;
+
+use super::{
+    format_value,
+    geometry::{bar_rects, nice_max},
+    label_step, SeriesPoint, PALETTE,
+};
+
+const WIDTH: f64 = 300.0;
+const PLOT_HEIGHT: f64 = 100.0;
+const HEIGHT: f64 = 116.0;
+
+#[derive(PartialEq, Properties)]
+pub struct BarChartProps {
+    pub series: Vec<SeriesPoint>,
+    /// Unit appended to values in tooltips, e.g. "min".
+    #[prop_or_default]
+    pub unit: String,
+    #[prop_or(PALETTE[0].to_string())]
+    pub colour: String,
+}
+
+#[function_component]
+pub fn BarChart(props: &BarChartProps) -> Html {
+    let BarChartProps {
+        series,
+        unit,
+        colour,
+    } = props;
+
+    let values: Vec<f64> = series.iter().map(|point| point.value).collect();
+    let max = nice_max(values.iter().cloned().fold(0.0, f64::max));
+    let rects = bar_rects(&values, max, WIDTH, PLOT_HEIGHT, 2.0);
+    let step = label_step(series.len(), 8);
+
+    html! {
+        <svg class="w-full" viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} role="img">
+            <line x1="0" y1={PLOT_HEIGHT.to_string()} x2={WIDTH.to_string()} y2={PLOT_HEIGHT.to_string()} stroke="#e5e7eb" />
+            <text x="0" y="8" font-size="8" fill="#6b7280">{format_value(max, unit)}</text>
+            {
+                series.iter().zip(rects.iter()).enumerate().map(|(i, (point, rect))| html! {
+                    <g>
+                        <rect
+                            x={format!("{:.2}", rect.x)}
+                            y={format!("{:.2}", rect.y)}
+                            width={format!("{:.2}", rect.width)}
+                            height={format!("{:.2}", rect.height)}
+                            rx="1"
+                            fill={colour.clone()}
+                        >
+                            <title>{format!("{}: {}", point.label, format_value(point.value, unit))}</title>
+                        </rect>
+                        if i % step == 0 {
+                            <text
+                                x={format!("{:.2}", rect.x + rect.width / 2.0)}
+                                y={(HEIGHT - 2.0).to_string()}
+                                font-size="8"
+                                fill="#6b7280"
+                                text-anchor="middle"
+                            >
+                                {point.label.clone()}
+                            </text>
+                        }
+                    </g>
+                }).collect::<Html>()
+            }
+        </svg>
+    }
+}
@@ -0,0 +1,78 @@
+use yew::prelude::*;
+
+use super::{format_value, geometry::donut_paths, SeriesPoint, PALETTE};
+
+const RADIUS: f64 = 50.0;
+const INNER_RADIUS: f64 = 30.0;
+
+#[derive(PartialEq, Properties)]
+pub struct DonutChartProps {
+    pub series: Vec<SeriesPoint>,
+    /// Slice colours in series order; the default palette is used when empty or too short.
+    #[prop_or_default]
+    pub colours: Vec<String>,
+    /// Unit appended to values in tooltips, e.g. "sessions".
+    #[prop_or_default]
+    pub unit: String,
+}
+
+#[function_component]
+pub fn DonutChart(props: &DonutChartProps) -> Html {
+    let DonutChartProps {
+        series,
+        colours,
+        unit,
+    } = props;
+
+    let values: Vec<f64> = series.iter().map(|point| point.value).collect();
+    let total: f64 = values.iter().map(|value| value.max(0.0)).sum();
+    let paths = donut_paths(&values, RADIUS, INNER_RADIUS);
+    let colour = |i: usize| {
+        colours
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| PALETTE[i % PALETTE.len()].to_string())
+    };
+    let share = |value: f64| {
+        if total > 0.0 {
+            value.max(0.0) / total * 100.0
+        } else {
+            0.0
+        }
+    };
+
+    html! {
+        <div class="flex items-center gap-4">
+            <svg class="w-28 h-28 shrink-0" viewBox={format!("0 0 {0} {0}", RADIUS * 2.0)} role="img">
+                if paths.is_empty() {
+                    <circle
+                        cx={RADIUS.to_string()}
+                        cy={RADIUS.to_string()}
+                        r={((RADIUS + INNER_RADIUS) / 2.0).to_string()}
+                        fill="none"
+                        stroke="#e5e7eb"
+                        stroke-width={(RADIUS - INNER_RADIUS).to_string()}
+                    />
+                }
+                {
+                    series.iter().zip(paths.iter()).enumerate().map(|(i, (point, path))| html! {
+                        <path d={path.clone()} fill={colour(i)}>
+                            <title>{format!("{}: {} ({:.0}%)", point.label, format_value(point.value, unit), share(point.value))}</title>
+                        </path>
+                    }).collect::<Html>()
+                }
+            </svg>
+            <ul class="text-xs">
+                {
+                    series.iter().enumerate().map(|(i, point)| html! {
+                        <li class="flex items-center gap-1">
+                            <span class="inline-block w-2 h-2 rounded-sm" style={format!("background-color: {}", colour(i))}></span>
+                            <span class="truncate">{point.label.clone()}</span>
+                            <span class="text-gray-500">{format!("{:.0}%", share(point.value))}</span>
+                        </li>
+                    }).collect::<Html>()
+                }
+            </ul>
+        </div>
+    }
+}
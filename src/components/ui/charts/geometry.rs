@@ -0,0 +1,199 @@
+use std::f64::consts::PI;
+
+/// A rectangle in SVG user units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Rounds `max` up to a "nice" axis limit: 1, 2 or 5 times a power of ten.
+///
+/// # Returns
+///
+/// The axis limit, or `1.0` if `max` is not positive so empty charts still have a scale.
+pub fn nice_max(max: f64) -> f64 {
+    if !max.is_finite() || max <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(max.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|step| step * magnitude)
+        .find(|&limit| limit >= max)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Lays out one bar per value, bottom-aligned in a `width` by `height` box.
+///
+/// # Arguments
+///
+/// * `values` - The bar values; negative values are drawn as empty bars.
+/// * `max` - The value that maps to the full `height`.
+/// * `gap` - Horizontal space between bars.
+pub fn bar_rects(values: &[f64], max: f64, width: f64, height: f64, gap: f64) -> Vec<Rect> {
+    if values.is_empty() {
+        return Vec::new();
+    }
+    let slot = width / values.len() as f64;
+    let bar_width = (slot - gap).max(1.0);
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let bar_height = (value.max(0.0) / max * height).min(height);
+            Rect {
+                x: i as f64 * slot + (slot - bar_width) / 2.0,
+                y: height - bar_height,
+                width: bar_width,
+                height: bar_height,
+            }
+        })
+        .collect()
+}
+
+/// Spreads the values evenly across `width` and returns the (x, y) coordinate of each point.
+///
+/// A single value is centred horizontally.
+pub fn line_points(values: &[f64], max: f64, width: f64, height: f64) -> Vec<(f64, f64)> {
+    let step = if values.len() > 1 {
+        width / (values.len() - 1) as f64
+    } else {
+        0.0
+    };
+    let offset = if values.len() == 1 { width / 2.0 } else { 0.0 };
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let y = height - (value.max(0.0) / max * height).min(height);
+            (offset + i as f64 * step, y)
+        })
+        .collect()
+}
+
+/// Formats points as an SVG path of straight segments, e.g. "M0 10 L5 2".
+pub fn line_path(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, (x, y))| {
+            let command = if i == 0 { "M" } else { "L" };
+            format!("{}{:.2} {:.2}", command, x, y)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn polar(cx: f64, cy: f64, radius: f64, angle: f64) -> (f64, f64) {
+    // Angles start at 12 o'clock and run clockwise.
+    (cx + radius * angle.sin(), cy - radius * angle.cos())
+}
+
+/// Builds one SVG path per value for a donut centred on (`radius`, `radius`).
+///
+/// # Arguments
+///
+/// * `values` - Slice sizes; negative values count as zero.
+/// * `radius` - Outer radius.
+/// * `inner_radius` - Radius of the hole.
+///
+/// # Returns
+///
+/// One path per value (empty for zero-sized slices), or no paths if all values are zero.
+pub fn donut_paths(values: &[f64], radius: f64, inner_radius: f64) -> Vec<String> {
+    let total: f64 = values.iter().map(|value| value.max(0.0)).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut start = 0.0;
+    values
+        .iter()
+        .map(|value| {
+            let sweep = value.max(0.0) / total * 2.0 * PI;
+            if sweep <= 0.0 {
+                return String::new();
+            }
+            // A full circle can't be drawn with one arc, so stop just short of it.
+            let end = start + sweep.min(2.0 * PI - 1e-4);
+            let large_arc = if end - start > PI { 1 } else { 0 };
+
+            let (ox1, oy1) = polar(radius, radius, radius, start);
+            let (ox2, oy2) = polar(radius, radius, radius, end);
+            let (ix1, iy1) = polar(radius, radius, inner_radius, end);
+            let (ix2, iy2) = polar(radius, radius, inner_radius, start);
+            start += sweep;
+
+            format!(
+                "M{:.2} {:.2} A{r:.2} {r:.2} 0 {la} 1 {:.2} {:.2} L{:.2} {:.2} A{ir:.2} {ir:.2} 0 {la} 0 {:.2} {:.2} Z",
+                ox1, oy1, ox2, oy2, ix1, iy1, ix2, iy2,
+                r = radius,
+                ir = inner_radius,
+                la = large_arc,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_max() {
+        assert_eq!(nice_max(0.0), 1.0);
+        assert_eq!(nice_max(7.0), 10.0);
+        assert_eq!(nice_max(13.0), 20.0);
+        assert_eq!(nice_max(45.0), 50.0);
+        assert_eq!(nice_max(100.0), 100.0);
+    }
+
+    #[test]
+    fn test_bar_rects_are_bottom_aligned() {
+        let rects = bar_rects(&[10.0, 5.0, 0.0, 20.0], 10.0, 40.0, 100.0, 2.0);
+
+        assert_eq!(rects.len(), 4);
+        assert_eq!(
+            rects[0],
+            Rect {
+                x: 1.0,
+                y: 0.0,
+                width: 8.0,
+                height: 100.0
+            }
+        );
+        assert_eq!(rects[1].y, 50.0);
+        assert_eq!(rects[2].height, 0.0);
+        // Values above the axis limit are clipped to the chart.
+        assert_eq!(rects[3].height, 100.0);
+    }
+
+    #[test]
+    fn test_line_path() {
+        let points = line_points(&[0.0, 5.0, 10.0], 10.0, 100.0, 50.0);
+        assert_eq!(points, vec![(0.0, 50.0), (50.0, 25.0), (100.0, 0.0)]);
+        assert_eq!(line_path(&points), "M0.00 50.00 L50.00 25.00 L100.00 0.00");
+        assert_eq!(line_points(&[3.0], 10.0, 100.0, 50.0)[0].0, 50.0);
+    }
+
+    #[test]
+    fn test_donut_paths() {
+        assert!(donut_paths(&[0.0, 0.0], 50.0, 30.0).is_empty());
+
+        let paths = donut_paths(&[1.0, 0.0, 3.0], 50.0, 30.0);
+        assert_eq!(paths.len(), 3);
+        assert!(paths[0].starts_with("M50.00 0.00 A50.00 50.00 0 0 1 100.00 50.00"));
+        assert!(paths[1].is_empty());
+        // The second slice covers three quarters, so it needs the large arc flag.
+        assert!(paths[2].contains(" 0 1 1 "));
+
+        let full = donut_paths(&[5.0], 50.0, 30.0);
+        assert_eq!(full.len(), 1);
+        assert!(full[0].contains(" 0 1 1 "));
+    }
+}
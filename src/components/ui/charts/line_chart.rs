@@ -0,0 +1,69 @@
+use yew::prelude::*;
+
+use super::{
+    format_value,
+    geometry::{line_path, line_points, nice_max},
+    label_step, SeriesPoint, PALETTE,
+};
+
+const WIDTH: f64 = 300.0;
+const PLOT_HEIGHT: f64 = 100.0;
+const HEIGHT: f64 = 116.0;
+/// Keeps the end points and their markers inside the view box.
+const PADDING: f64 = 4.0;
+
+#[derive(PartialEq, Properties)]
+pub struct LineChartProps {
+    pub series: Vec<SeriesPoint>,
+    /// Unit appended to values in tooltips, e.g. "min".
+    #[prop_or_default]
+    pub unit: String,
+    #[prop_or(PALETTE[1].to_string())]
+    pub colour: String,
+}
+
+#[function_component]
+pub fn LineChart(props: &LineChartProps) -> Html {
+    let LineChartProps {
+        series,
+        unit,
+        colour,
+    } = props;
+
+    let values: Vec<f64> = series.iter().map(|point| point.value).collect();
+    let max = nice_max(values.iter().cloned().fold(0.0, f64::max));
+    let points: Vec<(f64, f64)> =
+        line_points(&values, max, WIDTH - 2.0 * PADDING, PLOT_HEIGHT - PADDING)
+            .into_iter()
+            .map(|(x, y)| (x + PADDING, y + PADDING))
+            .collect();
+    let step = label_step(series.len(), 6);
+
+    html! {
+        <svg class="w-full" viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} role="img">
+            <line x1="0" y1={PLOT_HEIGHT.to_string()} x2={WIDTH.to_string()} y2={PLOT_HEIGHT.to_string()} stroke="#e5e7eb" />
+            <text x="0" y="8" font-size="8" fill="#6b7280">{format_value(max, unit)}</text>
+            <path d={line_path(&points)} fill="none" stroke={colour.clone()} stroke-width="2" stroke-linejoin="round" />
+            {
+                series.iter().zip(points.iter()).enumerate().map(|(i, (point, (x, y)))| html! {
+                    <g>
+                        <circle cx={format!("{:.2}", x)} cy={format!("{:.2}", y)} r="2.5" fill={colour.clone()}>
+                            <title>{format!("{}: {}", point.label, format_value(point.value, unit))}</title>
+                        </circle>
+                        if i % step == 0 {
+                            <text
+                                x={format!("{:.2}", x)}
+                                y={(HEIGHT - 2.0).to_string()}
+                                font-size="8"
+                                fill="#6b7280"
+                                text-anchor="middle"
+                            >
+                                {point.label.clone()}
+                            </text>
+                        }
+                    </g>
+                }).collect::<Html>()
+            }
+        </svg>
+    }
+}
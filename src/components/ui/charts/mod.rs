@@ -0,0 +1,44 @@
+pub mod bar_chart;
+pub mod donut_chart;
+pub mod geometry;
+pub mod line_chart;
+
+/// A single labelled value of a chart series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesPoint {
+    pub label: String,
+    pub value: f64,
+}
+
+impl SeriesPoint {
+    pub fn new(label: impl Into<String>, value: impl Into<f64>) -> Self {
+        SeriesPoint {
+            label: label.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Colours used for series and slices when none are given.
+pub const PALETTE: [&str; 8] = [
+    "#10b981", "#6366f1", "#f59e0b", "#ef4444", "#06b6d4", "#8b5cf6", "#84cc16", "#ec4899",
+];
+
+/// Formats a value for axis labels and tooltips, dropping the decimals of whole numbers.
+pub fn format_value(value: f64, unit: &str) -> String {
+    let number = if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    };
+    if unit.is_empty() {
+        number
+    } else {
+        format!("{} {}", number, unit)
+    }
+}
+
+/// Picks every n-th label so at most `max_labels` are drawn along an axis.
+pub fn label_step(count: usize, max_labels: usize) -> usize {
+    count.div_ceil(max_labels.max(1)).max(1)
+}
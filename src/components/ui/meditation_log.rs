@@ -1,15 +1,25 @@
+use chrono::{Local, TimeZone};
 use yew::prelude::*;
+use yew_router::prelude::*;
 
 use crate::{
     components::ui::card::Card,
-    utils::database::meditation_log::{
-        meditation_data_builder::MeditationData, read_write_meditation_data::read_meditation_data,
+    routes::Route,
+    utils::{
+        database::meditation_log::{
+            meditation_data_builder::MeditationData,
+            read_write_meditation_data::read_meditation_data,
+        },
+        shared::{format_date_time, format_duration},
     },
 };
 
+const RECENT_SESSIONS: usize = 4;
+
 #[derive(PartialEq, Properties)]
 pub struct MeditationLogProps {}
 
+/// The most recently logged sessions, with a link to the full history.
 #[function_component]
 pub fn MeditationLog(props: &MeditationLogProps) -> Html {
     let MeditationLogProps {} = props;
@@ -26,19 +36,25 @@ pub fn MeditationLog(props: &MeditationLogProps) -> Html {
         <Card>
             <div class="w-full">
                 {
-                    (*data_state).clone().into_iter().rev().take(4).map(|feedback| {
-                        let MeditationData {category,datetime,duration,speaker} = feedback;
+                    (*data_state).clone().into_iter().rev().take(RECENT_SESSIONS).map(|feedback| {
+                        let MeditationData {category,datetime,duration,speaker, ..} = feedback;
+                        let start = Local
+                            .timestamp_opt(datetime, 0)
+                            .single()
+                            .map(|datetime| format_date_time(datetime.naive_local()))
+                            .unwrap_or_default();
 
                         html! {
                             <div class="grid gap-1 grid-flow-col auto-cols-fr">
-                                <div class="truncate">{"category: "} {category}</div>
-                                <div class="">{"datetime: "} {datetime}</div>
-                                <div class="">{"duration: "} {duration}</div>
-                                <div class="">{"speaker: "} {speaker}</div>
+                                <div class="truncate">{category}</div>
+                                <div class="">{start}</div>
+                                <div class="">{format_duration(duration as i64)}</div>
+                                <div class="truncate">{speaker}</div>
                             </div>
                         }
                     }).collect::<Html>()
                 }
+                <Link<Route> to={Route::History} classes="text-sm text-emerald-700 underline">{"See all sessions"}</Link<Route>>
             </div>
         </Card>
     }
@@ -1,3 +1,7 @@
 pub mod card;
+pub mod charts;
 pub mod meditation_log;
+pub mod pwa_banner;
+pub mod reminder_banner;
+pub mod storage_banner;
 pub mod title_banner;
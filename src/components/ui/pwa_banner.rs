@@ -0,0 +1,60 @@
+use web_sys::wasm_bindgen::JsValue;
+use yew::prelude::*;
+
+use crate::services::{install_prompt::InstallPrompt, service_worker::ServiceWorkerService};
+
+#[derive(PartialEq, Properties)]
+pub struct PwaBannerProps {}
+
+/// Registers the offline service worker and offers app updates and installation.
+#[function_component]
+pub fn PwaBanner(props: &PwaBannerProps) -> Html {
+    let PwaBannerProps {} = props;
+    let update_ready = use_state(|| false);
+    let installable = use_state(|| false);
+
+    {
+        let update_ready = update_ready.clone();
+        let installable = installable.clone();
+        use_effect_with((), move |_| {
+            if let Err(error) = ServiceWorkerService::register(move || update_ready.set(true)) {
+                web_sys::console::log_2(&JsValue::from_str("App won't work offline"), &error);
+            }
+            if InstallPrompt::listen(move || installable.set(true)).is_err() {
+                web_sys::console::log_1(&JsValue::from_str("Install prompt is not available"));
+            }
+        });
+    }
+
+    let on_update = Callback::from(|_: MouseEvent| {
+        if let Err(error) = ServiceWorkerService::apply_update() {
+            web_sys::console::log_2(&JsValue::from_str("Update failed"), &error);
+        }
+    });
+    let on_install = {
+        let installable = installable.clone();
+        Callback::from(move |_: MouseEvent| {
+            if InstallPrompt::prompt().is_err() {
+                web_sys::console::log_1(&JsValue::from_str("Install prompt is not available"));
+            }
+            installable.set(false);
+        })
+    };
+
+    let button = "px-3 py-1 rounded-lg font-semibold bg-white text-emerald-700";
+    html! {
+        <>
+            if *update_ready {
+                <div class="flex items-center justify-between gap-2 p-2 text-sm text-white bg-emerald-600">
+                    <span>{"A new version is available."}</span>
+                    <button class={button} onclick={on_update}>{"Reload"}</button>
+                </div>
+            } else if *installable {
+                <div class="flex items-center justify-between gap-2 p-2 text-sm text-white bg-emerald-600">
+                    <span>{"Install the app to meditate offline."}</span>
+                    <button class={button} onclick={on_install}>{"Install"}</button>
+                </div>
+            }
+        </>
+    }
+}
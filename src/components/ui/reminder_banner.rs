@@ -0,0 +1,66 @@
+use gloo_timers::callback::Interval;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{
+    routes::Route,
+    utils::{
+        hooks::use_settings::use_settings,
+        settings::app_settings::{due_reminder, ReminderTime},
+    },
+};
+
+/// Time between checks for a reminder that came due.
+const CHECK_INTERVAL_MS: u32 = 20 * 1000;
+
+#[derive(PartialEq, Properties)]
+pub struct ReminderBannerProps {}
+
+/// Reminds to meditate at the profile's reminder times while the app is open. A reminder
+/// that came due while the device slept is shown when it wakes.
+#[function_component]
+pub fn ReminderBanner(props: &ReminderBannerProps) -> Html {
+    let ReminderBannerProps {} = props;
+
+    let settings = use_settings().settings;
+    let due = use_state(|| None::<ReminderTime>);
+
+    {
+        let due = due.clone();
+        use_effect_with(
+            (settings.timezone, settings.reminder_times.clone()),
+            move |(timezone, reminders)| {
+                let timezone = *timezone;
+                let reminders = reminders.clone();
+                let mut since = timezone.now();
+                let interval = Interval::new(CHECK_INTERVAL_MS, move || {
+                    let now = timezone.now();
+                    if let Some(time) = due_reminder(&reminders, since, now) {
+                        due.set(Some(time));
+                    }
+                    since = now;
+                });
+                move || drop(interval)
+            },
+        );
+    }
+
+    let Some(time) = *due else {
+        return Html::default();
+    };
+    let on_dismiss = {
+        let due = due.clone();
+        Callback::from(move |_: MouseEvent| due.set(None))
+    };
+    let button = "px-3 py-1 rounded-lg font-semibold bg-white text-gray-800";
+
+    html! {
+        <div class="flex items-center justify-between gap-2 p-2 text-sm text-white bg-emerald-600">
+            <span>{format!("It's {}, time to meditate.", time)}</span>
+            <div class="flex gap-2">
+                <Link<Route> to={Route::Timer} classes={classes!(button)}>{"Start Timer"}</Link<Route>>
+                <button class={button} onclick={on_dismiss}>{"Dismiss"}</button>
+            </div>
+        </div>
+    }
+}
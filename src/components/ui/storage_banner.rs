@@ -0,0 +1,70 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{
+    routes::Route,
+    services::local_storage::LocalStorageService,
+    utils::{
+        database::{
+            meditation_log::{
+                read_write_meditation_data::pending_meditation_count,
+                read_write_storage::storage_usage,
+            },
+            storage_quota::UsageLevel,
+        },
+        hooks::use_meditation_state::use_stats_aggregator,
+    },
+};
+
+#[derive(PartialEq, Properties)]
+pub struct StorageBannerProps {}
+
+/// Warns when data can't be saved at all, when storage is filling up, and when finished
+/// sessions are waiting for space.
+#[function_component]
+pub fn StorageBanner(props: &StorageBannerProps) -> Html {
+    let StorageBannerProps {} = props;
+    // Checked again whenever sessions are recorded or reloaded.
+    let _aggregator = use_stats_aggregator();
+
+    if LocalStorageService::check_available().is_err() {
+        return html! {
+            <div class="p-2 text-sm text-white bg-red-600">
+                {"This browser isn't letting the app save data, e.g. in a private window. Your sessions are kept only until you close the app."}
+            </div>
+        };
+    }
+
+    let pending = pending_meditation_count();
+    let usage = storage_usage();
+    let link = "px-3 py-1 rounded-lg font-semibold bg-white text-gray-800";
+
+    let (class, text) = if pending > 0 {
+        (
+            "bg-red-600",
+            format!(
+                "Storage is full. {} finished sessions are only kept until you close the app.",
+                pending
+            ),
+        )
+    } else {
+        match usage {
+            Some(usage) if usage.level() == UsageLevel::Critical => (
+                "bg-red-600",
+                "Storage is almost full; new sessions may not be saved.".to_string(),
+            ),
+            Some(usage) if usage.level() == UsageLevel::Warning => (
+                "bg-amber-500",
+                format!("Storage is {:.0}% full.", usage.fraction() * 100.0),
+            ),
+            _ => return Html::default(),
+        }
+    };
+
+    html! {
+        <div class={classes!("flex", "items-center", "justify-between", "gap-2", "p-2", "text-sm", "text-white", class)}>
+            <span>{text}</span>
+            <Link<Route> to={Route::Sync} classes={classes!(link)}>{"Free Up Space"}</Link<Route>>
+        </div>
+    }
+}
@@ -1,4 +1,5 @@
 pub mod components;
 pub mod pages;
+pub mod routes;
 pub mod services;
 pub mod utils;
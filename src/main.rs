@@ -1,6 +1,7 @@
 mod app;
 pub mod components;
 mod pages;
+mod routes;
 pub mod services;
 pub mod utils;
 use app::App;
@@ -0,0 +1,17 @@
+use crate::components::{
+    features::breathing_exercise::BreathingExercise, layouts::primary_layout::PrimaryLayout,
+    ui::title_banner::TitleBanner,
+};
+use yew::prelude::*;
+
+#[function_component]
+pub fn BreathingPage() -> Html {
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={"Breathe"} />
+                <BreathingExercise/>
+            </PrimaryLayout>
+        </>
+    }
+}
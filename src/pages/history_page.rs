@@ -0,0 +1,24 @@
+use crate::{
+    components::{
+        features::meditation_history::MeditationHistory, layouts::primary_layout::PrimaryLayout,
+        ui::title_banner::TitleBanner,
+    },
+    routes::Route,
+};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[function_component]
+pub fn HistoryPage() -> Html {
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={"History"} />
+                <div class="flex justify-end px-4 pt-2 text-sm">
+                    <Link<Route> to={Route::NewSession} classes="text-emerald-700 underline">{"Add a session"}</Link<Route>>
+                </div>
+                <MeditationHistory/>
+            </PrimaryLayout>
+        </>
+    }
+}
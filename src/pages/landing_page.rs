@@ -1,6 +1,13 @@
 use yew::prelude::*;
+use yew_router::prelude::*;
 
-use crate::components::layouts::primary_layout::PrimaryLayout;
+use crate::{
+    components::{
+        features::guided_library::GuidedLibrary, layouts::primary_layout::PrimaryLayout,
+        ui::title_banner::TitleBanner,
+    },
+    routes::Route,
+};
 
 #[derive(PartialEq, Properties)]
 pub struct LandingPageProps {}
@@ -8,10 +15,21 @@ pub struct LandingPageProps {}
 #[function_component]
 pub fn LandingPage(props: &LandingPageProps) -> Html {
     let LandingPageProps {} = props;
+    let link = "px-3 py-1 rounded-lg bg-white shadow-sm text-emerald-700";
     html! {
         <PrimaryLayout>
-            <h1>{"LandingPage"}</h1>
+            <TitleBanner title={"Welcome"} />
+            <div class="flex flex-wrap justify-center gap-2 p-2 text-sm">
+                <Link<Route> to={Route::Timer} classes={classes!(link)}>{"Timer"}</Link<Route>>
+                <Link<Route> to={Route::Breathing} classes={classes!(link)}>{"Breathe"}</Link<Route>>
+                <Link<Route> to={Route::History} classes={classes!(link)}>{"History"}</Link<Route>>
+                <Link<Route> to={Route::UserStats} classes={classes!(link)}>{"Stats"}</Link<Route>>
+                <Link<Route> to={Route::Sync} classes={classes!(link)}>{"Sync"}</Link<Route>>
+                <Link<Route> to={Route::Privacy} classes={classes!(link)}>{"Privacy"}</Link<Route>>
+                <Link<Route> to={Route::Profiles} classes={classes!(link)}>{"Profiles"}</Link<Route>>
+                <Link<Route> to={Route::Settings} classes={classes!(link)}>{"Settings"}</Link<Route>>
+            </div>
+            <GuidedLibrary/>
         </PrimaryLayout>
-
     }
 }
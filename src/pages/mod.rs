@@ -1,3 +1,12 @@
+pub mod breathing_page;
+pub mod history_page;
 pub mod landing_page;
+pub mod privacy_page;
+pub mod profiles_page;
+pub mod session_form_page;
+pub mod settings_page;
+pub mod sync_page;
+pub mod tags_page;
 pub mod test_page;
+pub mod timer_page;
 pub mod user_stats_page;
@@ -0,0 +1,17 @@
+use crate::components::{
+    features::encryption_panel::EncryptionPanel, layouts::primary_layout::PrimaryLayout,
+    ui::title_banner::TitleBanner,
+};
+use yew::prelude::*;
+
+#[function_component]
+pub fn PrivacyPage() -> Html {
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={"Privacy"} />
+                <EncryptionPanel/>
+            </PrimaryLayout>
+        </>
+    }
+}
@@ -0,0 +1,17 @@
+use crate::components::{
+    features::profile_manager::ProfileManager, layouts::primary_layout::PrimaryLayout,
+    ui::title_banner::TitleBanner,
+};
+use yew::prelude::*;
+
+#[function_component]
+pub fn ProfilesPage() -> Html {
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={"Profiles"} />
+                <ProfileManager/>
+            </PrimaryLayout>
+        </>
+    }
+}
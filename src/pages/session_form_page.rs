@@ -0,0 +1,59 @@
+use crate::{
+    components::{
+        features::meditation_form::MeditationForm,
+        layouts::primary_layout::PrimaryLayout,
+        ui::{card::Card, title_banner::TitleBanner},
+    },
+    utils::database::meditation_log::{
+        meditation_data_builder::MeditationData, read_write_meditation_data::find_meditation,
+    },
+};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct SessionFormPageProps {
+    /// Id of the session to edit; `None` to add a new session.
+    #[prop_or_default]
+    pub id: Option<String>,
+}
+
+#[function_component]
+pub fn SessionFormPage(props: &SessionFormPageProps) -> Html {
+    let SessionFormPageProps { id } = props;
+
+    let editing = {
+        let id = id.clone();
+        use_state(move || id.and_then(|id| find_meditation(&id)))
+    };
+    let navigator = use_navigator();
+
+    // After an edit, go back to wherever the session was opened from.
+    let on_saved = {
+        let editing = editing.is_some();
+        Callback::from(move |_: MeditationData| {
+            if let (true, Some(navigator)) = (editing, &navigator) {
+                navigator.back();
+            }
+        })
+    };
+
+    html! {
+        <>
+            <PrimaryLayout>
+                if id.is_some() {
+                    <TitleBanner title={"Edit Session"} />
+                } else {
+                    <TitleBanner title={"Add Session"} />
+                }
+                if id.is_some() && editing.is_none() {
+                    <Card>
+                        <div class="text-sm text-gray-600">{"This session no longer exists."}</div>
+                    </Card>
+                } else {
+                    <MeditationForm editing={(*editing).clone()} {on_saved} />
+                }
+            </PrimaryLayout>
+        </>
+    }
+}
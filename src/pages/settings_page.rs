@@ -0,0 +1,17 @@
+use crate::components::{
+    features::settings_form::SettingsForm, layouts::primary_layout::PrimaryLayout,
+    ui::title_banner::TitleBanner,
+};
+use yew::prelude::*;
+
+#[function_component]
+pub fn SettingsPage() -> Html {
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={"Settings"} />
+                <SettingsForm/>
+            </PrimaryLayout>
+        </>
+    }
+}
@@ -0,0 +1,20 @@
+use crate::components::{
+    features::{backup_panel::BackupPanel, storage_panel::StoragePanel, sync_panel::SyncPanel},
+    layouts::primary_layout::PrimaryLayout,
+    ui::title_banner::TitleBanner,
+};
+use yew::prelude::*;
+
+#[function_component]
+pub fn SyncPage() -> Html {
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={"Sync & Storage"} />
+                <SyncPanel/>
+                <BackupPanel/>
+                <StoragePanel/>
+            </PrimaryLayout>
+        </>
+    }
+}
@@ -0,0 +1,17 @@
+use crate::components::{
+    features::tag_manager::TagManager, layouts::primary_layout::PrimaryLayout,
+    ui::title_banner::TitleBanner,
+};
+use yew::prelude::*;
+
+#[function_component]
+pub fn TagsPage() -> Html {
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={"Tags"} />
+                <TagManager/>
+            </PrimaryLayout>
+        </>
+    }
+}
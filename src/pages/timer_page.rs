@@ -0,0 +1,32 @@
+use crate::{
+    components::{
+        features::meditation_timer::MeditationTimer, layouts::primary_layout::PrimaryLayout,
+        ui::title_banner::TitleBanner,
+    },
+    utils::library::guided_meditations::find_guided_meditation,
+};
+use yew::prelude::*;
+
+#[derive(PartialEq, Properties)]
+pub struct TimerPageProps {
+    /// The id of a library meditation to start; `None` opens the plain timer.
+    #[prop_or_default]
+    pub guided: Option<String>,
+}
+
+#[function_component]
+pub fn TimerPage(props: &TimerPageProps) -> Html {
+    let TimerPageProps { guided } = props;
+    let meditation = guided.as_deref().and_then(find_guided_meditation);
+    html! {
+        <>
+            <PrimaryLayout>
+                <TitleBanner title={meditation.map_or("Meditate", |meditation| meditation.title)} />
+                if guided.is_some() && meditation.is_none() {
+                    <div class="text-center text-sm text-gray-600">{"That guided meditation isn't in the library."}</div>
+                }
+                <MeditationTimer key={guided.clone().unwrap_or_default()} guided={meditation} />
+            </PrimaryLayout>
+        </>
+    }
+}
@@ -1,5 +1,9 @@
 use crate::components::{
-    features::user_stats::UserStats, layouts::primary_layout::PrimaryLayout,
+    features::{
+        mood_impact::MoodImpact, practice_heatmap::PracticeHeatmap, stats_charts::StatsCharts,
+        tag_stats::TagStats, user_stats::UserStats,
+    },
+    layouts::primary_layout::PrimaryLayout,
     ui::title_banner::TitleBanner,
 };
 use yew::prelude::*;
@@ -11,6 +15,10 @@ pub fn UserStatsPage() -> Html {
             <PrimaryLayout>
                 <TitleBanner title={"Stats"} />
                 <UserStats/>
+                <PracticeHeatmap/>
+                <StatsCharts/>
+                <MoodImpact/>
+                <TagStats/>
             </PrimaryLayout>
         </>
     }
@@ -0,0 +1,34 @@
+use yew_router::prelude::*;
+
+#[derive(Clone, Routable, PartialEq)]
+pub enum Route {
+    #[at("/")]
+    Home,
+    #[at("/user-stats")]
+    UserStats,
+    #[at("/timer")]
+    Timer,
+    #[at("/timer/:id")]
+    GuidedTimer { id: String },
+    #[at("/breathe")]
+    Breathing,
+    #[at("/history")]
+    History,
+    #[at("/sessions/new")]
+    NewSession,
+    #[at("/sessions/:id/edit")]
+    EditSession { id: String },
+    #[at("/tags")]
+    Tags,
+    #[at("/sync")]
+    Sync,
+    #[at("/privacy")]
+    Privacy,
+    #[at("/profiles")]
+    Profiles,
+    #[at("/settings")]
+    Settings,
+    #[at("/test")] // Add a route for the Test page
+    Test,
+    // Add more routes here if needed
+}
@@ -0,0 +1,84 @@
+use std::cell::RefCell;
+
+use web_sys::{wasm_bindgen::JsValue, AudioContext, AudioScheduledSourceNode, OscillatorType};
+
+/// Frequency of the bell's fundamental in Hz.
+const FUNDAMENTAL: f32 = 220.0;
+/// Inharmonic partials of a singing bowl as (frequency ratio, relative volume).
+const PARTIALS: [(f32, f32); 4] = [(1.0, 0.5), (2.76, 0.25), (5.4, 0.12), (8.93, 0.06)];
+/// Seconds until a strike has faded out.
+const DECAY: f64 = 6.0;
+/// Seconds between strikes when the bell is rung more than once.
+const STRIKE_SPACING: f64 = 2.5;
+
+thread_local! {
+    static AUDIO_CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// A service that synthesizes meditation bells with the Web Audio API.
+///
+/// The sound is built from a few decaying sine partials, so no audio files need to be
+/// downloaded and the bell works offline.
+pub struct BellSound;
+
+impl BellSound {
+    /// Runs `f` with the shared `AudioContext`, creating it on first use.
+    fn with_context<T>(f: impl FnOnce(&AudioContext) -> Result<T, JsValue>) -> Result<T, JsValue> {
+        AUDIO_CONTEXT.with(|cell| {
+            let mut cell = cell.borrow_mut();
+            let context = match cell.as_ref() {
+                Some(context) => context,
+                None => cell.insert(AudioContext::new()?),
+            };
+            f(context)
+        })
+    }
+
+    /// Creates or resumes the audio context.
+    ///
+    /// Browsers only allow audio to start from a user gesture, so call this from the click
+    /// handler that starts a session; later bells can then ring from timer callbacks.
+    ///
+    /// # Returns
+    /// A `Result<(), JsValue>`: `Err(JsValue)` if Web Audio is not available.
+    pub fn unlock() -> Result<(), JsValue> {
+        Self::with_context(|context| context.resume().map(|_| ()))
+    }
+
+    /// Strikes the bell once, starting `delay` seconds from now.
+    fn strike(context: &AudioContext, delay: f64) -> Result<(), JsValue> {
+        let start = context.current_time() + delay;
+
+        for (ratio, volume) in PARTIALS {
+            let oscillator = context.create_oscillator()?;
+            oscillator.set_type(OscillatorType::Sine);
+            oscillator.frequency().set_value(FUNDAMENTAL * ratio);
+
+            let gain = context.create_gain()?;
+            gain.gain().set_value_at_time(0.0, start)?;
+            gain.gain()
+                .linear_ramp_to_value_at_time(volume, start + 0.01)?;
+            // Higher partials fade faster, like a real bowl.
+            gain.gain()
+                .exponential_ramp_to_value_at_time(0.0001, start + DECAY / ratio.sqrt() as f64)?;
+
+            oscillator.connect_with_audio_node(&gain)?;
+            gain.connect_with_audio_node(&context.destination())?;
+
+            let source: &AudioScheduledSourceNode = &oscillator;
+            source.start_with_when(start)?;
+            source.stop_with_when(start + DECAY)?;
+        }
+        Ok(())
+    }
+
+    /// Rings the bell `strikes` times.
+    ///
+    /// # Returns
+    /// A `Result<(), JsValue>`: `Err(JsValue)` if Web Audio is not available.
+    pub fn ring(strikes: u32) -> Result<(), JsValue> {
+        Self::with_context(|context| {
+            (0..strikes).try_for_each(|i| Self::strike(context, i as f64 * STRIKE_SPACING))
+        })
+    }
+}
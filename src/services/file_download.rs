@@ -0,0 +1,42 @@
+use web_sys::{
+    js_sys::Array,
+    wasm_bindgen::{JsCast, JsValue},
+    window, Blob, BlobPropertyBag, HtmlAnchorElement, Url,
+};
+
+/// A service that hands generated files to the browser as downloads.
+pub struct FileDownload;
+
+impl FileDownload {
+    /// Offers `contents` as a file download.
+    ///
+    /// # Parameters
+    /// - `file_name`: The suggested name of the downloaded file.
+    /// - `mime_type`: The media type of the file, e.g. `"text/csv"`.
+    /// - `contents`: The text of the file.
+    ///
+    /// # Returns
+    /// A `Result<(), JsValue>`:
+    /// - `Ok(())` once the download was started.
+    /// - `Err(JsValue)` if there is no document or the browser refuses to create the file.
+    pub fn save_text(file_name: &str, mime_type: &str, contents: &str) -> Result<(), JsValue> {
+        let document = window()
+            .and_then(|window| window.document())
+            .ok_or("no global `document` exists")?;
+
+        let mut options = BlobPropertyBag::new();
+        options.type_(mime_type);
+        let blob = Blob::new_with_str_sequence_and_options(
+            &Array::of1(&JsValue::from_str(contents)),
+            &options,
+        )?;
+        let url = Url::create_object_url_with_blob(&blob)?;
+
+        let anchor: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+        anchor.set_href(&url);
+        anchor.set_download(file_name);
+        anchor.click();
+
+        Url::revoke_object_url(&url)
+    }
+}
@@ -0,0 +1,35 @@
+use web_sys::{
+    wasm_bindgen::{closure::Closure, JsValue},
+    File,
+};
+
+/// A service that reads files the user picked in a file input.
+pub struct FileUpload;
+
+impl FileUpload {
+    /// Reads `file` as text.
+    ///
+    /// # Parameters
+    /// - `file`: A file from an `<input type="file">`.
+    /// - `on_done`: Called once with the text, or the error if the file can't be read.
+    pub fn read_text(file: &File, on_done: impl FnOnce(Result<String, JsValue>) + 'static) {
+        let on_done = std::rc::Rc::new(std::cell::RefCell::new(Some(on_done)));
+        let finish = move |result: Result<String, JsValue>| {
+            if let Some(on_done) = on_done.borrow_mut().take() {
+                on_done(result);
+            }
+        };
+        let on_failed_finish = finish.clone();
+
+        let on_text = Closure::once(move |text: JsValue| {
+            finish(
+                text.as_string()
+                    .ok_or_else(|| JsValue::from_str("not text")),
+            )
+        });
+        let on_failed = Closure::once(move |error: JsValue| on_failed_finish(Err(error)));
+        let _ = file.text().then2(&on_text, &on_failed);
+        on_text.forget();
+        on_failed.forget();
+    }
+}
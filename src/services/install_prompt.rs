@@ -0,0 +1,54 @@
+use std::cell::RefCell;
+
+use web_sys::{
+    js_sys::{Function, Reflect},
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    window, Event,
+};
+
+thread_local! {
+    static DEFERRED_PROMPT: RefCell<Option<Event>> = const { RefCell::new(None) };
+}
+
+/// A service that offers to install the app from the page instead of the browser menu.
+///
+/// Browsers that support installation fire `beforeinstallprompt` when the app is
+/// installable; the event is kept so the prompt can be shown from a button later.
+pub struct InstallPrompt;
+
+impl InstallPrompt {
+    /// Starts listening for the browser's install prompt.
+    ///
+    /// # Parameters
+    /// - `on_available`: Called when the app can be installed.
+    ///
+    /// # Returns
+    /// A `Result<(), JsValue>`; `Err(JsValue)` if there is no window.
+    pub fn listen(on_available: impl Fn() + 'static) -> Result<(), JsValue> {
+        let window = window().ok_or("no global `window` exists")?;
+        let on_prompt = Closure::<dyn FnMut(Event)>::new(move |event: Event| {
+            // Stops the browser's own mini-infobar; the app shows its button instead.
+            event.prevent_default();
+            DEFERRED_PROMPT.with(|cell| cell.replace(Some(event)));
+            on_available();
+        });
+        window.add_event_listener_with_callback(
+            "beforeinstallprompt",
+            on_prompt.as_ref().unchecked_ref(),
+        )?;
+        on_prompt.forget();
+        Ok(())
+    }
+
+    /// Shows the install prompt. Each prompt can be shown only once.
+    ///
+    /// # Returns
+    /// A `Result<(), JsValue>`; `Err(JsValue)` if no prompt is available.
+    pub fn prompt() -> Result<(), JsValue> {
+        let event = DEFERRED_PROMPT
+            .with(|cell| cell.take())
+            .ok_or("the app can't be installed right now")?;
+        let prompt: Function = Reflect::get(&event, &JsValue::from_str("prompt"))?.dyn_into()?;
+        prompt.call0(&event).map(|_| ())
+    }
+}
@@ -1,12 +1,109 @@
-use web_sys::{wasm_bindgen::JsValue, window};
+use std::{cell::RefCell, collections::BTreeMap, fmt};
+
+use serde::{de::DeserializeOwned, Serialize};
+use web_sys::{
+    js_sys::Reflect,
+    wasm_bindgen::{JsCast, JsValue},
+    window, Storage,
+};
+
+/// Reasons local storage couldn't be used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StorageError {
+    /// The browser doesn't let the site use local storage, e.g. in private mode, a sandboxed
+    /// iframe or a worker.
+    Unavailable,
+    /// The site has used up its share of local storage.
+    QuotaExceeded,
+    /// The stored value can't be read, e.g. because a newer version of the app wrote it.
+    Unreadable(String),
+    /// Any other failure reported by the browser.
+    Failed(String),
+}
+
+impl StorageError {
+    /// Classifies an error thrown by the Storage API.
+    pub fn from_js(error: &JsValue) -> Self {
+        let name = Reflect::get(error, &JsValue::from_str("name"))
+            .ok()
+            .and_then(|name| name.as_string());
+        match name.as_deref() {
+            // Firefox used its own name before adopting the standard one.
+            Some("QuotaExceededError") | Some("NS_ERROR_DOM_QUOTA_REACHED") => {
+                StorageError::QuotaExceeded
+            }
+            _ => StorageError::Failed(
+                error
+                    .dyn_ref::<web_sys::js_sys::Error>()
+                    .map(|error| String::from(error.message()))
+                    .or_else(|| error.as_string())
+                    .unwrap_or_else(|| format!("{:?}", error)),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Unavailable => f.write_str("storage is not available"),
+            StorageError::QuotaExceeded => f.write_str("storage is full"),
+            StorageError::Unreadable(message) => {
+                write!(f, "stored data can't be read: {}", message)
+            }
+            StorageError::Failed(message) => write!(f, "storage failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// Where values are kept: the browser's local storage, or memory when it is unavailable.
+enum Backend {
+    Browser(Storage),
+    Memory,
+}
+
+thread_local! {
+    /// Decided on first use, so values don't move between backends halfway through a visit.
+    static BACKEND: Backend = browser_storage().map_or(Backend::Memory, Backend::Browser);
+    /// Values kept for this visit only, while local storage is unavailable.
+    static MEMORY: RefCell<BTreeMap<String, String>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+fn browser_storage() -> Result<Storage, StorageError> {
+    let window = window().ok_or(StorageError::Unavailable)?;
+    // Throws a SecurityError when storage is blocked, and is null where it doesn't exist.
+    window
+        .local_storage()
+        .ok()
+        .flatten()
+        .ok_or(StorageError::Unavailable)
+}
 
 /// A service for interacting with the browser's local storage.
 ///
 /// The `LocalStorageService` provides methods to create, read, update, and delete key-value pairs in the browser's local storage.
 /// This struct uses the `web_sys` crate to interface with Web APIs.
+///
+/// Where the browser doesn't allow local storage, values are kept in memory instead, so the
+/// app keeps working but forgets them when it is closed; `check_available` reports this.
 pub struct LocalStorageService;
 
 impl LocalStorageService {
+    /// Reports whether values are kept in the browser's local storage.
+    ///
+    /// # Returns
+    /// A `Result<(), StorageError>`:
+    /// - `Ok(())` if values persist.
+    /// - `Err(StorageError::Unavailable)` if they are only kept in memory for this visit.
+    pub fn check_available() -> Result<(), StorageError> {
+        BACKEND.with(|backend| match backend {
+            Backend::Browser(_) => Ok(()),
+            Backend::Memory => Err(StorageError::Unavailable),
+        })
+    }
+
     /// Stores a key-value pair in the local storage.
     ///
     /// # Parameters
@@ -14,17 +111,23 @@ impl LocalStorageService {
     /// - `value`: A string slice representing the value to be stored.
     ///
     /// # Returns
-    /// A `Result<(), JsValue>`:
+    /// A `Result<(), StorageError>`:
     /// - `Ok(())` if the operation is successful.
-    /// - `Err(JsValue)` if an error occurs, e.g., if the `window` or `local_storage` is not available.
-    ///
-    /// # Panics
-    /// Panics if the global `window` object does not exist.
-    pub fn create(key: &str, value: &str) -> Result<(), JsValue> {
-        let window = window().expect("no global `window` exists");
-        let local_storage = window.local_storage()?.expect("no local storage");
-
-        local_storage.set_item(key, value)
+    /// - `Err(StorageError)` if an error occurs, e.g. `StorageError::QuotaExceeded`.
+    pub fn create(key: &str, value: &str) -> Result<(), StorageError> {
+        BACKEND.with(|backend| match backend {
+            Backend::Browser(storage) => storage
+                .set_item(key, value)
+                .map_err(|error| StorageError::from_js(&error)),
+            Backend::Memory => {
+                MEMORY.with(|memory| {
+                    memory
+                        .borrow_mut()
+                        .insert(key.to_string(), value.to_string())
+                });
+                Ok(())
+            }
+        })
     }
 
     /// Retrieves a value from local storage by key.
@@ -33,18 +136,17 @@ impl LocalStorageService {
     /// - `key`: A string slice representing the key to retrieve.
     ///
     /// # Returns
-    /// A `Result<Option<String>, JsValue>`:
+    /// A `Result<Option<String>, StorageError>`:
     /// - `Ok(Some(String))` if the key exists with its corresponding value.
     /// - `Ok(None)` if the key does not exist.
-    /// - `Err(JsValue)` if an error occurs.
-    ///
-    /// # Panics
-    /// Panics if the global `window` object does not exist.
-    pub fn read(key: &str) -> Result<Option<String>, JsValue> {
-        let window = window().expect("no global `window` exists");
-        let local_storage = window.local_storage()?.expect("no local storage");
-
-        local_storage.get_item(key)
+    /// - `Err(StorageError)` if an error occurs.
+    pub fn read(key: &str) -> Result<Option<String>, StorageError> {
+        BACKEND.with(|backend| match backend {
+            Backend::Browser(storage) => storage
+                .get_item(key)
+                .map_err(|error| StorageError::from_js(&error)),
+            Backend::Memory => Ok(MEMORY.with(|memory| memory.borrow().get(key).cloned())),
+        })
     }
 
     /// Removes a key-value pair from local storage.
@@ -53,16 +155,210 @@ impl LocalStorageService {
     /// - `key`: A string slice representing the key to be removed.
     ///
     /// # Returns
-    /// A `Result<(), JsValue>`:
+    /// A `Result<(), StorageError>`:
+    /// - `Ok(())` if the operation is successful.
+    /// - `Err(StorageError)` if an error occurs.
+    pub fn delete(key: &str) -> Result<(), StorageError> {
+        BACKEND.with(|backend| match backend {
+            Backend::Browser(storage) => storage
+                .remove_item(key)
+                .map_err(|error| StorageError::from_js(&error)),
+            Backend::Memory => {
+                MEMORY.with(|memory| memory.borrow_mut().remove(key));
+                Ok(())
+            }
+        })
+    }
+
+    /// Returns the namespace `name`, a part of local storage with its own keys.
+    pub fn namespace(name: &str) -> StorageNamespace {
+        StorageNamespace::new(name)
+    }
+
+    /// Lists the keys in local storage.
+    ///
+    /// # Returns
+    /// A `Result<Vec<String>, StorageError>`:
+    /// - `Ok(Vec<String>)` with every key, sorted.
+    /// - `Err(StorageError)` if an error occurs.
+    pub fn keys() -> Result<Vec<String>, StorageError> {
+        BACKEND.with(|backend| match backend {
+            Backend::Browser(storage) => {
+                let js_error = |error: JsValue| StorageError::from_js(&error);
+                let mut keys = Vec::new();
+                for index in 0..storage.length().map_err(js_error)? {
+                    keys.extend(storage.key(index).map_err(js_error)?);
+                }
+                keys.sort();
+                Ok(keys)
+            }
+            Backend::Memory => Ok(MEMORY.with(|memory| memory.borrow().keys().cloned().collect())),
+        })
+    }
+
+    /// Removes every key-value pair whose key starts with `prefix`.
+    ///
+    /// # Parameters
+    /// - `prefix`: The start of the keys to remove, e.g. `StorageNamespace::prefix`.
+    ///
+    /// # Returns
+    /// A `Result<usize, StorageError>`:
+    /// - `Ok(usize)` with the number of pairs removed.
+    /// - `Err(StorageError)` if an error occurs; pairs removed before it stay removed.
+    pub fn clear_namespace(prefix: &str) -> Result<usize, StorageError> {
+        let keys: Vec<String> = Self::keys()?
+            .into_iter()
+            .filter(|key| key.starts_with(prefix))
+            .collect();
+        for key in &keys {
+            Self::delete(key)?;
+        }
+        Ok(keys.len())
+    }
+
+    /// Retrieves a value stored as JSON.
+    ///
+    /// # Parameters
+    /// - `key`: A string slice representing the key to retrieve.
+    ///
+    /// # Returns
+    /// A `Result<Option<T>, StorageError>`:
+    /// - `Ok(Some(T))` if the key exists and holds a `T`.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StorageError::Unreadable)` if the value isn't a `T`, or another error if reading fails.
+    pub fn get_json<T: DeserializeOwned>(key: &str) -> Result<Option<T>, StorageError> {
+        Self::read(key)?
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(|error| StorageError::Unreadable(error.to_string()))
+    }
+
+    /// Stores a value as JSON.
+    ///
+    /// # Parameters
+    /// - `key`: A string slice representing the key.
+    /// - `value`: The value to be stored.
+    ///
+    /// # Returns
+    /// A `Result<(), StorageError>`:
     /// - `Ok(())` if the operation is successful.
-    /// - `Err(JsValue)` if an error occurs.
+    /// - `Err(StorageError)` if serialization or storing fails.
+    pub fn set_json<T: Serialize + ?Sized>(key: &str, value: &T) -> Result<(), StorageError> {
+        let value = serde_json::to_string(value)
+            .map_err(|error| StorageError::Failed(format!("serialization failed: {}", error)))?;
+        Self::create(key, &value)
+    }
+
+    /// Estimates how much of the local storage quota is in use.
+    ///
+    /// # Returns
+    /// A `Result<u64, StorageError>`:
+    /// - `Ok(u64)` with the bytes taken by all keys and values, stored as UTF-16.
+    /// - `Err(StorageError)` if an error occurs.
+    pub fn used_bytes() -> Result<u64, StorageError> {
+        BACKEND.with(|backend| match backend {
+            Backend::Browser(storage) => {
+                let js_error = |error: JsValue| StorageError::from_js(&error);
+                let mut used = 0;
+                for index in 0..storage.length().map_err(js_error)? {
+                    if let Some(key) = storage.key(index).map_err(js_error)? {
+                        let value = storage
+                            .get_item(&key)
+                            .map_err(js_error)?
+                            .unwrap_or_default();
+                        used += stored_bytes(&key, &value);
+                    }
+                }
+                Ok(used)
+            }
+            Backend::Memory => Ok(MEMORY.with(|memory| {
+                memory
+                    .borrow()
+                    .iter()
+                    .map(|(key, value)| stored_bytes(key, value))
+                    .sum()
+            })),
+        })
+    }
+}
+
+/// A part of local storage whose keys all start with the same prefix, so e.g. test runs and
+/// profiles can each keep a `meditationLog` without colliding.
+///
+/// Keys passed to its methods are relative to the namespace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageNamespace {
+    prefix: String,
+}
+
+impl StorageNamespace {
+    /// Separates the namespace name from the keys inside it.
+    const SEPARATOR: char = ':';
+
+    /// Creates the namespace `name`, whose keys are stored as `name:key`.
+    pub fn new(name: &str) -> Self {
+        StorageNamespace {
+            prefix: format!("{}{}", name, Self::SEPARATOR),
+        }
+    }
+
+    /// The start of every key stored in this namespace.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// The full local storage key for `key` in this namespace.
+    pub fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    /// Stores a key-value pair in this namespace; see `LocalStorageService::create`.
+    pub fn create(&self, key: &str, value: &str) -> Result<(), StorageError> {
+        LocalStorageService::create(&self.key(key), value)
+    }
+
+    /// Retrieves a value from this namespace; see `LocalStorageService::read`.
+    pub fn read(&self, key: &str) -> Result<Option<String>, StorageError> {
+        LocalStorageService::read(&self.key(key))
+    }
+
+    /// Removes a key-value pair from this namespace; see `LocalStorageService::delete`.
+    pub fn delete(&self, key: &str) -> Result<(), StorageError> {
+        LocalStorageService::delete(&self.key(key))
+    }
+
+    /// Lists the keys in this namespace, without the prefix, sorted.
+    pub fn keys(&self) -> Result<Vec<String>, StorageError> {
+        Ok(LocalStorageService::keys()?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(&self.prefix).map(str::to_string))
+            .collect())
+    }
+
+    /// Removes every key-value pair in this namespace.
     ///
-    /// # Panics
-    /// Panics if the global `window` object does not exist.
-    pub fn delete(key: &str) -> Result<(), JsValue> {
-        let window = window().expect("no global `window` exists");
-        let local_storage = window.local_storage()?.expect("no local storage");
+    /// # Returns
+    /// The number of pairs removed.
+    pub fn clear(&self) -> Result<usize, StorageError> {
+        LocalStorageService::clear_namespace(&self.prefix)
+    }
+
+    /// Retrieves a value stored as JSON; see `LocalStorageService::get_json`.
+    pub fn get_json<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, StorageError> {
+        LocalStorageService::get_json(&self.key(key))
+    }
 
-        local_storage.remove_item(key)
+    /// Stores a value as JSON; see `LocalStorageService::set_json`.
+    pub fn set_json<T: Serialize + ?Sized>(
+        &self,
+        key: &str,
+        value: &T,
+    ) -> Result<(), StorageError> {
+        LocalStorageService::set_json(&self.key(key), value)
     }
 }
+
+/// The bytes a key and value take in local storage, which keeps strings as UTF-16.
+pub fn stored_bytes(key: &str, value: &str) -> u64 {
+    (key.encode_utf16().count() + value.encode_utf16().count()) as u64 * 2
+}
@@ -1 +1,8 @@
+pub mod bell_sound;
+pub mod file_download;
+pub mod file_upload;
+pub mod install_prompt;
 pub mod local_storage;
+pub mod secure_random;
+pub mod service_worker;
+pub mod sync_http;
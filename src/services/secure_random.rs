@@ -0,0 +1,21 @@
+use web_sys::{wasm_bindgen::JsValue, window};
+
+/// A service for cryptographically secure random bytes, from the browser's Web Crypto API.
+pub struct SecureRandom;
+
+impl SecureRandom {
+    /// Returns `N` random bytes.
+    ///
+    /// # Returns
+    /// A `Result<[u8; N], JsValue>`:
+    /// - `Ok([u8; N])` with the random bytes.
+    /// - `Err(JsValue)` if Web Crypto is not available.
+    pub fn bytes<const N: usize>() -> Result<[u8; N], JsValue> {
+        let window = window().ok_or_else(|| JsValue::from_str("no global `window` exists"))?;
+        let mut bytes = [0; N];
+        window
+            .crypto()?
+            .get_random_values_with_u8_array(&mut bytes)?;
+        Ok(bytes)
+    }
+}
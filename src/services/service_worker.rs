@@ -0,0 +1,118 @@
+use std::{cell::RefCell, rc::Rc};
+
+use web_sys::{
+    js_sys::Reflect,
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    window, ServiceWorkerContainer, ServiceWorkerRegistration, ServiceWorkerState,
+};
+
+/// The worker script, copied to the root of `dist` so it controls every route.
+const SCRIPT_URL: &str = "/sw.js";
+/// Tells a waiting worker to take over; handled in `assets/pwa/sw.js`.
+const SKIP_WAITING: &str = "SKIP_WAITING";
+
+thread_local! {
+    static REGISTRATION: RefCell<Option<ServiceWorkerRegistration>> = const { RefCell::new(None) };
+}
+
+/// A service that installs the offline service worker and applies new builds.
+pub struct ServiceWorkerService;
+
+impl ServiceWorkerService {
+    fn container() -> Result<ServiceWorkerContainer, JsValue> {
+        let navigator = window().ok_or("no global `window` exists")?.navigator();
+        // Missing on insecure origins and in some private browsing modes.
+        if !Reflect::has(&navigator, &JsValue::from_str("serviceWorker"))? {
+            return Err("service workers are not supported".into());
+        }
+        Ok(navigator.service_worker())
+    }
+
+    /// Registers the service worker and watches for new builds.
+    ///
+    /// # Parameters
+    /// - `on_update`: Called when a new build has been downloaded and is waiting to take over.
+    ///
+    /// # Returns
+    /// A `Result<(), JsValue>`:
+    /// - `Ok(())` once registration has started; failures after that are logged to the console.
+    /// - `Err(JsValue)` if the browser has no service worker support.
+    pub fn register(on_update: impl Fn() + 'static) -> Result<(), JsValue> {
+        let container = Self::container()?;
+        let on_update: Rc<dyn Fn()> = Rc::new(on_update);
+
+        let watched_container = container.clone();
+        let on_registered = Closure::once(move |registration: JsValue| {
+            let registration: ServiceWorkerRegistration = registration.unchecked_into();
+            Self::watch(&registration, watched_container, on_update);
+            REGISTRATION.with(|cell| cell.replace(Some(registration)));
+        });
+        let on_failed = Closure::once(|error: JsValue| {
+            web_sys::console::log_2(&JsValue::from_str("Service worker not registered"), &error);
+        });
+        let _ = container
+            .register(SCRIPT_URL)
+            .then2(&on_registered, &on_failed);
+        on_registered.forget();
+        on_failed.forget();
+        Ok(())
+    }
+
+    fn watch(
+        registration: &ServiceWorkerRegistration,
+        container: ServiceWorkerContainer,
+        on_update: Rc<dyn Fn()>,
+    ) {
+        // Without a controller the page was loaded before any worker existed, so the worker
+        // being installed is the first one rather than an update.
+        if registration.waiting().is_some() && container.controller().is_some() {
+            on_update();
+        }
+
+        let found = registration.clone();
+        let on_update_found = Closure::<dyn FnMut()>::new(move || {
+            let Some(worker) = found.installing() else {
+                return;
+            };
+            let installed = worker.clone();
+            let container = container.clone();
+            let on_update = on_update.clone();
+            let on_state_change = Closure::<dyn FnMut()>::new(move || {
+                if installed.state() == ServiceWorkerState::Installed
+                    && container.controller().is_some()
+                {
+                    on_update();
+                }
+            });
+            worker.set_onstatechange(Some(on_state_change.as_ref().unchecked_ref()));
+            on_state_change.forget();
+        });
+        registration.set_onupdatefound(Some(on_update_found.as_ref().unchecked_ref()));
+        on_update_found.forget();
+    }
+
+    /// Activates the waiting build and reloads the page once it has taken over.
+    ///
+    /// # Returns
+    /// A `Result<(), JsValue>`:
+    /// - `Ok(())` once the waiting worker was asked to take over.
+    /// - `Err(JsValue)` if no update is waiting.
+    pub fn apply_update() -> Result<(), JsValue> {
+        let container = Self::container()?;
+        let waiting = REGISTRATION
+            .with(|cell| {
+                cell.borrow()
+                    .as_ref()
+                    .and_then(|registration| registration.waiting())
+            })
+            .ok_or("no service worker update is waiting")?;
+
+        let reload = Closure::once_into_js(|| {
+            if let Some(window) = window() {
+                let _ = window.location().reload();
+            }
+        });
+        container.set_oncontrollerchange(Some(reload.unchecked_ref()));
+        waiting.post_message(&JsValue::from_str(SKIP_WAITING))
+    }
+}
@@ -0,0 +1,111 @@
+use std::{cell::RefCell, fmt, rc::Rc};
+
+use web_sys::{
+    js_sys::{Object, Reflect},
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    window, RequestInit, Response,
+};
+
+/// Reasons an HTTP request to the sync server failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HttpError {
+    /// The server couldn't be reached, e.g. because the device is offline.
+    Network(String),
+    /// The server answered with a status other than 2xx.
+    Status(u16),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::Network(message) => write!(f, "server unreachable: {}", message),
+            HttpError::Status(status) => write!(f, "server answered with status {}", status),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+type Finish = Rc<dyn Fn(Result<String, HttpError>)>;
+
+fn network_error(error: JsValue) -> HttpError {
+    HttpError::Network(error.as_string().unwrap_or_else(|| format!("{:?}", error)))
+}
+
+fn on_failure(finish: Finish) -> Closure<dyn FnMut(JsValue)> {
+    Closure::once(move |error: JsValue| finish(Err(network_error(error))))
+}
+
+/// A service that exchanges JSON with the sync server using `fetch`.
+pub struct SyncHttp;
+
+impl SyncHttp {
+    /// Sends a request and passes the response body to `on_done`.
+    ///
+    /// # Parameters
+    /// - `method`: The HTTP method, e.g. `"POST"`.
+    /// - `url`: The full URL of the request.
+    /// - `body`: Optional. A JSON request body.
+    /// - `on_done`: Called once with the response body, or the reason the request failed.
+    pub fn send(
+        method: &str,
+        url: &str,
+        body: Option<&str>,
+        on_done: impl FnOnce(Result<String, HttpError>) + 'static,
+    ) {
+        let on_done = RefCell::new(Some(on_done));
+        let finish: Finish = Rc::new(move |result| {
+            if let Some(on_done) = on_done.borrow_mut().take() {
+                on_done(result);
+            }
+        });
+        let Some(window) = window() else {
+            return finish(Err(HttpError::Network(
+                "no global `window` exists".to_string(),
+            )));
+        };
+
+        let headers = Object::new();
+        let _ = Reflect::set(
+            &headers,
+            &JsValue::from_str("Content-Type"),
+            &JsValue::from_str("application/json"),
+        );
+        let mut init = RequestInit::new();
+        init.method(method).headers(&headers);
+        if let Some(body) = body {
+            init.body(Some(&JsValue::from_str(body)));
+        }
+
+        let on_response = {
+            let finish = finish.clone();
+            Closure::once(move |response: JsValue| {
+                let response: Response = response.unchecked_into();
+                if !response.ok() {
+                    return finish(Err(HttpError::Status(response.status())));
+                }
+                match response.text() {
+                    Ok(text) => {
+                        let on_text = {
+                            let finish = finish.clone();
+                            Closure::once(move |text: JsValue| {
+                                finish(Ok(text.as_string().unwrap_or_default()))
+                            })
+                        };
+                        let on_failed = on_failure(finish);
+                        let _ = text.then2(&on_text, &on_failed);
+                        on_text.forget();
+                        on_failed.forget();
+                    }
+                    Err(error) => finish(Err(network_error(error))),
+                }
+            })
+        };
+        let on_failed = on_failure(finish);
+        let _ = window
+            .fetch_with_str_and_init(url, &init)
+            .then2(&on_response, &on_failed);
+        on_response.forget();
+        on_failed.forget();
+    }
+}
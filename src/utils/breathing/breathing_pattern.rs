@@ -0,0 +1,161 @@
+/// A single step of a breathing cycle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BreathPhase {
+    Inhale,
+    HoldIn,
+    Exhale,
+    HoldOut,
+}
+
+impl BreathPhase {
+    /// The instruction shown to the user during this phase.
+    pub fn label(&self) -> &'static str {
+        match self {
+            BreathPhase::Inhale => "Breathe in",
+            BreathPhase::HoldIn | BreathPhase::HoldOut => "Hold",
+            BreathPhase::Exhale => "Breathe out",
+        }
+    }
+}
+
+/// Where in a breathing exercise a moment falls.
+///
+/// # Fields
+/// - `phase`: The current phase.
+/// - `cycle`: The current cycle, starting at `0`.
+/// - `progress`: How far through the current phase, from `0.0` to `1.0`.
+/// - `remaining_seconds`: Whole seconds left in the current phase, rounded up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreathPosition {
+    pub phase: BreathPhase,
+    pub cycle: u32,
+    pub progress: f32,
+    pub remaining_seconds: u32,
+}
+
+/// A breathing pattern: a named sequence of phases that repeats every cycle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreathingPattern {
+    pub name: String,
+    pub steps: Vec<(BreathPhase, u32)>,
+}
+
+impl BreathingPattern {
+    /// Box breathing: in, hold, out and hold for four seconds each.
+    pub fn box_breathing() -> Self {
+        BreathingPattern::custom("Box breathing", 4, 4, 4, 4)
+    }
+
+    /// 4-7-8 breathing: in for four, hold for seven, out for eight seconds.
+    pub fn four_seven_eight() -> Self {
+        BreathingPattern::custom("4-7-8 breathing", 4, 7, 8, 0)
+    }
+
+    /// The built-in patterns, in the order they are offered.
+    pub fn presets() -> Vec<Self> {
+        vec![
+            BreathingPattern::box_breathing(),
+            BreathingPattern::four_seven_eight(),
+        ]
+    }
+
+    /// A pattern with the given phase lengths in seconds; phases of zero seconds are skipped.
+    pub fn custom(
+        name: impl Into<String>,
+        inhale: u32,
+        hold_in: u32,
+        exhale: u32,
+        hold_out: u32,
+    ) -> Self {
+        BreathingPattern {
+            name: name.into(),
+            steps: [
+                (BreathPhase::Inhale, inhale),
+                (BreathPhase::HoldIn, hold_in),
+                (BreathPhase::Exhale, exhale),
+                (BreathPhase::HoldOut, hold_out),
+            ]
+            .into_iter()
+            .filter(|(_, seconds)| *seconds > 0)
+            .collect(),
+        }
+    }
+
+    /// The length of one cycle in seconds.
+    pub fn cycle_seconds(&self) -> u32 {
+        self.steps.iter().map(|(_, seconds)| seconds).sum()
+    }
+
+    /// The length of an exercise of `cycles` cycles in seconds.
+    pub fn total_seconds(&self, cycles: u32) -> u32 {
+        self.cycle_seconds() * cycles
+    }
+
+    /// Finds the phase `elapsed_ms` into the exercise.
+    ///
+    /// # Returns
+    /// The position, or `None` if the pattern has no steps.
+    pub fn position_at(&self, elapsed_ms: i64) -> Option<BreathPosition> {
+        let cycle_ms = self.cycle_seconds() as i64 * 1000;
+        if cycle_ms == 0 {
+            return None;
+        }
+        let elapsed_ms = elapsed_ms.max(0);
+        let mut within_cycle = elapsed_ms % cycle_ms;
+
+        for (phase, seconds) in &self.steps {
+            let phase_ms = *seconds as i64 * 1000;
+            if within_cycle < phase_ms {
+                return Some(BreathPosition {
+                    phase: *phase,
+                    cycle: (elapsed_ms / cycle_ms) as u32,
+                    progress: within_cycle as f32 / phase_ms as f32,
+                    remaining_seconds: ((phase_ms - within_cycle + 999) / 1000) as u32,
+                });
+            }
+            within_cycle -= phase_ms;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total_time() {
+        assert_eq!(BreathingPattern::box_breathing().cycle_seconds(), 16);
+        assert_eq!(BreathingPattern::four_seven_eight().cycle_seconds(), 19);
+        assert_eq!(BreathingPattern::four_seven_eight().total_seconds(4), 76);
+    }
+
+    #[test]
+    fn test_custom_skips_empty_phases() {
+        let pattern = BreathingPattern::custom("Custom", 5, 0, 5, 0);
+        assert_eq!(
+            pattern.steps,
+            vec![(BreathPhase::Inhale, 5), (BreathPhase::Exhale, 5)]
+        );
+        assert_eq!(
+            BreathingPattern::custom("Empty", 0, 0, 0, 0).position_at(0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_phase_sequence() {
+        let pattern = BreathingPattern::four_seven_eight();
+        let phase_at = |seconds: i64| pattern.position_at(seconds * 1000).unwrap().phase;
+
+        assert_eq!(phase_at(0), BreathPhase::Inhale);
+        assert_eq!(phase_at(4), BreathPhase::HoldIn);
+        assert_eq!(phase_at(11), BreathPhase::Exhale);
+        assert_eq!(phase_at(19), BreathPhase::Inhale);
+
+        let position = pattern.position_at(21_000).unwrap();
+        assert_eq!(position.cycle, 1);
+        assert_eq!(position.progress, 0.5);
+        assert_eq!(position.remaining_seconds, 2);
+    }
+}
@@ -0,0 +1 @@
+pub mod breathing_pattern;
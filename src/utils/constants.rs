@@ -0,0 +1,19 @@
+// Keys of the values kept in local storage. Keys marked per profile are stored inside the
+// active profile's namespace; read and write them through `profile_key`.
+
+/// The meditation log, per profile.
+pub const MEDITATION_LOG_KEY: &str = "meditationLog";
+/// The replicated state of the log, per profile.
+pub const LOG_CRDT_KEY: &str = "meditationLogCrdt";
+/// Sync settings and progress, per profile.
+pub const SYNC_STATE_KEY: &str = "syncState";
+/// The catalog of categories and speakers, per profile.
+pub const CATALOG_KEY: &str = "meditationCatalog";
+/// The timer of a session in progress, per profile.
+pub const ACTIVE_TIMER_KEY: &str = "activeTimer";
+/// Preferences, per profile.
+pub const SETTINGS_KEY: &str = "settings";
+/// The profiles on this device.
+pub const PROFILES_KEY: &str = "profiles";
+/// The passphrase check and key derivation parameters, for all profiles.
+pub const VAULT_KEY: &str = "encryption";
@@ -0,0 +1,268 @@
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de::DeserializeOwned, de::Error as _, Deserialize, Serialize};
+use serde_json::{Error, Map, Result, Value};
+
+/// How hard deflate works; 6 is zlib's default balance of size and speed.
+const DEFLATE_LEVEL: u8 = 6;
+
+/// One field of every record, stored together so deflate finds the repetition.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Column {
+    /// Whole numbers present in every record, stored as the difference to the previous one,
+    /// which keeps sorted timestamps short.
+    Deltas(Vec<i64>),
+    /// Strings, each distinct one stored once; a code is an index into `values` plus one,
+    /// or 0 for records without the field.
+    Strings {
+        values: Vec<String>,
+        codes: Vec<u32>,
+    },
+    /// Any other values, with `null` for records without the field.
+    Values(Vec<Value>),
+}
+
+impl Column {
+    fn from_cells(cells: Vec<Option<Value>>) -> Column {
+        let integers: Option<Vec<i64>> = cells
+            .iter()
+            .map(|cell| cell.as_ref().and_then(Value::as_i64))
+            .collect();
+        if let Some(integers) = integers {
+            let mut previous = 0_i64;
+            return Column::Deltas(
+                integers
+                    .into_iter()
+                    .map(|value| {
+                        let delta = value.wrapping_sub(previous);
+                        previous = value;
+                        delta
+                    })
+                    .collect(),
+            );
+        }
+
+        if cells
+            .iter()
+            .all(|cell| cell.as_ref().is_none_or(Value::is_string))
+        {
+            let mut values = Vec::new();
+            let mut index: BTreeMap<String, u32> = BTreeMap::new();
+            let codes = cells
+                .into_iter()
+                .map(|cell| match cell {
+                    Some(Value::String(text)) => *index.entry(text).or_insert_with_key(|text| {
+                        values.push(text.clone());
+                        values.len() as u32
+                    }),
+                    _ => 0,
+                })
+                .collect();
+            return Column::Strings { values, codes };
+        }
+
+        Column::Values(
+            cells
+                .into_iter()
+                .map(|cell| cell.unwrap_or(Value::Null))
+                .collect(),
+        )
+    }
+
+    fn into_cells(self, rows: usize) -> Result<Vec<Option<Value>>> {
+        let cells: Vec<Option<Value>> = match self {
+            Column::Deltas(deltas) => {
+                let mut value = 0_i64;
+                deltas
+                    .into_iter()
+                    .map(|delta| {
+                        value = value.wrapping_add(delta);
+                        Some(Value::from(value))
+                    })
+                    .collect()
+            }
+            Column::Strings { values, codes } => codes
+                .into_iter()
+                .map(|code| match code {
+                    0 => Ok(None),
+                    code => values
+                        .get(code as usize - 1)
+                        .map(|text| Some(Value::String(text.clone())))
+                        .ok_or_else(|| Error::custom(format!("unknown string code {}", code))),
+                })
+                .collect::<Result<_>>()?,
+            Column::Values(values) => values
+                .into_iter()
+                .map(|value| Some(value).filter(|value| !value.is_null()))
+                .collect(),
+        };
+        if cells.len() != rows {
+            return Err(Error::custom(format!(
+                "column has {} entries for {} records",
+                cells.len(),
+                rows
+            )));
+        }
+        Ok(cells)
+    }
+}
+
+/// The columns of a compacted log, before deflate.
+#[derive(Deserialize, Serialize)]
+struct Columns {
+    rows: usize,
+    columns: BTreeMap<String, Column>,
+}
+
+/// Compacts records into a deflated, base64 columnar form that is safe to keep in a string.
+///
+/// Each field is stored once for all records, repeated strings once per log, and whole
+/// numbers as differences. Fields that are `null` are stored like missing ones, so they read
+/// back as missing.
+///
+/// # Arguments
+/// - `records`: The records; each must serialize to a JSON object.
+///
+/// # Returns
+/// - `Ok(String)`: The compacted records.
+/// - `Err(Error)`: If a record doesn't serialize to an object.
+pub fn compact<T>(records: &[T]) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut fields: BTreeMap<String, Vec<Option<Value>>> = BTreeMap::new();
+    for (row, record) in records.iter().enumerate() {
+        let Value::Object(object) = serde_json::to_value(record)? else {
+            return Err(Error::custom(
+                "only records with named fields can be compacted",
+            ));
+        };
+        for (field, value) in object {
+            fields
+                .entry(field)
+                .or_insert_with(|| vec![None; records.len()])[row] = Some(value);
+        }
+    }
+
+    let columns = Columns {
+        rows: records.len(),
+        columns: fields
+            .into_iter()
+            .map(|(field, cells)| (field, Column::from_cells(cells)))
+            .collect(),
+    };
+    let json = serde_json::to_vec(&columns)?;
+    Ok(STANDARD.encode(miniz_oxide::deflate::compress_to_vec(&json, DEFLATE_LEVEL)))
+}
+
+/// Reads records written by `compact`.
+///
+/// # Returns
+/// - `Ok(Vec<T>)`: The records, in the order they were compacted.
+/// - `Err(Error)`: If the text is damaged or doesn't hold records of type `T`.
+pub fn expand<T>(compacted: &str) -> Result<Vec<T>>
+where
+    T: DeserializeOwned,
+{
+    let deflated = STANDARD.decode(compacted).map_err(Error::custom)?;
+    let json = miniz_oxide::inflate::decompress_to_vec(&deflated)
+        .map_err(|error| Error::custom(format!("can't inflate: {:?}", error.status)))?;
+    let Columns { rows, columns } = serde_json::from_slice(&json)?;
+
+    let mut objects = vec![Map::new(); rows];
+    for (field, column) in columns {
+        for (object, cell) in objects.iter_mut().zip(column.into_cells(rows)?) {
+            if let Some(value) = cell {
+                object.insert(field.clone(), value);
+            }
+        }
+    }
+    objects
+        .into_iter()
+        .map(|object| serde_json::from_value(Value::Object(object)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::meditation_log::{
+        meditation_data_builder::MeditationData, session_plan::SessionPlan,
+    };
+
+    const CATEGORIES: [&str; 4] = ["Mindfulness", "Relaxation", "Healing", "Sleep"];
+    const SPEAKERS: [&str; 3] = ["Alice", "Bob", "Charlie"];
+
+    /// Three sessions a day, every tenth with a note and some with moods and tags.
+    fn synthetic_meditations(count: usize) -> Vec<MeditationData> {
+        (0..count)
+            .map(|i| MeditationData {
+                datetime: 1_600_000_000 + (i / 3) as i64 * 86_400 + (i % 3) as i64 * 14_400,
+                duration: 300 + (i % 12) as i32 * 150,
+                category: CATEGORIES[i % CATEGORIES.len()].to_string(),
+                speaker: SPEAKERS[i % SPEAKERS.len()].to_string(),
+                notes: (i % 10 == 0).then(|| format!("Session {} felt calm", i)),
+                mood_after: (i % 2 == 0).then_some((i % 5) as u8 + 1),
+                tags: if i % 4 == 0 {
+                    vec!["morning".to_string()]
+                } else {
+                    Vec::new()
+                },
+                id: format!("a1b2c3d4-{:x}", i),
+                modified_at: 1_600_000_000_000 + i as i64 * 1_000,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut meditations = synthetic_meditations(50);
+        meditations[3].plan = Some(SessionPlan::default());
+        meditations[4].notes = Some(String::new());
+
+        let compacted = compact(&meditations).unwrap();
+        assert_eq!(expand::<MeditationData>(&compacted).unwrap(), meditations);
+    }
+
+    #[test]
+    fn test_round_trip_empty() {
+        let compacted = compact::<MeditationData>(&[]).unwrap();
+        assert!(expand::<MeditationData>(&compacted).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_extreme_numbers() {
+        let meditations = vec![
+            MeditationData {
+                datetime: i64::MAX,
+                duration: i32::MIN,
+                ..Default::default()
+            },
+            MeditationData {
+                datetime: i64::MIN,
+                duration: i32::MAX,
+                ..Default::default()
+            },
+        ];
+        let compacted = compact(&meditations).unwrap();
+        assert_eq!(expand::<MeditationData>(&compacted).unwrap(), meditations);
+    }
+
+    #[test]
+    fn test_rejects_damaged_text() {
+        assert!(expand::<MeditationData>("not base64!").is_err());
+        assert!(expand::<MeditationData>(&STANDARD.encode(b"not deflate")).is_err());
+        assert!(compact(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_compact_is_a_fraction_of_json() {
+        // Sizes of larger logs are measured by the log_encoding bench.
+        let meditations = synthetic_meditations(1_000);
+        let json = serde_json::to_string(&meditations).unwrap();
+        assert!(compact(&meditations).unwrap().len() * 10 < json.len());
+    }
+}
@@ -1,14 +1,13 @@
-use crate::services::local_storage::LocalStorageService;
 use web_sys::wasm_bindgen::JsValue;
 
+use crate::services::local_storage::StorageError;
+
 use super::{
-    json_array_handler::{deserialize_json_array, serialize_json_array},
+    log_envelope::{decode_log, encode_log_as, log_encoding, LogEncoding},
     meditation_log::meditation_data_builder::MeditationData,
+    protected_storage::ProtectedStorage,
 };
 
-/// A struct representing the data related to meditation.
-/// This struct can be serialized and deserialized for storage purposes.
-
 /// Manages database operations for `MeditationData`.
 pub struct DatabaseManager {}
 
@@ -22,32 +21,60 @@ impl DatabaseManager {
     /// * `database_key` - The key used for storing the data in local storage.
     ///
     /// This function serializes the updated data vector and writes it to the local storage.
-    /// Logs an error message to the web console on failure.
     fn serialize_and_write(
         data_vector: &[MeditationData],
         data: MeditationData,
         database_key: &str,
-    ) {
+    ) -> Result<(), StorageError> {
         let mut data_vector = data_vector.to_vec();
         data_vector.push(data);
 
-        match serialize_json_array(&data_vector) {
-            Ok(res) => LocalStorageService::create(database_key, &res).unwrap_or_else(|_| {
-                web_sys::console::log_1(&JsValue::from_str("Failed to write data"));
-            }),
-            Err(_) => web_sys::console::log_1(&JsValue::from_str("Serialization failed")),
-        };
+        Self::serialize_and_replace(&data_vector, database_key)
+    }
+
+    /// Serializes the whole data vector and writes it to local storage, replacing what was there.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_vector` - A slice of `MeditationData`, representing the new state of data.
+    /// * `database_key` - The key used for storing the data in local storage.
+    ///
+    /// The data is written in the encoding the stored data already uses.
+    fn serialize_and_replace(
+        data_vector: &[MeditationData],
+        database_key: &str,
+    ) -> Result<(), StorageError> {
+        Self::serialize_and_replace_as(data_vector, Self::encoding(database_key), database_key)
+    }
+
+    /// Serializes the whole data vector in `encoding` and writes it to local storage.
+    fn serialize_and_replace_as(
+        data_vector: &[MeditationData],
+        encoding: LogEncoding,
+        database_key: &str,
+    ) -> Result<(), StorageError> {
+        let res = encode_log_as(data_vector, encoding)
+            .map_err(|err| StorageError::Failed(format!("serialization failed: {}", err)))?;
+        ProtectedStorage::create(database_key, &res)
+    }
+
+    /// Logs a failed write to the web console.
+    fn log_failure(error: &StorageError) {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Failed to write data: {}",
+            error
+        )));
     }
 
     /// Deserializes a JSON string into a vector of `MeditationData`.
     ///
     /// # Arguments
     ///
-    /// * `value` - A JSON string representing serialized `MeditationData`.
+    /// * `value` - A JSON string representing serialized `MeditationData`, in any storage version.
     ///
     /// Returns a vector of `MeditationData` or an empty vector on deserialization failure.
     fn deserialize_and_read(value: &str) -> Vec<MeditationData> {
-        deserialize_json_array(value).unwrap_or_else(|_| Vec::new())
+        decode_log(value).unwrap_or_else(|_| Vec::new())
     }
 
     /// Writes `MeditationData` to local storage.
@@ -58,23 +85,89 @@ impl DatabaseManager {
     /// * `database_key` - The key used for storing the data in local storage.
     ///
     /// Reads the current data from local storage, updates it with the new data,
-    /// and then writes it back to the storage.
-    /// Logs an error message to the web console on read failure.
-    pub fn write_data(data: MeditationData, database_key: &str) {
-        match LocalStorageService::read(database_key) {
-            Ok(Some(value)) => {
-                let data_vector = Self::deserialize_and_read(&value);
-                Self::serialize_and_write(&data_vector, data, database_key);
-            }
-            Ok(None) => {
-                Self::serialize_and_write(&[], data, database_key);
-            }
-            Err(err) => {
-                web_sys::console::log_1(&JsValue::from_str(&format!("Error loading: {:?}", err)));
-            }
+    /// and then writes it back to the storage in the current format.
+    ///
+    /// Returns a `StorageError` if nothing was written: `StorageError::QuotaExceeded` when
+    /// storage is full, or `StorageError::Unreadable` when the stored data can't be read, e.g.
+    /// because a newer version of the app wrote it; it is left untouched then.
+    pub fn write_data(data: MeditationData, database_key: &str) -> Result<(), StorageError> {
+        match ProtectedStorage::read(database_key)? {
+            Some(value) => match decode_log(&value) {
+                Ok(data_vector) => Self::serialize_and_write(&data_vector, data, database_key),
+                Err(err) => Err(StorageError::Unreadable(err.to_string())),
+            },
+            None => Self::serialize_and_write(&[], data, database_key),
         }
     }
 
+    /// Writes the whole `MeditationData` log to local storage, replacing what was stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_vector` - The new state of the data.
+    /// * `database_key` - The key used for storing the data in local storage.
+    ///
+    /// Returns a `StorageError` if nothing was written, e.g. `StorageError::QuotaExceeded`.
+    pub fn replace_data(
+        data_vector: &[MeditationData],
+        database_key: &str,
+    ) -> Result<(), StorageError> {
+        Self::serialize_and_replace(data_vector, database_key)
+    }
+
+    /// The position of the entry with `id` in `data_vector`; entries without an id are never
+    /// found.
+    fn position(data_vector: &[MeditationData], id: &str) -> Option<usize> {
+        if id.is_empty() {
+            return None;
+        }
+        data_vector.iter().position(|data| data.id == id)
+    }
+
+    /// Replaces the `MeditationData` with `id` in local storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the entry in the stored data.
+    /// * `data` - The `MeditationData` to store in its place.
+    /// * `database_key` - The key used for storing the data in local storage.
+    ///
+    /// Returns the replaced entry, or `None` if there is no entry with `id` or the write
+    /// failed, in which case nothing is written and the failure is logged to the web console.
+    pub fn update_data(
+        id: &str,
+        data: MeditationData,
+        database_key: &str,
+    ) -> Option<MeditationData> {
+        let mut data_vector = Self::read_data(database_key);
+        let index = Self::position(&data_vector, id)?;
+        let previous = std::mem::replace(&mut data_vector[index], data);
+
+        Self::serialize_and_replace(&data_vector, database_key)
+            .inspect_err(Self::log_failure)
+            .ok()?;
+        Some(previous)
+    }
+
+    /// Removes the `MeditationData` with `id` from local storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the entry in the stored data.
+    /// * `database_key` - The key used for storing the data in local storage.
+    ///
+    /// Returns the removed entry, or `None` if there is no entry with `id` or the write
+    /// failed, in which case nothing is written and the failure is logged to the web console.
+    pub fn delete_data(id: &str, database_key: &str) -> Option<MeditationData> {
+        let mut data_vector = Self::read_data(database_key);
+        let removed = data_vector.remove(Self::position(&data_vector, id)?);
+
+        Self::serialize_and_replace(&data_vector, database_key)
+            .inspect_err(Self::log_failure)
+            .ok()?;
+        Some(removed)
+    }
+
     /// Reads `MeditationData` from local storage.
     ///
     /// # Arguments
@@ -84,9 +177,56 @@ impl DatabaseManager {
     /// Returns a vector of `MeditationData`.
     /// Returns an empty vector if the data is not found or on read failure.
     pub fn read_data(database_key: &str) -> Vec<MeditationData> {
-        match LocalStorageService::read(database_key) {
+        match ProtectedStorage::read(database_key) {
             Ok(Some(value)) => Self::deserialize_and_read(&value),
             _ => Vec::new(),
         }
     }
+
+    /// Reads `MeditationData` from local storage, telling an empty log apart from one that
+    /// can't be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_key` - The key used for accessing the data in local storage.
+    ///
+    /// Returns the stored data, an empty vector if nothing is stored, or `None` if the
+    /// stored data or local storage can't be read.
+    pub fn try_read_data(database_key: &str) -> Option<Vec<MeditationData>> {
+        match ProtectedStorage::read(database_key) {
+            Ok(Some(value)) => decode_log(&value).ok(),
+            Ok(None) => Some(Vec::new()),
+            Err(_) => None,
+        }
+    }
+
+    /// Tells how the data in local storage is encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_key` - The key used for accessing the data in local storage.
+    ///
+    /// Returns `LogEncoding::Json` if nothing readable is stored.
+    pub fn encoding(database_key: &str) -> LogEncoding {
+        match ProtectedStorage::read(database_key) {
+            Ok(Some(value)) => log_encoding(&value),
+            _ => LogEncoding::default(),
+        }
+    }
+
+    /// Rewrites the data in local storage in `encoding`; later writes keep using it.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_key` - The key used for storing the data in local storage.
+    /// * `encoding` - How to write the data from now on.
+    ///
+    /// Returns a `StorageError` if nothing was written; stored data that can't be read is
+    /// left untouched.
+    pub fn set_encoding(database_key: &str, encoding: LogEncoding) -> Result<(), StorageError> {
+        let data_vector = Self::try_read_data(database_key).ok_or_else(|| {
+            StorageError::Unreadable("not rewriting in another encoding".to_string())
+        })?;
+        Self::serialize_and_replace_as(&data_vector, encoding, database_key)
+    }
 }
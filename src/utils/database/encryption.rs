@@ -0,0 +1,311 @@
+use std::fmt;
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// PBKDF2 rounds for new passphrases, following current OWASP guidance for HMAC-SHA256.
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Length of the random salt a key is derived with.
+pub const SALT_LEN: usize = 16;
+/// Length of the random nonce every encrypted value is written with.
+pub const NONCE_LEN: usize = 12;
+/// The shortest passphrase accepted.
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// The encrypted format version written by this build.
+const ENCRYPTED_VERSION: u32 = 1;
+/// Known text encrypted into the header, so a passphrase can be checked before any data is
+/// decrypted with it.
+const CHECK_TEXT: &str = "meditation-tracker";
+
+/// Reasons stored data can't be encrypted or decrypted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncryptionError {
+    /// The passphrase doesn't match the one the data was encrypted with.
+    WrongPassphrase,
+    /// The passphrase is shorter than `MIN_PASSPHRASE_LEN`.
+    WeakPassphrase,
+    /// Encrypted data is needed but no passphrase has been entered.
+    Locked,
+    /// Stored data is damaged or was written by a newer version of the app.
+    Unreadable(String),
+    /// The data couldn't be written back to storage.
+    Storage(String),
+}
+
+impl fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncryptionError::WrongPassphrase => f.write_str("the passphrase is not correct"),
+            EncryptionError::WeakPassphrase => write!(
+                f,
+                "the passphrase must be at least {} characters",
+                MIN_PASSPHRASE_LEN
+            ),
+            EncryptionError::Locked => f.write_str("enter your passphrase to unlock your data"),
+            EncryptionError::Unreadable(message) => {
+                write!(f, "encrypted data can't be read: {}", message)
+            }
+            EncryptionError::Storage(message) => {
+                write!(f, "encrypted data can't be stored: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncryptionError {}
+
+/// The stored form of an encrypted value.
+///
+/// Unknown fields are refused, so telling a stored log from an encrypted value stops at its
+/// first field rather than reading it all.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptedValue {
+    /// The encrypted format version.
+    pub encrypted: u32,
+    pub nonce: String,
+    /// The AES-256-GCM ciphertext and tag.
+    pub data: String,
+}
+
+/// An AES-256 key derived from a passphrase.
+///
+/// Deliberately not `Debug`, so it can't end up in a log.
+#[derive(Clone, PartialEq)]
+pub struct EncryptionKey {
+    key: [u8; 32],
+}
+
+impl EncryptionKey {
+    /// Derives the key for `passphrase` with PBKDF2-HMAC-SHA256.
+    pub fn derive(passphrase: &str, salt: &[u8], iterations: u32) -> Self {
+        let mut key = [0; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+        EncryptionKey { key }
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key))
+    }
+
+    fn seal(&self, plaintext: &str, nonce: [u8; NONCE_LEN]) -> EncryptedValue {
+        let data = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+            .expect("AES-GCM encryption of an in-memory buffer can't fail");
+        EncryptedValue {
+            encrypted: ENCRYPTED_VERSION,
+            nonce: STANDARD.encode(nonce),
+            data: STANDARD.encode(data),
+        }
+    }
+
+    fn open(&self, value: &EncryptedValue) -> Result<String, EncryptionError> {
+        if value.encrypted > ENCRYPTED_VERSION {
+            return Err(EncryptionError::Unreadable(format!(
+                "version {} is newer than the supported version {}",
+                value.encrypted, ENCRYPTED_VERSION
+            )));
+        }
+        let decode = |text: &str| {
+            STANDARD
+                .decode(text)
+                .map_err(|error| EncryptionError::Unreadable(error.to_string()))
+        };
+        let nonce = decode(&value.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(EncryptionError::Unreadable("bad nonce".to_string()));
+        }
+        // GCM authenticates the data, so a wrong key fails here rather than returning garbage.
+        let plaintext = self
+            .cipher()
+            .decrypt(Nonce::from_slice(&nonce), decode(&value.data)?.as_slice())
+            .map_err(|_| EncryptionError::WrongPassphrase)?;
+        String::from_utf8(plaintext).map_err(|error| EncryptionError::Unreadable(error.to_string()))
+    }
+
+    /// Encrypts `plaintext` for storage.
+    ///
+    /// # Arguments
+    /// * `plaintext` - The value to store.
+    /// * `nonce` - Fresh random bytes; a nonce must never be used twice with the same key.
+    ///
+    /// # Returns
+    /// The JSON of an `EncryptedValue`.
+    pub fn encrypt(&self, plaintext: &str, nonce: [u8; NONCE_LEN]) -> String {
+        serde_json::to_string(&self.seal(plaintext, nonce))
+            .expect("an encrypted value always serializes")
+    }
+
+    /// Decrypts a value written by `encrypt`.
+    ///
+    /// # Returns
+    /// - `Ok(String)`: The stored plaintext.
+    /// - `Err(EncryptionError::WrongPassphrase)`: If the value was encrypted with another key.
+    /// - `Err(EncryptionError::Unreadable)`: If the value is damaged or not encrypted.
+    pub fn decrypt(&self, stored: &str) -> Result<String, EncryptionError> {
+        let value: EncryptedValue = serde_json::from_str(stored)
+            .map_err(|error| EncryptionError::Unreadable(error.to_string()))?;
+        self.open(&value)
+    }
+}
+
+/// Whether a stored value was written by `EncryptionKey::encrypt`.
+pub fn is_encrypted(stored: &str) -> bool {
+    serde_json::from_str::<EncryptedValue>(stored).is_ok()
+}
+
+/// What is stored about the passphrase: how to derive the key from it, and a known text
+/// encrypted with that key to check it against.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct VaultHeader {
+    pub salt: String,
+    pub iterations: u32,
+    pub check: EncryptedValue,
+}
+
+impl VaultHeader {
+    /// Creates the header for a new passphrase.
+    ///
+    /// # Returns
+    /// The header to store and the key to encrypt data with, or
+    /// `EncryptionError::WeakPassphrase` if the passphrase is too short.
+    pub fn create(
+        passphrase: &str,
+        salt: [u8; SALT_LEN],
+        iterations: u32,
+        nonce: [u8; NONCE_LEN],
+    ) -> Result<(VaultHeader, EncryptionKey), EncryptionError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+            return Err(EncryptionError::WeakPassphrase);
+        }
+        let key = EncryptionKey::derive(passphrase, &salt, iterations);
+        let header = VaultHeader {
+            salt: STANDARD.encode(salt),
+            iterations,
+            check: key.seal(CHECK_TEXT, nonce),
+        };
+        Ok((header, key))
+    }
+
+    /// Derives the key for `passphrase` and checks it against the header.
+    ///
+    /// # Returns
+    /// - `Ok(EncryptionKey)`: The key the stored data is encrypted with.
+    /// - `Err(EncryptionError::WrongPassphrase)`: If the passphrase doesn't match.
+    pub fn unlock(&self, passphrase: &str) -> Result<EncryptionKey, EncryptionError> {
+        let salt = STANDARD
+            .decode(&self.salt)
+            .map_err(|error| EncryptionError::Unreadable(error.to_string()))?;
+        let key = EncryptionKey::derive(passphrase, &salt, self.iterations);
+        match key.open(&self.check)?.as_str() {
+            CHECK_TEXT => Ok(key),
+            _ => Err(EncryptionError::WrongPassphrase),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Few rounds keep the tests fast; the rounds don't change the logic.
+    const ITERATIONS: u32 = 10;
+
+    fn vault(passphrase: &str) -> (VaultHeader, EncryptionKey) {
+        VaultHeader::create(passphrase, [7; SALT_LEN], ITERATIONS, [1; NONCE_LEN]).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let (_, key) = vault("correct horse");
+        let stored = key.encrypt(r#"{"version":2,"sessions":[]}"#, [2; NONCE_LEN]);
+
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("sessions"));
+        assert_eq!(
+            key.decrypt(&stored).unwrap(),
+            r#"{"version":2,"sessions":[]}"#
+        );
+        assert!(!is_encrypted(r#"{"version":2,"sessions":[]}"#));
+        assert!(!is_encrypted("[]"));
+        assert!(!is_encrypted(&stored.replace("{", r#"{"version":2,"#)));
+    }
+
+    #[test]
+    fn test_nonce_changes_ciphertext() {
+        let (_, key) = vault("correct horse");
+        assert_ne!(
+            key.encrypt("notes", [2; NONCE_LEN]),
+            key.encrypt("notes", [3; NONCE_LEN])
+        );
+    }
+
+    #[test]
+    fn test_unlock_checks_passphrase() {
+        let (header, key) = vault("correct horse");
+        let header: VaultHeader =
+            serde_json::from_str(&serde_json::to_string(&header).unwrap()).unwrap();
+
+        assert!(header.unlock("correct horse").unwrap() == key);
+        assert_eq!(
+            header.unlock("wrong horse").err(),
+            Some(EncryptionError::WrongPassphrase)
+        );
+    }
+
+    #[test]
+    fn test_wrong_key_fails_without_output() {
+        let (_, key) = vault("correct horse");
+        let (_, other) = vault("battery staple");
+        let stored = key.encrypt("notes", [2; NONCE_LEN]);
+
+        assert_eq!(
+            other.decrypt(&stored),
+            Err(EncryptionError::WrongPassphrase)
+        );
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let (_, key) = vault("correct horse");
+        let mut value: EncryptedValue =
+            serde_json::from_str(&key.encrypt("notes", [2; NONCE_LEN])).unwrap();
+        let mut data = STANDARD.decode(&value.data).unwrap();
+        data[0] ^= 1;
+        value.data = STANDARD.encode(data);
+
+        assert_eq!(
+            key.decrypt(&serde_json::to_string(&value).unwrap()),
+            Err(EncryptionError::WrongPassphrase)
+        );
+        assert!(matches!(
+            key.decrypt("[]"),
+            Err(EncryptionError::Unreadable(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_short_passphrase() {
+        assert_eq!(
+            VaultHeader::create("short", [7; SALT_LEN], ITERATIONS, [1; NONCE_LEN]).err(),
+            Some(EncryptionError::WeakPassphrase)
+        );
+    }
+
+    #[test]
+    fn test_matches_known_pbkdf2_vector() {
+        // RFC 7914, section 11: PBKDF2-HMAC-SHA256("passwd", "salt", 1).
+        let key = EncryptionKey::derive("passwd", b"salt", 1);
+        assert_eq!(
+            key.key[..8],
+            [0x55, 0xac, 0x04, 0x6e, 0x56, 0xe3, 0x08, 0x9f]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use serde::ser::Error;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Result, Value};
+
+use super::{
+    compact_log::{compact, expand},
+    json_array_handler::deserialize_json_array,
+};
+
+/// The newest storage format version this build reads.
+///
+/// - `1`: A bare JSON array of sessions.
+/// - `2`: An object with a `version` and the `sessions`, which may carry journal fields.
+/// - `3`: As `2`, with an `encoding`; compact logs keep their `sessions` in one string.
+pub const CURRENT_LOG_VERSION: u32 = 3;
+
+/// How the sessions of a stored log are written.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogEncoding {
+    /// A JSON array of session objects, readable by every version that reads envelopes.
+    #[default]
+    Json,
+    /// Columns of the session fields, deflated and base64 encoded; see `compact_log`.
+    Compact,
+}
+
+impl LogEncoding {
+    /// The format version logs in this encoding are written in. JSON logs stay at version 2,
+    /// so older versions of the app can still read them.
+    fn version(self) -> u32 {
+        match self {
+            LogEncoding::Json => 2,
+            LogEncoding::Compact => 3,
+        }
+    }
+
+    fn is_json(&self) -> bool {
+        *self == LogEncoding::Json
+    }
+}
+
+/// The stored form of a log: the sessions tagged with the format version they were written in.
+///
+/// `S` is the collection of sessions, e.g. a `Vec` when reading or a slice when writing, or
+/// the compacted string in a compact log.
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub struct LogEnvelope<S> {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "LogEncoding::is_json")]
+    pub encoding: LogEncoding,
+    pub sessions: S,
+}
+
+/// The fields of a stored log needed to tell how to read it.
+#[derive(Deserialize)]
+struct EnvelopeHeader {
+    version: u32,
+    #[serde(default)]
+    encoding: LogEncoding,
+}
+
+/// Reads a stored log, migrating older formats to the current one.
+///
+/// # Arguments
+/// - `json_data`: The stored JSON, either a bare array (version 1) or a `LogEnvelope`.
+///
+/// # Returns
+/// - `Ok(Vec<T>)`: The sessions in the current format.
+/// - `Err(Error)`: If the JSON is malformed or was written by a newer version of the app,
+///   in which case it must not be overwritten.
+pub fn decode_log<T>(json_data: &str) -> Result<Vec<T>>
+where
+    T: DeserializeOwned,
+{
+    let value: Value = serde_json::from_str(json_data)?;
+    if value.is_array() {
+        // Version 1 records are a subset of the current ones, the new fields default to empty.
+        return deserialize_json_array(json_data);
+    }
+
+    let header = EnvelopeHeader::deserialize(&value)?;
+    if header.version > CURRENT_LOG_VERSION {
+        return Err(Error::custom(format!(
+            "log version {} is newer than the supported version {}",
+            header.version, CURRENT_LOG_VERSION
+        )));
+    }
+    match header.encoding {
+        LogEncoding::Json => Ok(serde_json::from_value::<LogEnvelope<Vec<T>>>(value)?.sessions),
+        LogEncoding::Compact => {
+            expand(&serde_json::from_value::<LogEnvelope<String>>(value)?.sessions)
+        }
+    }
+}
+
+/// Tells how a stored log is encoded.
+///
+/// # Returns
+/// The encoding of the log, or `LogEncoding::Json` for bare arrays and unreadable data.
+pub fn log_encoding(json_data: &str) -> LogEncoding {
+    serde_json::from_str::<EnvelopeHeader>(json_data)
+        .map(|header| header.encoding)
+        .unwrap_or_default()
+}
+
+/// Writes sessions in the current storage format, as JSON.
+///
+/// # Arguments
+/// - `data`: The sessions to store.
+///
+/// # Returns
+/// - `Ok(String)`: The JSON of a `LogEnvelope`.
+/// - `Err(Error)`: An error if serialization fails.
+pub fn encode_log<T>(data: &[T]) -> Result<String>
+where
+    T: Serialize,
+{
+    encode_log_as(data, LogEncoding::Json)
+}
+
+/// Writes sessions in the current storage format, in the given encoding.
+///
+/// # Arguments
+/// - `data`: The sessions to store.
+/// - `encoding`: How to write the sessions.
+///
+/// # Returns
+/// - `Ok(String)`: The JSON of a `LogEnvelope`.
+/// - `Err(Error)`: An error if serialization fails.
+pub fn encode_log_as<T>(data: &[T], encoding: LogEncoding) -> Result<String>
+where
+    T: Serialize,
+{
+    match encoding {
+        LogEncoding::Json => serde_json::to_string(&LogEnvelope {
+            version: encoding.version(),
+            encoding,
+            sessions: data,
+        }),
+        LogEncoding::Compact => serde_json::to_string(&LogEnvelope {
+            version: encoding.version(),
+            encoding,
+            sessions: compact(data)?,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::meditation_log::meditation_data_builder::MeditationData;
+
+    #[test]
+    fn test_migrates_bare_array() {
+        let json = r#"[{"datetime":1707552000,"duration":600,"category":"Sleep","speaker":"Bob"}]"#;
+        let sessions: Vec<MeditationData> = decode_log(json).unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].category, "Sleep");
+        assert_eq!(sessions[0].notes, None);
+        assert!(sessions[0].tags.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let sessions = vec![MeditationData {
+            datetime: 1_707_552_000,
+            duration: 600,
+            category: "Sleep".to_string(),
+            speaker: "Bob".to_string(),
+            mood_after: Some(4),
+            tags: vec!["evening".to_string()],
+            ..Default::default()
+        }];
+        let json = encode_log(&sessions).unwrap();
+
+        assert!(json.starts_with(r#"{"version":2,"sessions":["#));
+        assert_eq!(decode_log::<MeditationData>(&json).unwrap(), sessions);
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        let sessions = vec![
+            MeditationData {
+                datetime: 1_707_552_000,
+                duration: 600,
+                category: "Sleep".to_string(),
+                speaker: "Bob".to_string(),
+                notes: Some("Calm".to_string()),
+                ..Default::default()
+            };
+            3
+        ];
+        let json = encode_log_as(&sessions, LogEncoding::Compact).unwrap();
+
+        assert!(json.starts_with(r#"{"version":3,"encoding":"compact","sessions":""#));
+        assert_eq!(log_encoding(&json), LogEncoding::Compact);
+        assert_eq!(decode_log::<MeditationData>(&json).unwrap(), sessions);
+
+        let json = encode_log(&sessions).unwrap();
+        assert_eq!(log_encoding(&json), LogEncoding::Json);
+        assert_eq!(log_encoding("[]"), LogEncoding::Json);
+    }
+
+    #[test]
+    fn test_rejects_newer_version() {
+        let json = r#"{"version":99,"sessions":[]}"#;
+        assert!(decode_log::<MeditationData>(json).is_err());
+    }
+}
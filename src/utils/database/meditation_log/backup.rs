@@ -0,0 +1,137 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::log_crdt::LogCrdt;
+
+/// Marks a JSON file as a backup of this app.
+pub const BACKUP_FORMAT: &str = "meditation-backup";
+/// The backup version written by this build.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// A full backup: the replicated log, including deletions, so restoring it into a log that
+/// changed since merges the two instead of overwriting either.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    /// When the backup was made, in UNIX milliseconds.
+    pub exported_at: i64,
+    pub log: LogCrdt,
+}
+
+/// The part of a backup read before the rest, to check what the file is.
+#[derive(Deserialize)]
+struct BackupHeader {
+    #[serde(default)]
+    format: String,
+    #[serde(default)]
+    version: u32,
+}
+
+/// Reasons a file can't be restored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackupError {
+    /// The file isn't a backup of this app.
+    NotABackup,
+    /// The backup was made by a newer version of the app.
+    UnsupportedVersion(u32),
+    /// The backup is damaged.
+    Unreadable(String),
+    /// The restored sessions couldn't be stored.
+    Storage(String),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::NotABackup => f.write_str("this file is not a meditation backup"),
+            BackupError::UnsupportedVersion(version) => write!(
+                f,
+                "backup version {} needs a newer version of the app",
+                version
+            ),
+            BackupError::Unreadable(message) => write!(f, "backup can't be read: {}", message),
+            BackupError::Storage(message) => write!(f, "restored sessions not stored: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+/// Serializes `log` as a backup file.
+pub fn encode_backup(log: &LogCrdt, exported_at: i64) -> serde_json::Result<String> {
+    serde_json::to_string(&Backup {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        exported_at,
+        log: log.clone(),
+    })
+}
+
+/// Reads a backup file.
+///
+/// # Returns
+/// - `Ok(Backup)`: The backup, ready to be merged into the local log.
+/// - `Err(BackupError)`: If the file isn't a backup, is from a newer version, or is damaged.
+pub fn decode_backup(json: &str) -> Result<Backup, BackupError> {
+    let header: BackupHeader = serde_json::from_str(json).map_err(|_| BackupError::NotABackup)?;
+    if header.format != BACKUP_FORMAT {
+        return Err(BackupError::NotABackup);
+    }
+    if header.version > BACKUP_VERSION {
+        return Err(BackupError::UnsupportedVersion(header.version));
+    }
+    serde_json::from_str(json).map_err(|error| BackupError::Unreadable(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::meditation_log::meditation_data_builder::MeditationData;
+
+    fn session(id: &str, duration: i32) -> MeditationData {
+        MeditationData {
+            id: id.to_string(),
+            datetime: 100,
+            duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_restoring_merges_with_changes_made_since() {
+        let mut log = LogCrdt::default();
+        log.observe(&[session("a", 600), session("b", 300)], "phone", 1_000);
+        let backup = decode_backup(&encode_backup(&log, 1_500).unwrap()).unwrap();
+        assert_eq!(backup.exported_at, 1_500);
+
+        // After the backup, "a" is deleted and "c" added.
+        log.observe(&[session("b", 300), session("c", 900)], "phone", 2_000);
+        assert!(log.merge(&backup.log).is_empty());
+        let ids: Vec<String> = log.meditations().into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+
+        // On a new device the backup brings everything back.
+        let mut fresh = LogCrdt::default();
+        assert_eq!(fresh.merge(&backup.log).len(), 2);
+        assert_eq!(fresh.meditations().len(), 2);
+    }
+
+    #[test]
+    fn test_decode_rejects_other_files() {
+        assert_eq!(decode_backup("[]"), Err(BackupError::NotABackup));
+        assert_eq!(
+            decode_backup(r#"{"format":"something-else","version":1}"#),
+            Err(BackupError::NotABackup)
+        );
+        assert_eq!(
+            decode_backup(r#"{"format":"meditation-backup","version":9}"#),
+            Err(BackupError::UnsupportedVersion(9))
+        );
+        assert!(matches!(
+            decode_backup(r#"{"format":"meditation-backup","version":1}"#),
+            Err(BackupError::Unreadable(_))
+        ));
+    }
+}
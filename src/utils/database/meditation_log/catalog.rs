@@ -0,0 +1,363 @@
+use super::meditation_data_builder::MeditationData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Colours handed out to new catalog entries, in order.
+pub const CATALOG_COLOURS: [&str; 8] = [
+    "#10b981", "#6366f1", "#f59e0b", "#ef4444", "#06b6d4", "#8b5cf6", "#84cc16", "#ec4899",
+];
+
+/// Categories every catalog starts with, as (display name, aliases).
+const DEFAULT_CATEGORIES: [(&str, &[&str]); 7] = [
+    ("Mindfulness", &["mindful", "vipassana"]),
+    ("Body Scan", &["bodyscan"]),
+    ("Breathing", &["breath", "breathwork", "pranayama"]),
+    ("Loving-Kindness", &["metta", "loving kindness"]),
+    ("Sleep", &[]),
+    ("Relaxation", &["relax"]),
+    ("Walking", &["walking meditation"]),
+];
+
+/// The two lists in a catalog.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CatalogKind {
+    Category,
+    Speaker,
+}
+
+/// A known category or speaker.
+///
+/// # Fields
+/// - `id`: A stable slug, e.g. `"body-scan"`, that survives renaming the entry.
+/// - `name`: The display name that is stored on sessions.
+/// - `colour`: A CSS colour for charts and labels.
+/// - `aliases`: Other spellings that mean the same entry.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub name: String,
+    pub colour: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// The known categories and speakers that free-text entries are normalised against.
+///
+/// # Fields
+/// - `categories`: Known categories.
+/// - `speakers`: Known speakers.
+/// - `reconciled`: Whether the stored log has been migrated to the catalog's names.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Catalog {
+    pub categories: Vec<CatalogEntry>,
+    pub speakers: Vec<CatalogEntry>,
+    #[serde(default)]
+    pub reconciled: bool,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        let mut catalog = Catalog {
+            categories: Vec::new(),
+            speakers: Vec::new(),
+            reconciled: false,
+        };
+        for (name, aliases) in DEFAULT_CATEGORIES {
+            catalog.insert(CatalogKind::Category, name);
+            if let Some(entry) = catalog.categories.last_mut() {
+                entry.aliases = aliases.iter().map(|alias| alias.to_string()).collect();
+            }
+        }
+        catalog
+    }
+}
+
+/// Trims and collapses runs of whitespace, e.g. `" Body   scan "` to `"Body scan"`.
+pub fn clean_text(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The form two spellings are compared in: cleaned and lowercase.
+fn match_key(value: &str) -> String {
+    clean_text(value).to_lowercase()
+}
+
+/// A URL-safe id for a name, e.g. `"Loving-Kindness"` to `"loving-kindness"`.
+fn slug(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "entry".to_string()
+    } else {
+        slug
+    }
+}
+
+/// The number of single-character edits that turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// How many typos a category of this length may contain and still match.
+fn typo_allowance(key: &str) -> usize {
+    match key.chars().count() {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+impl Catalog {
+    pub fn entries(&self, kind: CatalogKind) -> &[CatalogEntry] {
+        match kind {
+            CatalogKind::Category => &self.categories,
+            CatalogKind::Speaker => &self.speakers,
+        }
+    }
+
+    fn entries_mut(&mut self, kind: CatalogKind) -> &mut Vec<CatalogEntry> {
+        match kind {
+            CatalogKind::Category => &mut self.categories,
+            CatalogKind::Speaker => &mut self.speakers,
+        }
+    }
+
+    /// Finds the entry a free-text value refers to.
+    ///
+    /// Values match an entry's id, name or aliases ignoring case and extra whitespace.
+    /// Categories also match a single entry within a few typos, e.g. "Mindfullness";
+    /// speakers don't, since similar names often belong to different people.
+    pub fn resolve(&self, kind: CatalogKind, value: &str) -> Option<&CatalogEntry> {
+        let key = match_key(value);
+        if key.is_empty() {
+            return None;
+        }
+        let entries = self.entries(kind);
+
+        let exact = entries.iter().find(|entry| {
+            entry.id == key
+                || match_key(&entry.name) == key
+                || entry.aliases.iter().any(|alias| match_key(alias) == key)
+        });
+        if exact.is_some() || kind == CatalogKind::Speaker {
+            return exact;
+        }
+
+        let allowance = typo_allowance(&key);
+        let mut close = entries
+            .iter()
+            .filter(|entry| edit_distance(&match_key(&entry.name), &key) <= allowance);
+        match (close.next(), close.next()) {
+            (Some(entry), None) => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Adds an entry named after the cleaned `value`, with a unique id and the next colour.
+    ///
+    /// # Returns
+    /// The new entry.
+    pub fn insert(&mut self, kind: CatalogKind, value: &str) -> &CatalogEntry {
+        let name = clean_text(value);
+        let base = slug(&name);
+        let entries = self.entries_mut(kind);
+
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while entries.iter().any(|entry| entry.id == id) {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+        let colour = CATALOG_COLOURS[entries.len() % CATALOG_COLOURS.len()].to_string();
+
+        entries.push(CatalogEntry {
+            id,
+            name,
+            colour,
+            aliases: Vec::new(),
+        });
+        entries.last().expect("pushed above")
+    }
+
+    /// Maps a free-text value to its catalog name, adding a new entry for values never seen.
+    ///
+    /// # Returns
+    /// The display name to store, or `None` for a blank value, which is not added.
+    pub fn normalise(&mut self, kind: CatalogKind, value: &str) -> Option<String> {
+        if value.trim().is_empty() {
+            return None;
+        }
+        let entry = match self.resolve(kind, value) {
+            Some(entry) => entry,
+            None => self.insert(kind, value),
+        };
+        Some(entry.name.clone())
+    }
+
+    /// The colour of the entry a value refers to, if it is in the catalog.
+    pub fn colour(&self, kind: CatalogKind, value: &str) -> Option<&str> {
+        self.resolve(kind, value).map(|entry| entry.colour.as_str())
+    }
+}
+
+/// Maps each distinct value to its catalog name, visiting the most used values first.
+fn canonical_names<'a>(
+    values: impl Iterator<Item = &'a String>,
+    kind: CatalogKind,
+    catalog: &mut Catalog,
+) -> HashMap<String, String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value.as_str()).or_default() += 1;
+    }
+    let mut values: Vec<(&str, usize)> = counts.into_iter().collect();
+    values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    values
+        .into_iter()
+        .filter_map(|(value, _)| Some((value.to_string(), catalog.normalise(kind, value)?)))
+        .collect()
+}
+
+/// Migrates free-text categories and speakers in the log to catalog names.
+///
+/// Values are visited from the most used down, so when several spellings of a new value
+/// exist the most common one becomes its catalog name.
+///
+/// # Arguments
+/// * `meditations` - The whole meditation log.
+/// * `catalog` - The catalog to match against; unknown values are added to it.
+///
+/// # Returns
+/// The number of sessions that changed.
+pub fn reconcile_log(meditations: &mut [MeditationData], catalog: &mut Catalog) -> usize {
+    let categories = canonical_names(
+        meditations.iter().map(|meditation| &meditation.category),
+        CatalogKind::Category,
+        catalog,
+    );
+    let speakers = canonical_names(
+        meditations.iter().map(|meditation| &meditation.speaker),
+        CatalogKind::Speaker,
+        catalog,
+    );
+
+    let mut changed = 0;
+    for meditation in meditations.iter_mut() {
+        let mut touched = false;
+        for (value, names) in [
+            (&mut meditation.category, &categories),
+            (&mut meditation.speaker, &speakers),
+        ] {
+            if let Some(name) = names.get(value.as_str()).filter(|name| *name != value) {
+                *value = name.clone();
+                touched = true;
+            }
+        }
+        changed += usize::from(touched);
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_ignores_case_whitespace_and_aliases() {
+        let catalog = Catalog::default();
+        let name = |value| {
+            catalog
+                .resolve(CatalogKind::Category, value)
+                .map(|entry| entry.name.as_str())
+        };
+
+        assert_eq!(name("mindfulness "), Some("Mindfulness"));
+        assert_eq!(name("  body   SCAN"), Some("Body Scan"));
+        assert_eq!(name("metta"), Some("Loving-Kindness"));
+        assert_eq!(name("loving-kindness"), Some("Loving-Kindness"));
+        assert_eq!(name("Mindfullness"), Some("Mindfulness"));
+        assert_eq!(name("Yoga Nidra"), None);
+        assert_eq!(name("   "), None);
+    }
+
+    #[test]
+    fn test_speakers_need_a_close_match() {
+        let mut catalog = Catalog::default();
+        assert_eq!(
+            catalog.normalise(CatalogKind::Speaker, " sarah  jones"),
+            Some("sarah jones".to_string())
+        );
+        assert_eq!(
+            catalog.normalise(CatalogKind::Speaker, "Sarah Jones"),
+            Some("sarah jones".to_string())
+        );
+        assert_eq!(
+            catalog.normalise(CatalogKind::Speaker, "Sara Jones"),
+            Some("Sara Jones".to_string())
+        );
+        assert_eq!(catalog.speakers.len(), 2);
+        assert_eq!(catalog.speakers[0].id, "sarah-jones");
+    }
+
+    #[test]
+    fn test_insert_keeps_ids_unique() {
+        let mut catalog = Catalog::default();
+        catalog.insert(CatalogKind::Speaker, "Ajahn");
+        let second = catalog.insert(CatalogKind::Speaker, "ajahn");
+        assert_eq!(second.id, "ajahn-2");
+        assert_eq!(second.colour, CATALOG_COLOURS[1]);
+    }
+
+    #[test]
+    fn test_reconcile_log() {
+        let session = |category: &str, speaker: &str| MeditationData {
+            category: category.to_string(),
+            speaker: speaker.to_string(),
+            ..Default::default()
+        };
+        let mut log = vec![
+            session("Mindfulness", "Alice"),
+            session("mindfulness ", "alice"),
+            session("Mindfullness", "Alice"),
+            session("yoga nidra", "Bob"),
+            session("Yoga Nidra", "Bob"),
+            session("Yoga Nidra", "Bob"),
+        ];
+        let mut catalog = Catalog::default();
+
+        assert_eq!(reconcile_log(&mut log, &mut catalog), 3);
+        let categories: Vec<&str> = log.iter().map(|m| m.category.as_str()).collect();
+        assert_eq!(
+            categories,
+            vec![
+                "Mindfulness",
+                "Mindfulness",
+                "Mindfulness",
+                "Yoga Nidra",
+                "Yoga Nidra",
+                "Yoga Nidra"
+            ]
+        );
+        assert_eq!(log[1].speaker, "Alice");
+        assert_eq!(catalog.categories.len(), DEFAULT_CATEGORIES.len() + 1);
+        assert_eq!(catalog.speakers.len(), 2);
+
+        // Running it again changes nothing.
+        assert_eq!(reconcile_log(&mut log, &mut catalog), 0);
+    }
+}
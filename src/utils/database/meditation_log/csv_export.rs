@@ -0,0 +1,129 @@
+use super::meditation_data_builder::MeditationData;
+use chrono::prelude::*;
+use std::borrow::Cow;
+
+/// The header row of exported CSV files.
+pub const CSV_HEADER: &str =
+    "datetime,duration_seconds,category,speaker,mood_before,mood_after,rating,tags,notes";
+
+/// Characters that make spreadsheets read a cell as a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Escapes a free-text field.
+///
+/// A field that would start a formula is prefixed with `'`, so spreadsheets show it as
+/// text instead of running it. A field containing a delimiter, quote or line break is
+/// quoted, doubling inner quotes.
+fn escape_field(field: &str) -> Cow<'_, str> {
+    let field: Cow<'_, str> = if field.starts_with(FORMULA_PREFIXES) {
+        Cow::Owned(format!("'{}", field))
+    } else {
+        Cow::Borrowed(field)
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        field
+    }
+}
+
+fn optional_score(score: Option<u8>) -> String {
+    score.map(|score| score.to_string()).unwrap_or_default()
+}
+
+/// Exports sessions as CSV (RFC 4180), one row per session after a header row.
+///
+/// # Arguments
+///
+/// * `meditations` - The sessions to export, in the order they should appear.
+///
+/// # Returns
+///
+/// The CSV text with `\r\n` line endings. Datetimes are ISO 8601 in UTC, tags are
+/// separated by `;`, and missing optional values are left empty. Text that would run as a
+/// spreadsheet formula starts with `'`.
+pub fn to_csv(meditations: &[MeditationData]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push_str("\r\n");
+
+    for meditation in meditations {
+        let datetime = NaiveDateTime::from_timestamp_opt(meditation.datetime, 0)
+            .map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+            .unwrap_or_default();
+        let fields = [
+            datetime,
+            meditation.duration.to_string(),
+            escape_field(&meditation.category).into_owned(),
+            escape_field(&meditation.speaker).into_owned(),
+            optional_score(meditation.mood_before),
+            optional_score(meditation.mood_after),
+            optional_score(meditation.rating),
+            escape_field(&meditation.tags.join(";")).into_owned(),
+            escape_field(meditation.notes.as_deref().unwrap_or_default()).into_owned(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let meditations = vec![
+            MeditationData {
+                datetime: 1_707_552_000,
+                duration: 600,
+                category: "Sleep".to_string(),
+                speaker: "Bob".to_string(),
+                ..Default::default()
+            },
+            MeditationData {
+                datetime: 1_707_555_600,
+                duration: 900,
+                category: "Body scan, long".to_string(),
+                speaker: "Alice".to_string(),
+                mood_before: Some(2),
+                mood_after: Some(4),
+                rating: Some(5),
+                tags: vec!["calm".to_string(), "evening".to_string()],
+                notes: Some("Felt \"settled\"\nafterwards".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            to_csv(&meditations),
+            format!(
+                "{}\r\n{}\r\n{}\r\n",
+                CSV_HEADER,
+                "2024-02-10T08:00:00Z,600,Sleep,Bob,,,,,",
+                "2024-02-10T09:00:00Z,900,\"Body scan, long\",Alice,2,4,5,calm;evening,\"Felt \"\"settled\"\"\nafterwards\"",
+            )
+        );
+    }
+
+    #[test]
+    fn test_formulas_are_exported_as_text() {
+        let meditations = vec![MeditationData {
+            datetime: 1_707_552_000,
+            duration: 600,
+            category: "=HYPERLINK(\"http://example.com\",\"x\")".to_string(),
+            speaker: "@Alice".to_string(),
+            tags: vec!["+1".to_string(), "calm".to_string()],
+            notes: Some("-2 breaths, then rest".to_string()),
+            ..Default::default()
+        }];
+
+        assert_eq!(
+            to_csv(&meditations).lines().nth(1),
+            Some(
+                "2024-02-10T08:00:00Z,600,\"'=HYPERLINK(\"\"http://example.com\"\",\"\"x\"\")\",'@Alice,,,,'+1;calm,\"'-2 breaths, then rest\""
+            )
+        );
+        assert_eq!(escape_field("calm - evening"), "calm - evening");
+    }
+}
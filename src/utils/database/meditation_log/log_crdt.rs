@@ -0,0 +1,651 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{meditation_data_builder::MeditationData, session_plan::SessionPlan};
+
+/// Identifies one write that made a session present: the device and its count of such writes.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Dot {
+    pub device: String,
+    pub counter: u64,
+}
+
+/// A last-writer-wins register holding one field of a session.
+///
+/// # Fields
+/// - `value`: The field's value.
+/// - `at`: When it was written, in UNIX milliseconds.
+/// - `device`: The device that wrote it.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Lww<T> {
+    pub value: T,
+    pub at: i64,
+    pub device: String,
+}
+
+impl<T: Clone + PartialEq + Serialize> Lww<T> {
+    fn new(value: &T, at: i64, device: &str) -> Self {
+        Lww {
+            value: value.clone(),
+            at,
+            device: device.to_string(),
+        }
+    }
+
+    /// Whether this write replaces `other`. The later write wins; writes made in the same
+    /// millisecond are ordered by device and then by value, so every replica picks the same.
+    fn beats(&self, other: &Lww<T>) -> bool {
+        (self.at, &self.device)
+            .cmp(&(other.at, &other.device))
+            .then_with(|| {
+                serde_json::to_string(&self.value)
+                    .ok()
+                    .cmp(&serde_json::to_string(&other.value).ok())
+            })
+            == Ordering::Greater
+    }
+
+    fn merge(&mut self, other: &Lww<T>) {
+        if other.beats(self) {
+            *self = other.clone();
+        }
+    }
+
+    /// Writes `value` if it differs from the current one. The write is placed after the
+    /// current one, so it wins here even when the device clock is behind.
+    fn set(&mut self, value: &T, at: i64, device: &str) -> bool {
+        if self.value == *value {
+            return false;
+        }
+        *self = Lww::new(value, at.max(self.at + 1), device);
+        true
+    }
+}
+
+/// The fields of a session, each in its own register, so edits of different fields made on
+/// different devices are all kept.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SessionFields {
+    pub datetime: Lww<i64>,
+    pub duration: Lww<i32>,
+    pub category: Lww<String>,
+    pub speaker: Lww<String>,
+    pub plan: Lww<Option<SessionPlan>>,
+    pub notes: Lww<Option<String>>,
+    pub mood_before: Lww<Option<u8>>,
+    pub mood_after: Lww<Option<u8>>,
+    pub rating: Lww<Option<u8>>,
+    pub tags: Lww<Vec<String>>,
+}
+
+impl SessionFields {
+    fn new(meditation: &MeditationData, at: i64, device: &str) -> Self {
+        SessionFields {
+            datetime: Lww::new(&meditation.datetime, at, device),
+            duration: Lww::new(&meditation.duration, at, device),
+            category: Lww::new(&meditation.category, at, device),
+            speaker: Lww::new(&meditation.speaker, at, device),
+            plan: Lww::new(&meditation.plan, at, device),
+            notes: Lww::new(&meditation.notes, at, device),
+            mood_before: Lww::new(&meditation.mood_before, at, device),
+            mood_after: Lww::new(&meditation.mood_after, at, device),
+            rating: Lww::new(&meditation.rating, at, device),
+            tags: Lww::new(&meditation.tags, at, device),
+        }
+    }
+
+    /// Writes the fields of `meditation` that changed; returns whether any did.
+    fn set(&mut self, meditation: &MeditationData, at: i64, device: &str) -> bool {
+        // Not `any`, every field has to be written.
+        [
+            self.datetime.set(&meditation.datetime, at, device),
+            self.duration.set(&meditation.duration, at, device),
+            self.category.set(&meditation.category, at, device),
+            self.speaker.set(&meditation.speaker, at, device),
+            self.plan.set(&meditation.plan, at, device),
+            self.notes.set(&meditation.notes, at, device),
+            self.mood_before.set(&meditation.mood_before, at, device),
+            self.mood_after.set(&meditation.mood_after, at, device),
+            self.rating.set(&meditation.rating, at, device),
+            self.tags.set(&meditation.tags, at, device),
+        ]
+        .contains(&true)
+    }
+
+    fn merge(&mut self, other: &SessionFields) {
+        self.datetime.merge(&other.datetime);
+        self.duration.merge(&other.duration);
+        self.category.merge(&other.category);
+        self.speaker.merge(&other.speaker);
+        self.plan.merge(&other.plan);
+        self.notes.merge(&other.notes);
+        self.mood_before.merge(&other.mood_before);
+        self.mood_after.merge(&other.mood_after);
+        self.rating.merge(&other.rating);
+        self.tags.merge(&other.tags);
+    }
+
+    /// When any field was last written.
+    fn modified_at(&self) -> i64 {
+        [
+            self.datetime.at,
+            self.duration.at,
+            self.category.at,
+            self.speaker.at,
+            self.plan.at,
+            self.notes.at,
+            self.mood_before.at,
+            self.mood_after.at,
+            self.rating.at,
+            self.tags.at,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default()
+    }
+}
+
+/// One session in the replicated log.
+///
+/// Membership is an add-wins set: every add or edit leaves a dot, and a deletion tombstones
+/// the dots it has seen. The session is present while any dot isn't tombstoned, so an edit
+/// made on a device that hadn't seen a deletion brings the session back instead of being lost.
+///
+/// A deleted session keeps its fields: a session brought back that way must get the same
+/// fields whatever order the replicas merged in, including edits made before the deletion.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SessionCrdt {
+    pub adds: BTreeSet<Dot>,
+    pub removes: BTreeSet<Dot>,
+    pub fields: SessionFields,
+}
+
+impl SessionCrdt {
+    pub fn is_present(&self) -> bool {
+        self.adds.iter().any(|dot| !self.removes.contains(dot))
+    }
+
+    /// Merges another replica's state of the same session; returns whether this one changed.
+    pub fn merge(&mut self, other: &SessionCrdt) -> bool {
+        let before = self.clone();
+        self.adds.extend(other.adds.iter().cloned());
+        self.removes.extend(other.removes.iter().cloned());
+        self.fields.merge(&other.fields);
+        *self != before
+    }
+
+    /// The session's current values, stamped with the time of its latest field write.
+    pub fn to_meditation(&self, id: &str) -> MeditationData {
+        let fields = &self.fields;
+        MeditationData {
+            datetime: fields.datetime.value,
+            duration: fields.duration.value,
+            category: fields.category.value.clone(),
+            speaker: fields.speaker.value.clone(),
+            plan: fields.plan.value.clone(),
+            notes: fields.notes.value.clone(),
+            mood_before: fields.mood_before.value,
+            mood_after: fields.mood_after.value,
+            rating: fields.rating.value,
+            tags: fields.tags.value.clone(),
+            id: id.to_string(),
+            modified_at: fields.modified_at(),
+        }
+    }
+}
+
+/// One session of a stored `LogCrdt` with its id, so the replicated log is stored as a list
+/// of records like the meditation log, in the same envelope and encoding.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct StoredSession {
+    pub id: String,
+    #[serde(flatten)]
+    pub session: SessionCrdt,
+}
+
+/// The meditation log as a state-based CRDT, so copies changed on different devices can be
+/// merged without losing sessions.
+///
+/// Merging is commutative, associative and idempotent: replicas that have merged the same
+/// states hold the same log, whatever the order.
+///
+/// # Fields
+/// - `sessions`: Every session ever seen, by id, including deleted ones.
+/// - `counters`: The highest dot counter seen from each device.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct LogCrdt {
+    #[serde(default)]
+    sessions: BTreeMap<String, SessionCrdt>,
+    #[serde(default)]
+    counters: BTreeMap<String, u64>,
+}
+
+impl LogCrdt {
+    fn next_dot(&mut self, device: &str) -> Dot {
+        let counter = self.counters.entry(device.to_string()).or_default();
+        *counter += 1;
+        Dot {
+            device: device.to_string(),
+            counter: *counter,
+        }
+    }
+
+    /// Records the local log as changed by `device`, comparing it with the current state.
+    ///
+    /// New sessions are added, changed fields are written and sessions missing from
+    /// `meditations` are deleted. Sessions without an id are skipped. Writes are stamped with
+    /// the session's `modified_at`, or `now_ms` if it has none.
+    ///
+    /// # Returns
+    /// The ids of the sessions that changed.
+    pub fn observe(
+        &mut self,
+        meditations: &[MeditationData],
+        device: &str,
+        now_ms: i64,
+    ) -> Vec<String> {
+        let mut changed = Vec::new();
+        let mut seen = BTreeSet::new();
+        for meditation in meditations
+            .iter()
+            .filter(|meditation| !meditation.id.is_empty())
+        {
+            seen.insert(meditation.id.as_str());
+            let at = if meditation.modified_at > 0 {
+                meditation.modified_at
+            } else {
+                now_ms
+            };
+
+            let present = self
+                .sessions
+                .get(&meditation.id)
+                .is_some_and(SessionCrdt::is_present);
+            let written = self
+                .sessions
+                .get_mut(&meditation.id)
+                .is_some_and(|session| session.fields.set(meditation, at, device));
+            if present && !written {
+                continue;
+            }
+
+            let dot = self.next_dot(device);
+            self.sessions
+                .entry(meditation.id.clone())
+                .or_insert_with(|| SessionCrdt {
+                    adds: BTreeSet::new(),
+                    removes: BTreeSet::new(),
+                    fields: SessionFields::new(meditation, at, device),
+                })
+                .adds
+                .insert(dot);
+            changed.push(meditation.id.clone());
+        }
+
+        for (id, session) in &mut self.sessions {
+            if session.is_present() && !seen.contains(id.as_str()) {
+                session.removes.extend(session.adds.iter().cloned());
+                changed.push(id.clone());
+            }
+        }
+        changed
+    }
+
+    /// Merges another replica's state of the session `id`; returns whether it changed here.
+    pub fn merge_session(&mut self, id: &str, session: &SessionCrdt) -> bool {
+        for dot in session.adds.iter().chain(&session.removes) {
+            let counter = self.counters.entry(dot.device.clone()).or_default();
+            *counter = (*counter).max(dot.counter);
+        }
+        match self.sessions.get_mut(id) {
+            Some(local) => local.merge(session),
+            None => {
+                self.sessions.insert(id.to_string(), session.clone());
+                true
+            }
+        }
+    }
+
+    /// Merges another replica's state into this one.
+    ///
+    /// # Returns
+    /// The ids of the sessions that changed here.
+    pub fn merge(&mut self, other: &LogCrdt) -> Vec<String> {
+        for (device, counter) in &other.counters {
+            let local = self.counters.entry(device.clone()).or_default();
+            *local = (*local).max(*counter);
+        }
+        other
+            .sessions
+            .iter()
+            .filter(|(id, session)| self.merge_session(id, session))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// The state of the session `id`, including a deleted one.
+    pub fn session(&self, id: &str) -> Option<&SessionCrdt> {
+        self.sessions.get(id)
+    }
+
+    /// The ids of every session ever seen, including deleted ones.
+    pub fn ids(&self) -> impl Iterator<Item = &String> {
+        self.sessions.keys()
+    }
+
+    /// The sessions as records for storage; `from_stored` reads them back.
+    pub fn to_stored(&self) -> Vec<StoredSession> {
+        self.sessions
+            .iter()
+            .map(|(id, session)| StoredSession {
+                id: id.clone(),
+                session: session.clone(),
+            })
+            .collect()
+    }
+
+    /// Rebuilds a log from the records of `to_stored`.
+    ///
+    /// The counters aren't stored: every dot handed out stays in its session, so they are
+    /// the highest dot counter seen from each device.
+    pub fn from_stored(stored: Vec<StoredSession>) -> LogCrdt {
+        let mut log = LogCrdt::default();
+        for StoredSession { id, session } in stored {
+            log.merge_session(&id, &session);
+        }
+        log
+    }
+
+    /// The sessions that are present, oldest first.
+    pub fn meditations(&self) -> Vec<MeditationData> {
+        let mut meditations: Vec<MeditationData> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.is_present())
+            .map(|(id, session)| session.to_meditation(id))
+            .collect();
+        meditations.sort_by(|a, b| a.datetime.cmp(&b.datetime).then_with(|| a.id.cmp(&b.id)));
+        meditations
+    }
+}
+
+/// Merges two exported replica states into a new one.
+pub fn merge_logs(a: &LogCrdt, b: &LogCrdt) -> LogCrdt {
+    let mut merged = a.clone();
+    merged.merge(b);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::log_envelope::{decode_log, encode_log_as, LogEncoding};
+
+    fn session(id: &str, datetime: i64, duration: i32) -> MeditationData {
+        MeditationData {
+            id: id.to_string(),
+            datetime,
+            duration,
+            category: "Mindfulness".to_string(),
+            speaker: "Alice".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// A small deterministic generator, so failures can be replayed from their seed.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            // xorshift64*
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    /// A replica with its local view of the log.
+    struct Replica {
+        device: String,
+        log: LogCrdt,
+    }
+
+    impl Replica {
+        /// Makes a random local change: adding, editing or deleting a session.
+        fn change(&mut self, rng: &mut Rng, step: i64, now_ms: i64) {
+            let mut view = self.log.meditations();
+            match rng.below(4) {
+                0 => view.push(session(
+                    &format!("{}-{}", self.device, step),
+                    rng.below(1_000) as i64,
+                    rng.below(3_600) as i32,
+                )),
+                1 if !view.is_empty() => {
+                    let index = rng.below(view.len() as u64) as usize;
+                    view.remove(index);
+                }
+                2 if !view.is_empty() => {
+                    let index = rng.below(view.len() as u64) as usize;
+                    view[index].duration = rng.below(3_600) as i32;
+                    view[index].modified_at = now_ms;
+                }
+                _ if !view.is_empty() => {
+                    let index = rng.below(view.len() as u64) as usize;
+                    view[index].tags = vec![format!("tag{}", rng.below(3))];
+                    view[index].notes = Some(self.device.clone());
+                    view[index].modified_at = now_ms;
+                }
+                _ => {}
+            }
+            self.log.observe(&view, &self.device, now_ms);
+        }
+    }
+
+    /// Three replicas after a random history of local changes and partial merges.
+    fn random_replicas(seed: u64) -> Vec<LogCrdt> {
+        let mut rng = Rng(seed);
+        let mut replicas: Vec<Replica> = ["a", "b", "c"]
+            .iter()
+            .map(|device| Replica {
+                device: device.to_string(),
+                log: LogCrdt::default(),
+            })
+            .collect();
+        for step in 0..40 {
+            let at = rng.below(3) as usize;
+            // Clocks roughly agree but may run a little behind each other.
+            let now_ms = step * 10 + rng.below(15) as i64;
+            if rng.below(4) == 0 {
+                let from = rng.below(3) as usize;
+                let other = replicas[from].log.clone();
+                replicas[at].log.merge(&other);
+            } else {
+                replicas[at].change(&mut rng, step, now_ms);
+            }
+        }
+        replicas.into_iter().map(|replica| replica.log).collect()
+    }
+
+    #[test]
+    fn test_merge_is_commutative_associative_and_idempotent() {
+        for seed in 1..=200 {
+            let logs = random_replicas(seed);
+            let (a, b, c) = (&logs[0], &logs[1], &logs[2]);
+
+            assert_eq!(
+                merge_logs(a, b),
+                merge_logs(b, a),
+                "commutative, seed {}",
+                seed
+            );
+            assert_eq!(
+                merge_logs(&merge_logs(a, b), c),
+                merge_logs(a, &merge_logs(b, c)),
+                "associative, seed {}",
+                seed
+            );
+            assert_eq!(merge_logs(a, a), *a, "idempotent, seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_replicas_converge_whatever_the_merge_order() {
+        for seed in 1..=200 {
+            let logs = random_replicas(seed);
+            let mut forward = LogCrdt::default();
+            let mut backward = LogCrdt::default();
+            for log in &logs {
+                forward.merge(log);
+            }
+            for log in logs.iter().rev() {
+                backward.merge(log);
+            }
+            assert_eq!(
+                forward.meditations(),
+                backward.meditations(),
+                "seed {}",
+                seed
+            );
+            assert!(forward.merge(&backward).is_empty(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_edits_of_different_fields_are_both_kept() {
+        let mut phone = LogCrdt::default();
+        phone.observe(&[session("s", 100, 600)], "phone", 1_000);
+        let mut laptop = phone.clone();
+
+        let mut longer = session("s", 100, 900);
+        longer.modified_at = 2_000;
+        phone.observe(&[longer], "phone", 2_000);
+        let mut rated = session("s", 100, 600);
+        rated.rating = Some(5);
+        rated.modified_at = 3_000;
+        laptop.observe(&[rated], "laptop", 3_000);
+
+        let merged = merge_logs(&phone, &laptop).meditations();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].duration, 900);
+        assert_eq!(merged[0].rating, Some(5));
+        assert_eq!(merged[0].modified_at, 3_000);
+    }
+
+    #[test]
+    fn test_later_write_to_the_same_field_wins() {
+        let mut phone = LogCrdt::default();
+        phone.observe(&[session("s", 100, 600)], "phone", 1_000);
+        let mut laptop = phone.clone();
+
+        phone.observe(&[session("s", 100, 900)], "phone", 3_000);
+        laptop.observe(&[session("s", 100, 1_200)], "laptop", 2_000);
+
+        assert_eq!(merge_logs(&phone, &laptop).meditations()[0].duration, 900);
+        assert_eq!(merge_logs(&laptop, &phone).meditations()[0].duration, 900);
+    }
+
+    #[test]
+    fn test_concurrent_edit_wins_over_delete() {
+        let mut phone = LogCrdt::default();
+        phone.observe(&[session("s", 100, 600)], "phone", 1_000);
+        let mut laptop = phone.clone();
+
+        phone.observe(&[], "phone", 2_000);
+        laptop.observe(&[session("s", 100, 900)], "laptop", 2_000);
+
+        let merged = merge_logs(&phone, &laptop).meditations();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].duration, 900);
+    }
+
+    #[test]
+    fn test_delete_after_seeing_edits_removes_everywhere() {
+        let mut phone = LogCrdt::default();
+        phone.observe(&[session("s", 100, 600)], "phone", 1_000);
+        let mut laptop = phone.clone();
+        laptop.observe(&[session("s", 100, 900)], "laptop", 2_000);
+
+        phone.merge(&laptop);
+        assert_eq!(phone.observe(&[], "phone", 3_000), vec!["s".to_string()]);
+        laptop.merge(&phone);
+
+        assert!(laptop.meditations().is_empty());
+        assert!(!laptop.session("s").unwrap().is_present());
+    }
+
+    #[test]
+    fn test_observe_reports_only_changes() {
+        let mut log = LogCrdt::default();
+        let sessions = vec![session("a", 200, 600), session("b", 100, 300)];
+        assert_eq!(log.observe(&sessions, "d", 1), vec!["a", "b"]);
+        assert!(log.observe(&sessions, "d", 2).is_empty());
+        assert_eq!(
+            log.meditations()
+                .iter()
+                .map(|m| m.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["b", "a"]
+        );
+    }
+
+    #[test]
+    fn test_stored_sessions_round_trip() {
+        for seed in 1..=20 {
+            let replicas = random_replicas(seed);
+            let log = merge_logs(&replicas[0], &replicas[1]);
+            for encoding in [LogEncoding::Json, LogEncoding::Compact] {
+                let stored = encode_log_as(&log.to_stored(), encoding).unwrap();
+                let read = LogCrdt::from_stored(decode_log(&stored).unwrap());
+                assert_eq!(read, log, "{:?}, seed {}", encoding, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_revived_session_is_the_same_in_any_merge_order() {
+        let mut a = LogCrdt::default();
+        a.observe(&[session("x", 100, 600)], "a", 1_000);
+        let mut c = a.clone();
+
+        let mut noted = session("x", 100, 600);
+        noted.notes = Some("A notes".to_string());
+        noted.modified_at = 2_000;
+        a.observe(std::slice::from_ref(&noted), "a", 2_000);
+        let mut b = a.clone();
+        b.observe(&[], "b", 3_000);
+        // Concurrently with the deletion, so it brings the session back.
+        let mut longer = session("x", 100, 900);
+        longer.modified_at = 2_500;
+        c.observe(&[longer], "c", 2_500);
+
+        let left = merge_logs(&merge_logs(&a, &b), &c);
+        let right = merge_logs(&a, &merge_logs(&b, &c));
+        assert_eq!(left, right);
+        let merged = left.meditations();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].notes, Some("A notes".to_string()));
+        assert_eq!(merged[0].duration, 900);
+    }
+
+    #[test]
+    fn test_merging_own_dots_keeps_counters_ahead() {
+        let mut original = LogCrdt::default();
+        original.observe(&[session("a", 1, 60)], "d", 1);
+        original.observe(&[], "d", 2);
+
+        // The device lost its state and gets its own tombstoned session back from elsewhere.
+        let mut fresh = LogCrdt::default();
+        fresh.merge_session("a", original.session("a").unwrap());
+        fresh.observe(&[session("b", 1, 60)], "d", 3);
+        assert!(fresh.session("b").unwrap().is_present());
+    }
+}
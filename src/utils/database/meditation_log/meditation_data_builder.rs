@@ -1,4 +1,11 @@
+use super::catalog::{Catalog, CatalogKind};
+use super::session_plan::SessionPlan;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// The allowed values of mood and rating scores, from worst to best.
+pub const SCORE_RANGE: RangeInclusive<u8> = 1..=5;
 
 /// Represents data for a meditation session.
 ///
@@ -7,14 +14,120 @@ use serde::{Deserialize, Serialize};
 /// - `duration`: The duration of the meditation session in seconds.
 /// - `category`: A string categorizing the type of meditation.
 /// - `speaker`: The name of the speaker or guide leading the meditation session.
-#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+/// - `plan`: Optional. The session structure the timer followed, if any.
+/// - `notes`: Optional. Free-text journal notes about the session.
+/// - `mood_before`: Optional. Mood before the session, from 1 to 5.
+/// - `mood_after`: Optional. Mood after the session, from 1 to 5.
+/// - `rating`: Optional. How the session went, from 1 to 5.
+/// - `tags`: Free-form labels, trimmed and without duplicates.
+/// - `id`: Identifies the session across devices; empty until the session is first stored.
+/// - `modified_at`: When the session was last changed, in UNIX milliseconds; decides which
+///   version wins when synced copies conflict.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
 pub struct MeditationData {
     pub datetime: i64,
     pub duration: i32,
     pub category: String,
     pub speaker: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<SessionPlan>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mood_before: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mood_after: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub modified_at: i64,
+}
+
+fn is_zero(value: &i64) -> bool {
+    *value == 0
+}
+
+/// The fields of a `MeditationData` that are checked when building it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeditationField {
+    Datetime,
+    Duration,
+    Category,
+    Speaker,
+    MoodBefore,
+    MoodAfter,
+    Rating,
+}
+
+/// Reasons a `MeditationDataBuilder` can't build a `MeditationData`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MeditationDataError {
+    /// The datetime was never set, or is not after the UNIX epoch.
+    InvalidDatetime,
+    /// The duration was never set, or is not positive.
+    InvalidDuration,
+    /// The field was never set.
+    Missing(MeditationField),
+    /// The field is empty or only whitespace.
+    Blank(MeditationField),
+    /// The score is outside `SCORE_RANGE`.
+    OutOfRange(MeditationField),
+}
+
+impl MeditationDataError {
+    /// The field the error is about, so forms can show it next to the right input.
+    pub fn field(&self) -> MeditationField {
+        match self {
+            MeditationDataError::InvalidDatetime => MeditationField::Datetime,
+            MeditationDataError::InvalidDuration => MeditationField::Duration,
+            MeditationDataError::Missing(field)
+            | MeditationDataError::Blank(field)
+            | MeditationDataError::OutOfRange(field) => *field,
+        }
+    }
+}
+
+impl fmt::Display for MeditationField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            MeditationField::Datetime => "datetime",
+            MeditationField::Duration => "duration",
+            MeditationField::Category => "category",
+            MeditationField::Speaker => "speaker",
+            MeditationField::MoodBefore => "mood before",
+            MeditationField::MoodAfter => "mood after",
+            MeditationField::Rating => "rating",
+        };
+        f.write_str(name)
+    }
 }
 
+impl fmt::Display for MeditationDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeditationDataError::InvalidDatetime => f.write_str("datetime must be non-zero"),
+            MeditationDataError::InvalidDuration => f.write_str("duration must be non-zero"),
+            MeditationDataError::Missing(field) => write!(f, "{} is required", field),
+            MeditationDataError::Blank(field) => {
+                write!(f, "{} cannot be empty or whitespace", field)
+            }
+            MeditationDataError::OutOfRange(field) => write!(
+                f,
+                "{} must be between {} and {}",
+                field,
+                SCORE_RANGE.start(),
+                SCORE_RANGE.end()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MeditationDataError {}
+
 /// A builder for creating instances of `MeditationData`.
 ///
 /// This builder uses the builder pattern to create `MeditationData` instances, allowing
@@ -25,12 +138,24 @@ pub struct MeditationData {
 /// - `duration`: Optional. The session duration in seconds.
 /// - `category`: Optional. A string categorizing the meditation type.
 /// - `speaker`: Optional. The name of the meditation session's speaker or guide.
+/// - `plan`: Optional. The session structure the timer followed.
+/// - `notes`: Optional. Free-text journal notes.
+/// - `mood_before`: Optional. Mood before the session.
+/// - `mood_after`: Optional. Mood after the session.
+/// - `rating`: Optional. Session quality rating.
+/// - `tags`: Free-form labels.
 #[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct MeditationDataBuilder {
     datetime: Option<i64>,
     duration: Option<i32>,
     category: Option<String>,
     speaker: Option<String>,
+    plan: Option<SessionPlan>,
+    notes: Option<String>,
+    mood_before: Option<u8>,
+    mood_after: Option<u8>,
+    rating: Option<u8>,
+    tags: Vec<String>,
 }
 
 impl MeditationDataBuilder {
@@ -52,6 +177,18 @@ impl MeditationDataBuilder {
         self
     }
 
+    /// Sets the `datetime` field to a specific time, e.g. for a session entered by hand.
+    ///
+    /// # Arguments
+    /// - `timestamp`: The UNIX timestamp of the session's start.
+    ///
+    /// # Returns
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn datetime_at(&mut self, timestamp: i64) -> &mut Self {
+        self.datetime = Some(timestamp);
+        self
+    }
+
     /// Sets the `duration` of the meditation session.
     ///
     /// # Arguments
@@ -88,39 +225,167 @@ impl MeditationDataBuilder {
         self
     }
 
-    /// Finalizes the builder and returns a `MeditationData` instance.
+    /// Sets the `plan` the meditation session followed.
     ///
-    /// This method checks for the presence and validity of required fields. If any required field is missing or invalid,
-    /// it returns an `Err` with an appropriate error message.
+    /// # Arguments
+    /// - `plan`: The `SessionPlan` used by the timer.
     ///
     /// # Returns
-    /// `Result<MeditationData, String>` - `Ok(MeditationData)` if all fields are valid, or `Err(String)` with an error message.
-    pub fn build(&self) -> Result<MeditationData, String> {
-        let datetime = match self.datetime {
-            Some(dt) if dt > 0 => dt,
-            _ => return Err("datetime must be non-zero".into()),
-        };
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn plan(&mut self, plan: SessionPlan) -> &mut Self {
+        self.plan = Some(plan);
+        self
+    }
 
-        let duration = match self.duration {
-            Some(dur) if dur > 0 => dur,
-            _ => return Err("duration must be non-zero".into()),
-        };
+    /// Sets free-text `notes` about the session; blank notes are dropped.
+    ///
+    /// # Arguments
+    /// - `notes`: A value that can be converted into a `String`.
+    ///
+    /// # Returns
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn notes(&mut self, notes: impl Into<String>) -> &mut Self {
+        let notes = notes.into();
+        self.notes = (!notes.trim().is_empty()).then_some(notes);
+        self
+    }
+
+    /// Sets the mood before the session, from 1 to 5.
+    ///
+    /// # Returns
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn mood_before(&mut self, mood: u8) -> &mut Self {
+        self.mood_before = Some(mood);
+        self
+    }
+
+    /// Sets the mood after the session, from 1 to 5.
+    ///
+    /// # Returns
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn mood_after(&mut self, mood: u8) -> &mut Self {
+        self.mood_after = Some(mood);
+        self
+    }
+
+    /// Sets the session quality `rating`, from 1 to 5.
+    ///
+    /// # Returns
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn rating(&mut self, rating: u8) -> &mut Self {
+        self.rating = Some(rating);
+        self
+    }
 
-        let category = self.category.clone().ok_or("category is required")?;
-        if category.trim().is_empty() {
-            return Err("category cannot be empty or whitespace".into());
+    /// Sets the session's `tags`. Tags are trimmed, and blank or repeated tags are dropped.
+    ///
+    /// # Arguments
+    /// - `tags`: The tags, in the order they should be shown.
+    ///
+    /// # Returns
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn tags<I, S>(&mut self, tags: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.tags.clear();
+        for tag in tags {
+            let tag = tag.as_ref().trim();
+            if !tag.is_empty() && !self.tags.iter().any(|existing| existing == tag) {
+                self.tags.push(tag.to_string());
+            }
         }
+        self
+    }
 
-        let speaker = self.speaker.clone().ok_or("speaker is required")?;
-        if speaker.trim().is_empty() {
-            return Err("speaker cannot be empty or whitespace".into());
+    /// Replaces the category and speaker with their catalog names, so spelling variants such
+    /// as "mindfulness " and "Mindfullness" are stored as one value.
+    ///
+    /// Values not in the catalog yet are added to it. Blank or unset values are left alone,
+    /// so validation still reports them.
+    ///
+    /// # Arguments
+    /// - `catalog`: The catalog to normalise against.
+    ///
+    /// # Returns
+    /// A mutable reference to the builder itself (for chaining methods).
+    pub fn normalise(&mut self, catalog: &mut Catalog) -> &mut Self {
+        for (kind, value) in [
+            (CatalogKind::Category, &mut self.category),
+            (CatalogKind::Speaker, &mut self.speaker),
+        ] {
+            if let Some(name) = value
+                .as_deref()
+                .and_then(|value| catalog.normalise(kind, value))
+            {
+                *value = Some(name);
+            }
+        }
+        self
+    }
+
+    /// Checks every field and collects all problems, so they can be shown at once.
+    ///
+    /// # Returns
+    /// The validation errors in field order; empty if the builder is ready to build.
+    pub fn validate(&self) -> Vec<MeditationDataError> {
+        let mut errors = Vec::new();
+
+        if !matches!(self.datetime, Some(dt) if dt > 0) {
+            errors.push(MeditationDataError::InvalidDatetime);
+        }
+        if !matches!(self.duration, Some(dur) if dur > 0) {
+            errors.push(MeditationDataError::InvalidDuration);
+        }
+        for (field, value) in [
+            (MeditationField::Category, &self.category),
+            (MeditationField::Speaker, &self.speaker),
+        ] {
+            match value {
+                None => errors.push(MeditationDataError::Missing(field)),
+                Some(value) if value.trim().is_empty() => {
+                    errors.push(MeditationDataError::Blank(field))
+                }
+                Some(_) => {}
+            }
+        }
+        for (field, score) in [
+            (MeditationField::MoodBefore, self.mood_before),
+            (MeditationField::MoodAfter, self.mood_after),
+            (MeditationField::Rating, self.rating),
+        ] {
+            if score.is_some_and(|score| !SCORE_RANGE.contains(&score)) {
+                errors.push(MeditationDataError::OutOfRange(field));
+            }
+        }
+        errors
+    }
+
+    /// Finalizes the builder and returns a `MeditationData` instance.
+    ///
+    /// This method checks for the presence and validity of required fields. If any required field is missing or invalid,
+    /// it returns the first problem found; use `validate` to get all of them.
+    ///
+    /// # Returns
+    /// `Result<MeditationData, MeditationDataError>` - `Ok(MeditationData)` if all fields are valid, or the first error.
+    pub fn build(&self) -> Result<MeditationData, MeditationDataError> {
+        if let Some(error) = self.validate().into_iter().next() {
+            return Err(error);
         }
 
         Ok(MeditationData {
-            datetime,
-            duration,
-            category,
-            speaker,
+            datetime: self.datetime.unwrap_or_default(),
+            duration: self.duration.unwrap_or_default(),
+            category: self.category.clone().unwrap_or_default(),
+            speaker: self.speaker.clone().unwrap_or_default(),
+            plan: self.plan.clone(),
+            notes: self.notes.clone(),
+            mood_before: self.mood_before,
+            mood_after: self.mood_after,
+            rating: self.rating,
+            tags: self.tags.clone(),
+            ..Default::default()
         })
     }
 }
@@ -154,4 +419,103 @@ mod tests {
         // println!("{:?}", meditation_data_builder.unwrap());
         assert!(meditation_data_builder.is_err())
     }
+
+    #[test]
+    fn test_validate_reports_every_field() {
+        let errors = MeditationDataBuilder::new().speaker("\t").validate();
+
+        assert_eq!(
+            errors,
+            vec![
+                MeditationDataError::InvalidDatetime,
+                MeditationDataError::InvalidDuration,
+                MeditationDataError::Missing(MeditationField::Category),
+                MeditationDataError::Blank(MeditationField::Speaker),
+            ]
+        );
+        assert_eq!(errors[3].field(), MeditationField::Speaker);
+        assert_eq!(errors[2].to_string(), "category is required");
+    }
+
+    #[test]
+    fn test_journal_fields() {
+        let meditation = MeditationDataBuilder::new()
+            .datetime()
+            .duration(600)
+            .category("category")
+            .speaker("speaker")
+            .notes("  ")
+            .mood_before(2)
+            .mood_after(4)
+            .tags([" calm ", "", "morning", "calm"])
+            .build()
+            .unwrap();
+
+        assert_eq!(meditation.notes, None);
+        assert_eq!(
+            (meditation.mood_before, meditation.mood_after),
+            (Some(2), Some(4))
+        );
+        assert_eq!(meditation.rating, None);
+        assert_eq!(meditation.tags, vec!["calm", "morning"]);
+
+        let errors = MeditationDataBuilder::new()
+            .datetime()
+            .duration(600)
+            .category("category")
+            .speaker("speaker")
+            .mood_after(0)
+            .rating(6)
+            .validate();
+        assert_eq!(
+            errors,
+            vec![
+                MeditationDataError::OutOfRange(MeditationField::MoodAfter),
+                MeditationDataError::OutOfRange(MeditationField::Rating),
+            ]
+        );
+        assert_eq!(errors[1].to_string(), "rating must be between 1 and 5");
+    }
+
+    #[test]
+    fn test_normalise() {
+        let mut catalog = Catalog::default();
+        let meditation = MeditationDataBuilder::new()
+            .datetime()
+            .duration(600)
+            .category(" mindfulness")
+            .speaker("  Tara  Brach ")
+            .normalise(&mut catalog)
+            .build()
+            .unwrap();
+
+        assert_eq!(meditation.category, "Mindfulness");
+        assert_eq!(meditation.speaker, "Tara Brach");
+        assert_eq!(catalog.speakers.len(), 1);
+
+        let blank = MeditationDataBuilder::new()
+            .datetime()
+            .duration(600)
+            .category(" ")
+            .speaker("Tara Brach")
+            .normalise(&mut catalog)
+            .validate();
+        assert_eq!(
+            blank,
+            vec![MeditationDataError::Blank(MeditationField::Category)]
+        );
+    }
+
+    #[test]
+    fn test_datetime_at() {
+        let meditation = MeditationDataBuilder::new()
+            .datetime_at(1_707_552_000)
+            .duration(600)
+            .category("category")
+            .speaker("speaker")
+            .build()
+            .unwrap();
+
+        assert_eq!(meditation.datetime, 1_707_552_000);
+    }
 }
@@ -0,0 +1,244 @@
+use super::meditation_data_builder::MeditationData;
+
+/// Orders in which sessions can be listed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SortOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+    LongestFirst,
+    ShortestFirst,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 4] = [
+        SortOrder::NewestFirst,
+        SortOrder::OldestFirst,
+        SortOrder::LongestFirst,
+        SortOrder::ShortestFirst,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortOrder::NewestFirst => "Newest first",
+            SortOrder::OldestFirst => "Oldest first",
+            SortOrder::LongestFirst => "Longest first",
+            SortOrder::ShortestFirst => "Shortest first",
+        }
+    }
+}
+
+/// Filters and sorting for listing the meditation log.
+///
+/// # Fields
+/// - `from`: Optional. Only sessions starting at or after this UNIX timestamp.
+/// - `until`: Optional. Only sessions starting before this UNIX timestamp.
+/// - `category`: Optional. Only sessions with exactly this category.
+/// - `speaker`: Optional. Only sessions with exactly this speaker.
+/// - `tags`: Only sessions carrying all of these tags.
+/// - `sort`: The order of the results.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeditationQuery {
+    pub from: Option<i64>,
+    pub until: Option<i64>,
+    pub category: Option<String>,
+    pub speaker: Option<String>,
+    pub tags: Vec<String>,
+    pub sort: SortOrder,
+}
+
+/// A page of query results.
+#[derive(Debug, PartialEq)]
+pub struct QueryPage<'a> {
+    /// The sessions on the page, each with its position in the log so it can be edited or deleted.
+    pub items: Vec<(usize, &'a MeditationData)>,
+    /// The zero-based page number, clamped to the last page.
+    pub page: usize,
+    /// The number of pages; at least one, even when nothing matched.
+    pub page_count: usize,
+    /// The number of sessions matching the query across all pages.
+    pub total: usize,
+}
+
+impl MeditationQuery {
+    /// Checks whether a session passes every filter of the query.
+    pub fn matches(&self, meditation: &MeditationData) -> bool {
+        self.from.is_none_or(|from| meditation.datetime >= from)
+            && self.until.is_none_or(|until| meditation.datetime < until)
+            && self
+                .category
+                .as_ref()
+                .is_none_or(|category| &meditation.category == category)
+            && self
+                .speaker
+                .as_ref()
+                .is_none_or(|speaker| &meditation.speaker == speaker)
+            && self.tags.iter().all(|tag| meditation.tags.contains(tag))
+    }
+
+    /// Filters and sorts the log.
+    ///
+    /// # Arguments
+    ///
+    /// * `meditations` - The whole meditation log, in stored order.
+    ///
+    /// # Returns
+    ///
+    /// The matching sessions with their positions in `meditations`. Sessions that compare equal
+    /// keep their log order.
+    pub fn run<'a>(&self, meditations: &'a [MeditationData]) -> Vec<(usize, &'a MeditationData)> {
+        let mut results: Vec<(usize, &MeditationData)> = meditations
+            .iter()
+            .enumerate()
+            .filter(|(_, meditation)| self.matches(meditation))
+            .collect();
+
+        match self.sort {
+            SortOrder::NewestFirst => {
+                results.sort_by_key(|(_, meditation)| std::cmp::Reverse(meditation.datetime))
+            }
+            SortOrder::OldestFirst => results.sort_by_key(|(_, meditation)| meditation.datetime),
+            SortOrder::LongestFirst => {
+                results.sort_by_key(|(_, meditation)| std::cmp::Reverse(meditation.duration))
+            }
+            SortOrder::ShortestFirst => results.sort_by_key(|(_, meditation)| meditation.duration),
+        }
+        results
+    }
+
+    /// Runs the query and returns one page of the results.
+    ///
+    /// # Arguments
+    ///
+    /// * `meditations` - The whole meditation log, in stored order.
+    /// * `page` - The zero-based page to return; pages past the end return the last page.
+    /// * `per_page` - The number of sessions per page; at least one.
+    pub fn page<'a>(
+        &self,
+        meditations: &'a [MeditationData],
+        page: usize,
+        per_page: usize,
+    ) -> QueryPage<'a> {
+        let per_page = per_page.max(1);
+        let results = self.run(meditations);
+        let total = results.len();
+        let page_count = total.div_ceil(per_page).max(1);
+        let page = page.min(page_count - 1);
+
+        QueryPage {
+            items: results
+                .into_iter()
+                .skip(page * per_page)
+                .take(per_page)
+                .collect(),
+            page,
+            page_count,
+            total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(datetime: i64, duration: i32, category: &str, speaker: &str) -> MeditationData {
+        MeditationData {
+            datetime,
+            duration,
+            category: category.to_string(),
+            speaker: speaker.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn log() -> Vec<MeditationData> {
+        vec![
+            session(100, 600, "Mindfulness", "Alice"),
+            session(300, 1200, "Sleep", "Bob"),
+            session(200, 300, "Mindfulness", "Bob"),
+            session(400, 1200, "Mindfulness", "Alice"),
+        ]
+    }
+
+    fn positions(results: &[(usize, &MeditationData)]) -> Vec<usize> {
+        results.iter().map(|(index, _)| *index).collect()
+    }
+
+    #[test]
+    fn test_sort_orders_keep_log_positions() {
+        let log = log();
+        let run = |sort| {
+            positions(
+                &MeditationQuery {
+                    sort,
+                    ..Default::default()
+                }
+                .run(&log),
+            )
+        };
+
+        assert_eq!(run(SortOrder::NewestFirst), vec![3, 1, 2, 0]);
+        assert_eq!(run(SortOrder::OldestFirst), vec![0, 2, 1, 3]);
+        // Equal durations keep log order.
+        assert_eq!(run(SortOrder::LongestFirst), vec![1, 3, 0, 2]);
+        assert_eq!(run(SortOrder::ShortestFirst), vec![2, 0, 1, 3]);
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        let log = log();
+        let query = MeditationQuery {
+            from: Some(200),
+            until: Some(400),
+            category: Some("Mindfulness".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(positions(&query.run(&log)), vec![2]);
+
+        let query = MeditationQuery {
+            speaker: Some("Alice".to_string()),
+            sort: SortOrder::OldestFirst,
+            ..Default::default()
+        };
+        assert_eq!(positions(&query.run(&log)), vec![0, 3]);
+    }
+
+    #[test]
+    fn test_tag_filter_requires_every_tag() {
+        let mut log = log();
+        log[0].tags = vec!["morning".to_string(), "walking".to_string()];
+        log[2].tags = vec!["morning".to_string()];
+
+        let query = |tags: &[&str]| MeditationQuery {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            sort: SortOrder::OldestFirst,
+            ..Default::default()
+        };
+        assert_eq!(positions(&query(&["morning"]).run(&log)), vec![0, 2]);
+        assert_eq!(
+            positions(&query(&["morning", "walking"]).run(&log)),
+            vec![0]
+        );
+        assert!(query(&["retreat"]).run(&log).is_empty());
+    }
+
+    #[test]
+    fn test_pagination() {
+        let log = log();
+        let query = MeditationQuery::default();
+
+        let first = query.page(&log, 0, 3);
+        assert_eq!(positions(&first.items), vec![3, 1, 2]);
+        assert_eq!((first.page, first.page_count, first.total), (0, 2, 4));
+
+        // Past the end, e.g. after deleting the last session of the last page.
+        let last = query.page(&log, 5, 3);
+        assert_eq!(positions(&last.items), vec![0]);
+        assert_eq!(last.page, 1);
+
+        let empty = query.page(&[], 0, 3);
+        assert!(empty.items.is_empty());
+        assert_eq!((empty.page, empty.page_count, empty.total), (0, 1, 0));
+    }
+}
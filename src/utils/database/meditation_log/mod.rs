@@ -1,3 +1,19 @@
+pub mod backup;
 pub mod calculate_meditation_stats;
+pub mod catalog;
+pub mod csv_export;
+pub mod log_crdt;
 pub mod meditation_data_builder;
+pub mod meditation_query;
+pub mod mood_analysis;
+pub mod practice_calendar;
+pub mod read_write_backup;
+pub mod read_write_catalog;
+pub mod read_write_log_crdt;
 pub mod read_write_meditation_data;
+pub mod read_write_storage;
+pub mod session_plan;
+pub mod stats_aggregator;
+pub mod tag_manager;
+pub mod tag_stats;
+pub mod trend_analysis;
@@ -0,0 +1,164 @@
+use super::meditation_data_builder::MeditationData;
+use std::collections::HashMap;
+
+/// Running totals of the mood change across sessions that recorded a mood before and after.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MoodTotals {
+    sessions: u32,
+    delta_sum: i64,
+}
+
+impl MoodTotals {
+    /// Adds a session's mood change; sessions without both moods are ignored.
+    pub fn record(&mut self, meditation: &MeditationData) {
+        if let Some(delta) = mood_delta(meditation) {
+            self.sessions += 1;
+            self.delta_sum += delta as i64;
+        }
+    }
+
+    /// Number of sessions with a mood before and after.
+    pub fn sessions(&self) -> u32 {
+        self.sessions
+    }
+
+    /// The average mood change, or `None` if no session recorded both moods.
+    pub fn average_delta(&self) -> Option<f32> {
+        (self.sessions > 0).then(|| self.delta_sum as f32 / self.sessions as f32)
+    }
+}
+
+/// The mood change of a single session, positive if the mood improved.
+pub fn mood_delta(meditation: &MeditationData) -> Option<i32> {
+    Some(meditation.mood_after? as i32 - meditation.mood_before? as i32)
+}
+
+/// The average mood change of one group of sessions, e.g. one category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoodGroup {
+    pub label: String,
+    pub sessions: u32,
+    pub average_delta: f32,
+}
+
+/// How much sessions change the mood, overall and broken down by group.
+///
+/// Groups are sorted from the largest improvement to the smallest, and only include
+/// groups with at least one session that recorded a mood before and after.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoodImpact {
+    pub sessions: u32,
+    pub overall_delta: Option<f32>,
+    pub by_category: Vec<MoodGroup>,
+    pub by_speaker: Vec<MoodGroup>,
+    /// In the order of `DURATION_BUCKET_LABELS`, not by improvement.
+    pub by_duration: Vec<MoodGroup>,
+}
+
+fn to_groups<'a>(totals: impl IntoIterator<Item = (&'a str, &'a MoodTotals)>) -> Vec<MoodGroup> {
+    totals
+        .into_iter()
+        .filter_map(|(label, totals)| {
+            Some(MoodGroup {
+                label: label.to_string(),
+                sessions: totals.sessions(),
+                average_delta: totals.average_delta()?,
+            })
+        })
+        .collect()
+}
+
+/// Ranks groups from the largest mood improvement down, breaking ties by label.
+fn ranked(totals: &HashMap<String, MoodTotals>) -> Vec<MoodGroup> {
+    let mut groups = to_groups(
+        totals
+            .iter()
+            .map(|(label, totals)| (label.as_str(), totals)),
+    );
+    groups.sort_by(|a, b| {
+        b.average_delta
+            .total_cmp(&a.average_delta)
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    groups
+}
+
+/// Summarises mood totals into a `MoodImpact`.
+///
+/// # Arguments
+///
+/// * `overall` - Totals across all sessions.
+/// * `by_category` - Totals per category.
+/// * `by_speaker` - Totals per speaker.
+/// * `by_duration` - Totals per session length bucket, with their labels.
+pub fn mood_impact(
+    overall: &MoodTotals,
+    by_category: &HashMap<String, MoodTotals>,
+    by_speaker: &HashMap<String, MoodTotals>,
+    by_duration: &[(&str, MoodTotals)],
+) -> MoodImpact {
+    MoodImpact {
+        sessions: overall.sessions(),
+        overall_delta: overall.average_delta(),
+        by_category: ranked(by_category),
+        by_speaker: ranked(by_speaker),
+        by_duration: to_groups(by_duration.iter().map(|(label, totals)| (*label, totals))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(category: &str, before: Option<u8>, after: Option<u8>) -> MeditationData {
+        MeditationData {
+            category: category.to_string(),
+            mood_before: before,
+            mood_after: after,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_totals_skip_sessions_without_both_moods() {
+        let mut totals = MoodTotals::default();
+        assert_eq!(totals.average_delta(), None);
+
+        totals.record(&session("Sleep", Some(2), Some(4)));
+        totals.record(&session("Sleep", Some(3), None));
+        totals.record(&session("Sleep", Some(4), Some(3)));
+
+        assert_eq!(totals.sessions(), 2);
+        assert_eq!(totals.average_delta(), Some(0.5));
+    }
+
+    #[test]
+    fn test_groups_are_ranked() {
+        let mut by_category: HashMap<String, MoodTotals> = HashMap::new();
+        for meditation in [
+            session("Sleep", Some(3), Some(3)),
+            session("Body scan", Some(2), Some(4)),
+            session("Walking", Some(2), Some(3)),
+            session("Walking", Some(2), Some(4)),
+            session("Breathing", None, None),
+        ] {
+            by_category
+                .entry(meditation.category.clone())
+                .or_default()
+                .record(&meditation);
+        }
+
+        let impact = mood_impact(&MoodTotals::default(), &by_category, &HashMap::new(), &[]);
+        let labels: Vec<(&str, f32)> = impact
+            .by_category
+            .iter()
+            .map(|group| (group.label.as_str(), group.average_delta))
+            .collect();
+
+        assert_eq!(
+            labels,
+            vec![("Body scan", 2.0), ("Walking", 1.5), ("Sleep", 0.0)]
+        );
+        assert_eq!(impact.overall_delta, None);
+    }
+}
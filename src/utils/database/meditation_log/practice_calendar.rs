@@ -0,0 +1,157 @@
+use crate::utils::settings::app_settings::WeekStart;
+use chrono::prelude::*;
+use chrono::{Duration, Months};
+use std::collections::BTreeMap;
+
+/// Upper bounds (exclusive, in minutes) of the intensity buckets 1 to 3; anything above is bucket 4.
+const INTENSITY_THRESHOLDS: [f32; 3] = [10.0, 20.0, 40.0];
+
+/// A single day cell of the practice calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub minutes: f32,
+    /// Colour bucket from 0 (no practice) to 4 (40 minutes or more).
+    pub intensity: u8,
+}
+
+/// One calendar column, a week from its first day. Days outside the calendar range are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarWeek {
+    pub days: [Option<CalendarDay>; 7],
+    /// Short month name if a month starts in this week, e.g. "Feb".
+    pub month_label: Option<String>,
+}
+
+/// Maps the minutes meditated on a day to a colour intensity bucket.
+///
+/// # Returns
+///
+/// `0` for no practice, then `1` to `4` for increasing amounts of practice.
+pub fn intensity_bucket(minutes: f32) -> u8 {
+    if minutes <= 0.0 {
+        return 0;
+    }
+    INTENSITY_THRESHOLDS
+        .iter()
+        .position(|&threshold| minutes < threshold)
+        .map(|bucket| bucket as u8 + 1)
+        .unwrap_or(4)
+}
+
+/// Lays out the last 12 months of practice as week columns, ending with the week of `today`.
+///
+/// # Arguments
+///
+/// * `daily` - Minutes meditated per day.
+/// * `today` - The last day shown in the calendar.
+/// * `week_start` - The day each column starts on.
+///
+/// # Returns
+///
+/// The week columns from oldest to newest.
+pub fn build_practice_calendar(
+    daily: &BTreeMap<NaiveDate, f32>,
+    today: NaiveDate,
+    week_start: WeekStart,
+) -> Vec<CalendarWeek> {
+    let first_day = today
+        .checked_sub_months(Months::new(12))
+        .map(|date| date + Duration::days(1))
+        .unwrap_or(today);
+    let mut week_start = week_start.week_of(first_day);
+
+    let mut weeks = Vec::new();
+    while week_start <= today {
+        let days: [Option<CalendarDay>; 7] = std::array::from_fn(|offset| {
+            let date = week_start + Duration::days(offset as i64);
+            if date < first_day || date > today {
+                return None;
+            }
+            let minutes = daily.get(&date).copied().unwrap_or(0.0);
+            Some(CalendarDay {
+                date,
+                minutes,
+                intensity: intensity_bucket(minutes),
+            })
+        });
+
+        let month_label = days
+            .iter()
+            .flatten()
+            .find(|day| day.date.day() == 1)
+            .map(|day| day.date.format("%b").to_string());
+
+        weeks.push(CalendarWeek { days, month_label });
+        week_start += Duration::days(7);
+    }
+    weeks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_intensity_buckets() {
+        assert_eq!(intensity_bucket(0.0), 0);
+        assert_eq!(intensity_bucket(5.0), 1);
+        assert_eq!(intensity_bucket(10.0), 2);
+        assert_eq!(intensity_bucket(39.9), 3);
+        assert_eq!(intensity_bucket(90.0), 4);
+    }
+
+    #[test]
+    fn test_calendar_covers_last_twelve_months() {
+        // 2024-02-10 is a Saturday; the calendar starts on 2023-02-11, also a Saturday.
+        let today = date(2024, 2, 10);
+        let daily = BTreeMap::from([(today, 25.0), (date(2023, 2, 10), 60.0)]);
+        let weeks = build_practice_calendar(&daily, today, WeekStart::Monday);
+
+        assert_eq!(weeks.len(), 53);
+
+        let first = &weeks[0];
+        assert!(first.days[4].is_none());
+        assert_eq!(first.days[5].as_ref().unwrap().date, date(2023, 2, 11));
+
+        let last = &weeks[52];
+        let today_cell = last.days[5].as_ref().unwrap();
+        assert_eq!(today_cell.minutes, 25.0);
+        assert_eq!(today_cell.intensity, 3);
+        assert!(last.days[6].is_none());
+    }
+
+    #[test]
+    fn test_weeks_start_on_the_chosen_day() {
+        let today = date(2024, 2, 10);
+        let weeks = build_practice_calendar(&BTreeMap::new(), today, WeekStart::Saturday);
+
+        assert_eq!(weeks.len(), 53);
+        let first = weeks[0].days[0].as_ref().unwrap();
+        assert_eq!(first.date, date(2023, 2, 11));
+        assert_eq!(first.date.weekday(), Weekday::Sat);
+        let last = &weeks[52];
+        assert_eq!(last.days[0].as_ref().unwrap().date, today);
+        assert!(last.days[1].is_none());
+    }
+
+    #[test]
+    fn test_month_labels() {
+        let weeks = build_practice_calendar(&BTreeMap::new(), date(2024, 2, 10), WeekStart::Monday);
+        let labels: Vec<&str> = weeks
+            .iter()
+            .filter_map(|week| week.month_label.as_deref())
+            .collect();
+
+        assert_eq!(
+            labels,
+            vec![
+                "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec", "Jan", "Feb"
+            ]
+        );
+    }
+}
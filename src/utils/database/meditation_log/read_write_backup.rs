@@ -0,0 +1,50 @@
+use chrono::{Local, Utc};
+use web_sys::wasm_bindgen::JsValue;
+
+use super::{
+    backup::{decode_backup, encode_backup, BackupError},
+    read_write_log_crdt::{store_merged_log, try_track_meditation_log},
+};
+use crate::{
+    services::file_download::FileDownload,
+    utils::sync::{read_write_sync_state::record_sync_changes, sync_record::SyncRecord},
+};
+
+/// Offers the whole log, including deletions, as a backup file download.
+///
+/// # Returns
+/// A `Result<(), JsValue>`; `Err(JsValue)` if the log can't be read or the file can't be
+/// created.
+pub fn export_backup() -> Result<(), JsValue> {
+    let log = try_track_meditation_log().map_err(|error| JsValue::from_str(&error.to_string()))?;
+    let backup = encode_backup(&log, Utc::now().timestamp_millis())
+        .map_err(|error| JsValue::from_str(&error.to_string()))?;
+    let file_name = format!("meditation-backup-{}.json", Local::now().format("%Y-%m-%d"));
+    FileDownload::save_text(&file_name, "application/json", &backup)
+}
+
+/// Merges a backup file into the log.
+///
+/// Sessions changed or deleted since the backup was made keep their newer state; restored
+/// sessions are queued for sync.
+///
+/// # Arguments
+/// * `json` - The contents of the backup file.
+///
+/// # Returns
+/// The number of sessions that were restored or changed, or why the file can't be restored.
+pub fn restore_backup(json: &str) -> Result<usize, BackupError> {
+    let backup = decode_backup(json)?;
+    let mut log =
+        try_track_meditation_log().map_err(|error| BackupError::Storage(error.to_string()))?;
+    let changed = log.merge(&backup.log);
+    if !changed.is_empty() {
+        store_merged_log(&log).map_err(|error| BackupError::Storage(error.to_string()))?;
+        record_sync_changes(
+            changed
+                .iter()
+                .filter_map(|id| SyncRecord::from_log(&log, id)),
+        );
+    }
+    Ok(changed.len())
+}
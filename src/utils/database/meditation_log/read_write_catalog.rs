@@ -0,0 +1,65 @@
+use super::catalog::{reconcile_log, Catalog};
+use super::read_write_meditation_data::try_change_meditations;
+use crate::utils::{
+    constants::CATALOG_KEY, database::protected_storage::ProtectedStorage,
+    profile::read_write_profiles::profile_key,
+};
+use web_sys::wasm_bindgen::JsValue;
+
+/// Reads the stored catalog of categories and speakers.
+///
+/// # Returns
+/// The stored `Catalog`, or the default catalog if nothing is stored or it can't be read.
+pub fn read_catalog() -> Catalog {
+    ProtectedStorage::read(&profile_key(CATALOG_KEY))
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default()
+}
+
+/// Stores the catalog of categories and speakers.
+/// Logs an error message to the web console on failure.
+///
+/// # Arguments
+/// * `catalog` - The catalog to store.
+pub fn save_catalog(catalog: &Catalog) {
+    match serde_json::to_string(catalog) {
+        Ok(value) => {
+            ProtectedStorage::create(&profile_key(CATALOG_KEY), &value).unwrap_or_else(|_| {
+                web_sys::console::log_1(&JsValue::from_str("Failed to write catalog"));
+            })
+        }
+        Err(_) => web_sys::console::log_1(&JsValue::from_str("Serialization failed")),
+    }
+}
+
+/// Migrates the free-text categories and speakers of the stored log to catalog names.
+///
+/// Runs until it succeeds once; afterwards new sessions are normalised as they are saved.
+/// While the log can't be read or rewritten, it is tried again on the next call.
+///
+/// # Returns
+/// The number of sessions that changed, `0` if the log was already reconciled or couldn't
+/// be; the error is logged to the web console then.
+pub fn reconcile_meditation_log() -> usize {
+    let mut catalog = read_catalog();
+    if catalog.reconciled {
+        return 0;
+    }
+
+    match try_change_meditations(|meditations| reconcile_log(meditations, &mut catalog)) {
+        Ok(changed) => {
+            catalog.reconciled = true;
+            save_catalog(&catalog);
+            changed
+        }
+        Err(error) => {
+            web_sys::console::log_1(&JsValue::from_str(&format!(
+                "Log not reconciled yet: {}",
+                error
+            )));
+            0
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::Utc;
+use web_sys::wasm_bindgen::JsValue;
+
+use super::{
+    log_crdt::LogCrdt,
+    read_write_meditation_data::{
+        meditation_log_encoding, replace_meditations, try_read_meditation_data,
+    },
+};
+use crate::{
+    services::local_storage::StorageError,
+    utils::{
+        constants::LOG_CRDT_KEY,
+        database::{
+            log_envelope::{decode_log, encode_log_as},
+            protected_storage::ProtectedStorage,
+        },
+        profile::read_write_profiles::profile_key,
+        sync::{
+            read_write_sync_state::{record_sync_changes, try_read_sync_state},
+            sync_record::{assign_missing_ids, SyncRecord},
+        },
+    },
+};
+
+/// Reads a stored replicated log, in a `LogEnvelope` or, as stored before, a bare `LogCrdt`.
+fn decode_log_crdt(value: &str) -> serde_json::Result<LogCrdt> {
+    decode_log(value)
+        .map(LogCrdt::from_stored)
+        .or_else(|error| serde_json::from_str(value).map_err(|_| error))
+}
+
+/// Reads the replicated state of the log, telling an empty one apart from one that can't be
+/// read.
+///
+/// It may lack sessions stored since it was last tracked; merge changes from elsewhere into
+/// the log `try_track_meditation_log` returns, so storing the merge deletes none of them.
+///
+/// # Returns
+/// The stored `LogCrdt`, an empty one if none is stored, or a `StorageError` if it can't be
+/// read.
+pub fn try_read_log_crdt() -> Result<LogCrdt, StorageError> {
+    match ProtectedStorage::read(&profile_key(LOG_CRDT_KEY))? {
+        Some(value) => {
+            decode_log_crdt(&value).map_err(|error| StorageError::Unreadable(error.to_string()))
+        }
+        None => Ok(LogCrdt::default()),
+    }
+}
+
+/// Writes the replicated state of the log in the encoding of the stored log.
+fn write_log_crdt(log: &LogCrdt) -> Result<(), StorageError> {
+    let value = encode_log_as(&log.to_stored(), meditation_log_encoding())
+        .map_err(|error| StorageError::Failed(format!("serialization failed: {}", error)))?;
+    ProtectedStorage::create(&profile_key(LOG_CRDT_KEY), &value)
+}
+
+/// Stores the replicated state of the log.
+///
+/// Logs an error message to the web console on failure.
+pub fn save_log_crdt(log: &LogCrdt) {
+    write_log_crdt(log).unwrap_or_else(|error| {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Failed to write replicated log: {}",
+            error
+        )));
+    })
+}
+
+/// Rewrites the replicated state of the log after the stored log changed encoding.
+///
+/// # Returns
+/// A `StorageError` if nothing was written; a replicated log that can't be read is left
+/// untouched.
+pub fn reencode_log_crdt() -> Result<(), StorageError> {
+    match ProtectedStorage::read(&profile_key(LOG_CRDT_KEY))? {
+        Some(value) => write_log_crdt(
+            &decode_log_crdt(&value)
+                .map_err(|error| StorageError::Unreadable(error.to_string()))?,
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Records the stored log in its replicated state and queues the changed sessions for sync.
+///
+/// Call after every change to the stored log. Sessions stored before sessions had ids are
+/// given one first. Logs an error message to the web console if that isn't possible; see
+/// `try_track_meditation_log`.
+pub fn track_meditation_log() {
+    if let Err(error) = try_track_meditation_log() {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Sessions not tracked for sync: {}",
+            error
+        )));
+    }
+}
+
+/// Records the stored log in its replicated state and queues the changed sessions for sync,
+/// like `track_meditation_log`.
+///
+/// # Returns
+/// The replicated state, holding every stored session, to merge changes from elsewhere into.
+/// A `StorageError` if the stored log, its replicated state or the sync state can't be read,
+/// so none is mistaken for an empty one, or if new session ids can't be stored.
+pub fn try_track_meditation_log() -> Result<LogCrdt, StorageError> {
+    let mut meditations = try_read_meditation_data()
+        .ok_or_else(|| StorageError::Unreadable("the stored sessions can't be read".to_string()))?;
+    let device_id = try_read_sync_state()?.device_id;
+    let now_ms = Utc::now().timestamp_millis();
+    // Ids that weren't stored would be different next time, so stop until they are.
+    if assign_missing_ids(&mut meditations, &device_id, now_ms) > 0 {
+        replace_meditations(&meditations)?;
+    }
+
+    let mut log = try_read_log_crdt()?;
+    let changed = log.observe(&meditations, &device_id, now_ms);
+    if !changed.is_empty() {
+        save_log_crdt(&log);
+        record_sync_changes(
+            changed
+                .iter()
+                .filter_map(|id| SyncRecord::from_log(&log, id)),
+        );
+    }
+    Ok(log)
+}
+
+/// Stores a replicated log that gained changes from elsewhere and rewrites the stored log
+/// from it. `log` must be the one `try_track_meditation_log` returned, with the changes
+/// merged in.
+///
+/// The log is written first; if it can't be, the replicated state isn't either, so the
+/// merged changes aren't mistaken for local deletions later.
+///
+/// # Returns
+/// A `StorageError` if the log couldn't be written.
+pub fn store_merged_log(log: &LogCrdt) -> Result<(), StorageError> {
+    replace_meditations(&log.meditations())?;
+    save_log_crdt(log);
+    Ok(())
+}
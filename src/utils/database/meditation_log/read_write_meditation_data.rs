@@ -1,35 +1,249 @@
+use std::cell::RefCell;
+
+use web_sys::wasm_bindgen::JsValue;
+
 use super::meditation_data_builder::MeditationData;
-use super::meditation_data_builder::MeditationDataBuilder;
-use crate::utils::database::database_manager::DatabaseManager;
+use super::meditation_data_builder::{MeditationDataBuilder, MeditationDataError};
+use super::read_write_catalog::{read_catalog, save_catalog};
+use super::read_write_log_crdt::{reencode_log_crdt, track_meditation_log};
+use crate::{
+    services::local_storage::StorageError,
+    utils::{
+        constants::MEDITATION_LOG_KEY,
+        database::{database_manager::DatabaseManager, log_envelope::LogEncoding},
+        profile::read_write_profiles::profile_key,
+        sync::read_write_sync_state::stamp_change,
+    },
+};
+
+thread_local! {
+    /// Finished sessions that didn't fit in storage, oldest first, kept until there is space.
+    static PENDING_MEDITATIONS: RefCell<Vec<MeditationData>> = const { RefCell::new(Vec::new()) };
+}
 
 /// Logs meditation data into the database.
 ///
 /// This function takes in meditation data parameters, constructs a MeditationData object using
 /// the MeditationDataBuilder, and then attempts to write this data to the database using the
-/// DatabaseManager. If the construction of the MeditationData object fails, it does nothing.
+/// DatabaseManager. If the construction of the MeditationData object fails, nothing is written.
 ///
 /// # Arguments
-/// * `datetime` - An i32 representing the date and time of the meditation session.
 /// * `duration` - An i32 representing the duration of the meditation session in seconds.
 /// * `category` - A String specifying the category of the meditation session (e.g., "Mindfulness").
 /// * `speaker` - A String specifying the name of the speaker or guide of the meditation session.
 ///
+/// # Returns
+/// `Result<MeditationData, MeditationDataError>` - the logged session, so callers can fold it
+/// into running stats, or the validation error from the builder.
+pub fn log_meditation_data(
+    duration: i32,
+    category: String,
+    speaker: String,
+) -> Result<MeditationData, MeditationDataError> {
+    log_meditation(
+        MeditationDataBuilder::new()
+            .duration(duration)
+            .category(category)
+            .speaker(speaker),
+    )
+}
+
+/// Logs a meditation session described by a prepared `MeditationDataBuilder`.
+///
+/// Use this instead of `log_meditation_data` when the session carries more than a duration,
+/// category and speaker, e.g. a `SessionPlan`. The session is timestamped with the current
+/// time; use `save_meditation` for a session that already has its datetime.
+///
+/// # Arguments
+/// * `builder` - The builder holding the session's fields.
+///
+/// # Returns
+/// `Result<MeditationData, MeditationDataError>` - the logged session, or the validation error
+/// from the builder.
+pub fn log_meditation(
+    builder: &mut MeditationDataBuilder,
+) -> Result<MeditationData, MeditationDataError> {
+    save_meditation(builder.datetime())
+}
+
+/// Builds the session with its category and speaker normalised against the stored catalog.
+///
+/// The catalog is only written when the session is valid and added a new entry.
+fn build_normalised(
+    builder: &MeditationDataBuilder,
+) -> Result<MeditationData, MeditationDataError> {
+    let mut catalog = read_catalog();
+    let known = catalog.clone();
+    let meditation_data = builder.clone().normalise(&mut catalog).build()?;
+
+    if catalog != known {
+        save_catalog(&catalog);
+    }
+    Ok(meditation_data)
+}
+
+/// Adds a meditation session to the log with the datetime already set on the builder,
+/// e.g. a session entered by hand after the fact.
+///
+/// The category and speaker are stored under their catalog names. The session is given an
+/// id and tracked for sync. A session that can't be stored, e.g. because storage is full, is
+/// kept in memory and stored with the next one, or by `save_pending_meditations`.
+///
+/// # Arguments
+/// * `builder` - The builder holding the session's fields, including its datetime.
+///
+/// # Returns
+/// `Result<MeditationData, MeditationDataError>` - the saved session, or the validation error
+/// from the builder.
+pub fn save_meditation(
+    builder: &MeditationDataBuilder,
+) -> Result<MeditationData, MeditationDataError> {
+    let mut meditation_data = build_normalised(builder)?;
+    stamp_change(&mut meditation_data, None);
+
+    PENDING_MEDITATIONS.with(|pending| pending.borrow_mut().push(meditation_data.clone()));
+    if let Err(error) = save_pending_meditations() {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Session kept until it can be stored: {}",
+            error
+        )));
+    }
+    Ok(meditation_data)
+}
+
+/// The number of finished sessions waiting for space in storage.
+pub fn pending_meditation_count() -> usize {
+    PENDING_MEDITATIONS.with(|pending| pending.borrow().len())
+}
+
+/// Stores the finished sessions that didn't fit earlier, oldest first.
+///
+/// # Returns
+/// The number of sessions stored, or the `StorageError` that stopped the first one that
+/// still doesn't fit; it and later sessions stay pending.
+pub fn save_pending_meditations() -> Result<usize, StorageError> {
+    let mut saved = 0;
+    let result = loop {
+        let Some(next) = PENDING_MEDITATIONS.with(|pending| pending.borrow().first().cloned())
+        else {
+            break Ok(saved);
+        };
+        if let Err(error) = DatabaseManager::write_data(next, &profile_key(MEDITATION_LOG_KEY)) {
+            break Err(error);
+        }
+        PENDING_MEDITATIONS.with(|pending| pending.borrow_mut().remove(0));
+        saved += 1;
+    };
+    if saved > 0 {
+        track_meditation_log();
+    }
+    result
+}
+
+/// Replaces a logged meditation session with an edited version.
+///
+/// The edited session keeps the id of the one it replaces. Sessions are found by id, since
+/// their positions change whenever synced changes are merged or sessions are deleted.
+///
+/// # Arguments
+/// * `id` - The id of the session to replace.
+/// * `builder` - The builder holding the edited fields.
+///
+/// # Returns
+/// `Ok(Some(MeditationData))` with the saved session, `Ok(None)` if the log has no session
+/// with `id`, or the validation error from the builder.
+pub fn update_meditation(
+    id: &str,
+    builder: &MeditationDataBuilder,
+) -> Result<Option<MeditationData>, MeditationDataError> {
+    let mut meditation_data = build_normalised(builder)?;
+    let Some(previous) = find_meditation(id) else {
+        return Ok(None);
+    };
+    stamp_change(&mut meditation_data, Some(&previous));
 
-// TODO - store key in constant file.
-const MEDITATION_LOG_KEY: &str = "meditationLog";
+    let updated = DatabaseManager::update_data(
+        id,
+        meditation_data.clone(),
+        &profile_key(MEDITATION_LOG_KEY),
+    )
+    .map(|_| meditation_data);
+    if updated.is_some() {
+        track_meditation_log();
+    }
+    Ok(updated)
+}
 
-pub fn log_meditation_data(duration: i32, category: String, speaker: String) {
-    let meditation_data_builder = MeditationDataBuilder::new()
-        .datetime()
-        .duration(duration)
-        .category(category)
-        .speaker(speaker)
-        .build();
+/// Removes a meditation session from the log.
+///
+/// # Arguments
+/// * `id` - The id of the session to remove.
+///
+/// # Returns
+/// The removed session, or `None` if the log has no session with `id`.
+pub fn delete_meditation(id: &str) -> Option<MeditationData> {
+    let removed = DatabaseManager::delete_data(id, &profile_key(MEDITATION_LOG_KEY))?;
+    track_meditation_log();
+    Some(removed)
+}
 
-    match meditation_data_builder {
-        Ok(meditation_data) => DatabaseManager::write_data(meditation_data, MEDITATION_LOG_KEY),
-        Err(_) => (),
+/// Changes many sessions at once, e.g. renaming a tag across the whole log.
+///
+/// The log is read, passed to `change`, and written back in one go, but only if `change`
+/// reports that something changed. Changed sessions are stamped and tracked for sync.
+///
+/// # Arguments
+/// * `change` - Edits the sessions in place and returns how many it changed.
+///
+/// # Returns
+/// The number of sessions that changed, or 0 if the changes couldn't be stored; the error
+/// is logged to the web console then.
+pub fn change_meditations(change: impl FnOnce(&mut [MeditationData]) -> usize) -> usize {
+    try_change_meditations(change).unwrap_or_else(|error| {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Changes not stored: {}",
+            error
+        )));
+        0
+    })
+}
+
+/// Changes many sessions at once like `change_meditations`, telling a log that wasn't
+/// changed apart from one that couldn't be.
+///
+/// # Returns
+/// The number of sessions that changed, or a `StorageError` if the log can't be read or the
+/// changes couldn't be stored; `change` isn't called on a log that can't be read.
+pub fn try_change_meditations(
+    change: impl FnOnce(&mut [MeditationData]) -> usize,
+) -> Result<usize, StorageError> {
+    let mut meditations = try_read_meditation_data()
+        .ok_or_else(|| StorageError::Unreadable("the log can't be read".to_string()))?;
+    let before = meditations.clone();
+    let changed = change(&mut meditations);
+    if changed > 0 {
+        for (meditation, previous) in meditations.iter_mut().zip(&before) {
+            if meditation != previous {
+                stamp_change(meditation, Some(previous));
+            }
+        }
+        DatabaseManager::replace_data(&meditations, &profile_key(MEDITATION_LOG_KEY))?;
+        track_meditation_log();
     }
+    Ok(changed)
+}
+
+/// Replaces the whole log, e.g. with the result of merging synced changes.
+///
+/// Nothing is stamped or tracked for sync.
+///
+/// # Arguments
+/// * `meditations` - The new log.
+///
+/// # Returns
+/// A `StorageError` if nothing was written.
+pub fn replace_meditations(meditations: &[MeditationData]) -> Result<(), StorageError> {
+    DatabaseManager::replace_data(meditations, &profile_key(MEDITATION_LOG_KEY))
 }
 
 /// The function `read_meditation_data` reads meditation data from a database.
@@ -38,5 +252,39 @@ pub fn log_meditation_data(duration: i32, category: String, speaker: String) {
 ///
 /// A vector of `MeditationData` objects is being returned.
 pub fn read_meditation_data() -> Vec<MeditationData> {
-    DatabaseManager::read_data(MEDITATION_LOG_KEY)
+    DatabaseManager::read_data(&profile_key(MEDITATION_LOG_KEY))
+}
+
+/// The logged session with `id`, or `None` if there is none.
+pub fn find_meditation(id: &str) -> Option<MeditationData> {
+    if id.is_empty() {
+        return None;
+    }
+    read_meditation_data()
+        .into_iter()
+        .find(|meditation| meditation.id == id)
+}
+
+/// Reads the log, or `None` if it is stored but can't be read, e.g. because a newer version
+/// of the app wrote it.
+pub fn try_read_meditation_data() -> Option<Vec<MeditationData>> {
+    DatabaseManager::try_read_data(&profile_key(MEDITATION_LOG_KEY))
+}
+
+/// How the stored log is encoded.
+pub fn meditation_log_encoding() -> LogEncoding {
+    DatabaseManager::encoding(&profile_key(MEDITATION_LOG_KEY))
+}
+
+/// Rewrites the stored log and its replicated state in `encoding`, which later writes keep
+/// using.
+///
+/// # Arguments
+/// * `encoding` - `LogEncoding::Compact` to save space on large logs, or `LogEncoding::Json`.
+///
+/// # Returns
+/// A `StorageError` if the log or its replicated state wasn't written.
+pub fn set_meditation_log_encoding(encoding: LogEncoding) -> Result<(), StorageError> {
+    DatabaseManager::set_encoding(&profile_key(MEDITATION_LOG_KEY), encoding)?;
+    reencode_log_crdt()
 }
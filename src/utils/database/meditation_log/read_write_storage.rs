@@ -0,0 +1,55 @@
+use chrono::Local;
+use web_sys::wasm_bindgen::JsValue;
+
+use super::{
+    read_write_log_crdt::track_meditation_log,
+    read_write_meditation_data::{replace_meditations, try_read_meditation_data},
+};
+use crate::{
+    services::{file_download::FileDownload, local_storage::LocalStorageService},
+    utils::database::{
+        log_envelope::encode_log,
+        storage_quota::{split_archive, StorageUsage},
+    },
+};
+
+/// Estimates how much of its local storage the app uses.
+///
+/// # Returns
+/// The usage, or `None` if local storage can't be inspected.
+pub fn storage_usage() -> Option<StorageUsage> {
+    LocalStorageService::used_bytes()
+        .ok()
+        .map(StorageUsage::of_local_storage)
+}
+
+/// Moves the sessions from before `cutoff` into a downloaded archive file, freeing their space.
+///
+/// The file is offered before anything is removed, so a failed download loses nothing.
+/// Archived sessions are deleted like any other, also on devices this one syncs with; their
+/// replicated state stays, so edits made elsewhere still merge, and compact storage shrinks it.
+///
+/// # Arguments
+/// * `cutoff` - A UNIX timestamp; sessions that started earlier are archived.
+///
+/// # Returns
+/// The number of sessions archived, or an error if the log can't be read, the file can't be
+/// created, or the shortened log can't be stored.
+pub fn archive_meditations_before(cutoff: i64) -> Result<usize, JsValue> {
+    let meditations = try_read_meditation_data()
+        .ok_or_else(|| JsValue::from_str("the stored sessions can't be read"))?;
+    let (keep, archive) = split_archive(&meditations, cutoff);
+    if archive.is_empty() {
+        return Ok(0);
+    }
+
+    let file = encode_log(&archive).map_err(|error| JsValue::from_str(&error.to_string()))?;
+    let file_name = format!(
+        "meditation-archive-{}.json",
+        Local::now().format("%Y-%m-%d")
+    );
+    FileDownload::save_text(&file_name, "application/json", &file)?;
+    replace_meditations(&keep).map_err(|error| JsValue::from_str(&error.to_string()))?;
+    track_meditation_log();
+    Ok(archive.len())
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+
+/// The parts a meditation session is made of, in order.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum PhaseKind {
+    Preparation,
+    MainSit,
+    Closing,
+}
+
+impl PhaseKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PhaseKind::Preparation => "Preparation",
+            PhaseKind::MainSit => "Sitting",
+            PhaseKind::Closing => "Closing",
+        }
+    }
+}
+
+/// The kinds of bell rung during a session.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum BellKind {
+    /// Rung when the main sit begins.
+    Start,
+    /// Rung every `interval_bell_seconds` during the main sit.
+    Interval,
+    /// Rung when the closing phase begins.
+    Closing,
+    /// Rung when the whole session is over.
+    End,
+}
+
+impl BellKind {
+    /// How many times the bell is struck.
+    pub fn strikes(&self) -> u32 {
+        match self {
+            BellKind::Interval => 1,
+            BellKind::Start | BellKind::Closing => 2,
+            BellKind::End => 3,
+        }
+    }
+}
+
+/// A bell due at a point in the session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bell {
+    pub at_seconds: i64,
+    pub kind: BellKind,
+}
+
+/// The phase a session is in at a given moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhasePosition {
+    pub kind: PhaseKind,
+    pub remaining_seconds: i64,
+}
+
+/// The structure of a meditation session.
+///
+/// # Fields
+/// - `preparation_seconds`: Settling in before the first bell.
+/// - `main_seconds`: The main sit.
+/// - `interval_bell_seconds`: Time between bells during the main sit; `0` for none.
+/// - `closing_seconds`: Time after the closing bell, e.g. for a short reflection.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct SessionPlan {
+    pub preparation_seconds: i64,
+    pub main_seconds: i64,
+    pub interval_bell_seconds: i64,
+    pub closing_seconds: i64,
+}
+
+impl SessionPlan {
+    /// A plan with only a main sit of `main_seconds`.
+    pub fn simple(main_seconds: i64) -> Self {
+        SessionPlan {
+            main_seconds,
+            ..Default::default()
+        }
+    }
+
+    /// The length of the whole session in seconds.
+    pub fn total_seconds(&self) -> i64 {
+        self.preparation_seconds.max(0) + self.main_seconds.max(0) + self.closing_seconds.max(0)
+    }
+
+    /// The non-empty phases of the plan with their lengths in seconds, in order.
+    pub fn phases(&self) -> Vec<(PhaseKind, i64)> {
+        [
+            (PhaseKind::Preparation, self.preparation_seconds),
+            (PhaseKind::MainSit, self.main_seconds),
+            (PhaseKind::Closing, self.closing_seconds),
+        ]
+        .into_iter()
+        .filter(|(_, seconds)| *seconds > 0)
+        .collect()
+    }
+
+    /// Finds the phase `elapsed_seconds` into the session.
+    ///
+    /// # Returns
+    /// The phase and the seconds left in it, or `None` once the session is over.
+    pub fn phase_at(&self, elapsed_seconds: i64) -> Option<PhasePosition> {
+        let mut phase_end = 0;
+        for (kind, seconds) in self.phases() {
+            phase_end += seconds;
+            if elapsed_seconds < phase_end {
+                return Some(PhasePosition {
+                    kind,
+                    remaining_seconds: phase_end - elapsed_seconds,
+                });
+            }
+        }
+        None
+    }
+
+    /// Lists every bell of the session in order.
+    pub fn bells(&self) -> Vec<Bell> {
+        let main_start = self.preparation_seconds.max(0);
+        let main_end = main_start + self.main_seconds.max(0);
+        let mut bells = Vec::new();
+
+        if self.main_seconds > 0 {
+            bells.push(Bell {
+                at_seconds: main_start,
+                kind: BellKind::Start,
+            });
+        }
+        if self.interval_bell_seconds > 0 {
+            let mut at = main_start + self.interval_bell_seconds;
+            while at < main_end {
+                bells.push(Bell {
+                    at_seconds: at,
+                    kind: BellKind::Interval,
+                });
+                at += self.interval_bell_seconds;
+            }
+        }
+        if self.closing_seconds > 0 {
+            bells.push(Bell {
+                at_seconds: main_end,
+                kind: BellKind::Closing,
+            });
+        }
+        bells.push(Bell {
+            at_seconds: self.total_seconds(),
+            kind: BellKind::End,
+        });
+        bells
+    }
+
+    /// Lists the bells that fall after `from_ms` and up to and including `to_ms` of elapsed time.
+    ///
+    /// Calling this with the elapsed time of consecutive timer ticks rings each bell exactly once,
+    /// however irregular the ticks are.
+    pub fn bells_between(&self, from_ms: i64, to_ms: i64) -> Vec<Bell> {
+        self.bells()
+            .into_iter()
+            .filter(|bell| bell.at_seconds * 1000 > from_ms && bell.at_seconds * 1000 <= to_ms)
+            .collect()
+    }
+
+    /// Describes the plan in a few words, e.g. "2m prep · 20m sit · bell every 5m".
+    pub fn summary(&self) -> String {
+        let minutes = |seconds: i64| match seconds % 60 {
+            0 => format!("{}m", seconds / 60),
+            _ => format!("{}m {}s", seconds / 60, seconds % 60),
+        };
+        let mut parts = Vec::new();
+        if self.preparation_seconds > 0 {
+            parts.push(format!("{} prep", minutes(self.preparation_seconds)));
+        }
+        parts.push(format!("{} sit", minutes(self.main_seconds.max(0))));
+        if self.interval_bell_seconds > 0 {
+            parts.push(format!(
+                "bell every {}",
+                minutes(self.interval_bell_seconds)
+            ));
+        }
+        if self.closing_seconds > 0 {
+            parts.push(format!("{} closing", minutes(self.closing_seconds)));
+        }
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> SessionPlan {
+        SessionPlan {
+            preparation_seconds: 60,
+            main_seconds: 600,
+            interval_bell_seconds: 240,
+            closing_seconds: 120,
+        }
+    }
+
+    #[test]
+    fn test_phases() {
+        let plan = plan();
+        assert_eq!(plan.total_seconds(), 780);
+        assert_eq!(
+            plan.phase_at(0),
+            Some(PhasePosition {
+                kind: PhaseKind::Preparation,
+                remaining_seconds: 60
+            })
+        );
+        assert_eq!(plan.phase_at(60).unwrap().kind, PhaseKind::MainSit);
+        assert_eq!(plan.phase_at(700).unwrap().remaining_seconds, 80);
+        assert_eq!(plan.phase_at(780), None);
+        assert_eq!(
+            SessionPlan::simple(300).phases(),
+            vec![(PhaseKind::MainSit, 300)]
+        );
+    }
+
+    #[test]
+    fn test_bells() {
+        let at: Vec<(i64, BellKind)> = plan()
+            .bells()
+            .into_iter()
+            .map(|bell| (bell.at_seconds, bell.kind))
+            .collect();
+        assert_eq!(
+            at,
+            vec![
+                (60, BellKind::Start),
+                (300, BellKind::Interval),
+                (540, BellKind::Interval),
+                (660, BellKind::Closing),
+                (780, BellKind::End),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bells_between_rings_each_bell_once() {
+        let plan = plan();
+        assert!(plan.bells_between(0, 59_999).is_empty());
+        assert_eq!(plan.bells_between(59_999, 60_000)[0].kind, BellKind::Start);
+        assert!(plan.bells_between(60_000, 60_250).is_empty());
+        // A long gap, e.g. after the phone slept, returns everything that was missed.
+        assert_eq!(plan.bells_between(60_000, 600_000).len(), 2);
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(
+            plan().summary(),
+            "1m prep · 10m sit · bell every 4m · 2m closing"
+        );
+        assert_eq!(SessionPlan::simple(90).summary(), "1m 30s sit");
+    }
+}
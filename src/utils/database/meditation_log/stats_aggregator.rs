@@ -0,0 +1,462 @@
+use super::calculate_meditation_stats::Stats;
+use super::meditation_data_builder::MeditationData;
+use super::mood_analysis::{mood_impact, MoodTotals};
+use super::tag_stats::{tag_summaries, TagSummary, TagTotals};
+use super::trend_analysis::{add_daily_minutes, calculate_trends, StatsCalendar};
+use chrono::prelude::*;
+use chrono::Duration;
+use std::collections::{BTreeMap, HashMap};
+
+/// Upper bounds (exclusive, in minutes) of the session length buckets; the last bucket is open.
+pub const DURATION_BUCKET_LIMITS: [i32; 5] = [10, 20, 30, 45, 60];
+
+/// Display labels for the session length buckets, one more than `DURATION_BUCKET_LIMITS`.
+pub const DURATION_BUCKET_LABELS: [&str; 6] = ["<10", "10-20", "20-30", "30-45", "45-60", "60+"];
+
+/// Running totals over a meditation log that can be updated one session at a time.
+///
+/// Recording a session only touches the counters for that session, so newly logged
+/// meditations can be folded in without reading or walking the rest of the log.
+///
+/// Days and weeks are counted in the aggregator's `StatsCalendar`; changing it means
+/// aggregating the log again.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsAggregator {
+    calendar: StatsCalendar,
+    total_duration: i64,
+    total_sessions: u32,
+    category_counts: HashMap<String, u32>,
+    speaker_counts: HashMap<String, u32>,
+    daily_minutes: BTreeMap<NaiveDate, f32>,
+    duration_buckets: [u32; 6],
+    mood: MoodTotals,
+    mood_by_category: HashMap<String, MoodTotals>,
+    mood_by_speaker: HashMap<String, MoodTotals>,
+    mood_by_duration: [MoodTotals; 6],
+    tag_totals: HashMap<String, TagTotals>,
+}
+
+/// The index into `DURATION_BUCKET_LABELS` for a session of `duration` seconds.
+pub fn duration_bucket(duration: i32) -> usize {
+    let minutes = duration / 60;
+    DURATION_BUCKET_LIMITS
+        .iter()
+        .position(|&limit| minutes < limit)
+        .unwrap_or(DURATION_BUCKET_LIMITS.len())
+}
+
+impl StatsAggregator {
+    /// Constructs an empty `StatsAggregator` that counts days in UTC and weeks from Monday.
+    pub fn new() -> Self {
+        StatsAggregator::default()
+    }
+
+    /// Constructs an empty `StatsAggregator` that counts days and weeks in `calendar`.
+    pub fn with_calendar(calendar: StatsCalendar) -> Self {
+        StatsAggregator {
+            calendar,
+            ..StatsAggregator::default()
+        }
+    }
+
+    /// Constructs a `StatsAggregator` by recording every session in a single pass, counting
+    /// days in UTC and weeks from Monday.
+    ///
+    /// # Arguments
+    ///
+    /// * `meditations` - The meditation sessions to aggregate.
+    pub fn from_meditations(meditations: &[MeditationData]) -> Self {
+        Self::from_meditations_in(meditations, StatsCalendar::default())
+    }
+
+    /// Constructs a `StatsAggregator` by recording every session in a single pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `meditations` - The meditation sessions to aggregate.
+    /// * `calendar` - The time zone and week start days and weeks are counted in.
+    pub fn from_meditations_in(meditations: &[MeditationData], calendar: StatsCalendar) -> Self {
+        let mut aggregator = StatsAggregator::with_calendar(calendar);
+        for meditation in meditations {
+            aggregator.record(meditation);
+        }
+        aggregator
+    }
+
+    /// The time zone and week start days and weeks are counted in.
+    pub fn calendar(&self) -> StatsCalendar {
+        self.calendar
+    }
+
+    /// Today's date in the aggregator's time zone, for `stats`.
+    pub fn today(&self) -> NaiveDate {
+        self.calendar.today()
+    }
+
+    /// Folds a single meditation session into the running totals.
+    ///
+    /// Category and speaker names are only cloned the first time they are seen.
+    ///
+    /// # Arguments
+    ///
+    /// * `meditation` - The meditation session to add.
+    pub fn record(&mut self, meditation: &MeditationData) {
+        self.total_duration += meditation.duration as i64;
+        self.total_sessions += 1;
+        Self::increment(&mut self.category_counts, &meditation.category);
+        Self::increment(&mut self.speaker_counts, &meditation.speaker);
+        add_daily_minutes(&mut self.daily_minutes, meditation, self.calendar.timezone);
+
+        let bucket = duration_bucket(meditation.duration);
+        self.duration_buckets[bucket] += 1;
+
+        if meditation.mood_before.is_some() && meditation.mood_after.is_some() {
+            self.mood.record(meditation);
+            self.mood_by_duration[bucket].record(meditation);
+            Self::mood_totals(&mut self.mood_by_category, &meditation.category).record(meditation);
+            Self::mood_totals(&mut self.mood_by_speaker, &meditation.speaker).record(meditation);
+        }
+
+        for tag in &meditation.tags {
+            match self.tag_totals.get_mut(tag) {
+                Some(totals) => totals.record(meditation),
+                None => {
+                    let mut totals = TagTotals::default();
+                    totals.record(meditation);
+                    self.tag_totals.insert(tag.clone(), totals);
+                }
+            }
+        }
+    }
+
+    /// Minutes meditated per day, in the aggregator's time zone, across all recorded sessions.
+    pub fn daily_minutes(&self) -> &BTreeMap<NaiveDate, f32> {
+        &self.daily_minutes
+    }
+
+    /// Number of sessions per category.
+    pub fn category_counts(&self) -> &HashMap<String, u32> {
+        &self.category_counts
+    }
+
+    /// Number of sessions per speaker.
+    pub fn speaker_counts(&self) -> &HashMap<String, u32> {
+        &self.speaker_counts
+    }
+
+    /// Number of sessions in each length bucket, see `DURATION_BUCKET_LABELS`.
+    pub fn duration_buckets(&self) -> &[u32; 6] {
+        &self.duration_buckets
+    }
+
+    /// Categories used so far, most used first, e.g. for autocomplete suggestions.
+    pub fn categories_by_use(&self) -> Vec<String> {
+        Self::by_use(&self.category_counts)
+    }
+
+    /// Speakers used so far, most used first, e.g. for autocomplete suggestions.
+    pub fn speakers_by_use(&self) -> Vec<String> {
+        Self::by_use(&self.speaker_counts)
+    }
+
+    /// Tags used so far, most used first, e.g. for autocomplete suggestions and filters.
+    pub fn tags_by_use(&self) -> Vec<String> {
+        self.tag_summaries()
+            .into_iter()
+            .map(|summary| summary.tag)
+            .collect()
+    }
+
+    /// Sessions, minutes and mood change per tag, most used tags first.
+    pub fn tag_summaries(&self) -> Vec<TagSummary> {
+        tag_summaries(&self.tag_totals)
+    }
+
+    /// Lists the keys by descending count, breaking ties alphabetically.
+    fn by_use(counts: &HashMap<String, u32>) -> Vec<String> {
+        let mut keys: Vec<(&String, &u32)> = counts.iter().collect();
+        keys.sort_by(|(a_key, a_count), (b_key, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+        });
+        keys.into_iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    fn mood_totals<'a>(
+        totals: &'a mut HashMap<String, MoodTotals>,
+        key: &str,
+    ) -> &'a mut MoodTotals {
+        if !totals.contains_key(key) {
+            totals.insert(key.to_string(), MoodTotals::default());
+        }
+        totals.get_mut(key).expect("inserted above")
+    }
+
+    fn increment(counts: &mut HashMap<String, u32>, key: &str) {
+        match counts.get_mut(key) {
+            Some(count) => *count += 1,
+            None => {
+                counts.insert(key.to_string(), 1);
+            }
+        }
+    }
+
+    /// Finds the most frequent key, breaking ties alphabetically so the result is stable.
+    fn most_frequent(counts: &HashMap<String, u32>) -> String {
+        counts
+            .iter()
+            .max_by(|(a_key, a_count), (b_key, b_count)| {
+                a_count.cmp(b_count).then_with(|| b_key.cmp(a_key))
+            })
+            .map(|(key, _)| key.clone())
+            .unwrap_or_default()
+    }
+
+    /// Counts the consecutive days with at least one session, ending today or yesterday.
+    fn current_streak(&self, today: NaiveDate) -> u32 {
+        let mut day = if self.daily_minutes.contains_key(&today) {
+            today
+        } else {
+            today - Duration::days(1)
+        };
+
+        let mut streak = 0;
+        while self.daily_minutes.contains_key(&day) {
+            streak += 1;
+            day -= Duration::days(1);
+        }
+        streak
+    }
+
+    /// Produces the `Stats` for the recorded sessions.
+    ///
+    /// # Arguments
+    ///
+    /// * `today` - The date that streaks and trends are measured against.
+    pub fn stats(&self, today: NaiveDate) -> Stats {
+        let average_duration_per_meditation = if self.total_sessions == 0 {
+            0.0 // Avoid division by zero if there are no meditations.
+        } else {
+            self.total_duration as f32 / self.total_sessions as f32
+        };
+
+        Stats {
+            total_hours_meditated: self.total_duration as f32 / 3600.0,
+            average_duration_per_meditation,
+            days_meditated_in_row: self.current_streak(today),
+            total_meditation_sessions: self.total_sessions,
+            favorite_category: Self::most_frequent(&self.category_counts),
+            favorite_speaker: Self::most_frequent(&self.speaker_counts),
+            trends: calculate_trends(&self.daily_minutes, today, self.calendar.week_start),
+            mood: mood_impact(
+                &self.mood,
+                &self.mood_by_category,
+                &self.mood_by_speaker,
+                &std::array::from_fn::<_, 6, _>(|bucket| {
+                    (
+                        DURATION_BUCKET_LABELS[bucket],
+                        self.mood_by_duration[bucket],
+                    )
+                }),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        database::meditation_log::calculate_meditation_stats::{
+            create_mock_meditations, StatsBuilder,
+        },
+        settings::app_settings::{TimeZoneSetting, WeekStart},
+    };
+
+    #[test]
+    fn test_record_matches_full_rebuild() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+        let meditations = create_mock_meditations();
+        let (history, latest) = meditations.split_at(meditations.len() - 1);
+
+        let mut aggregator = StatsAggregator::from_meditations(history);
+        aggregator.record(&latest[0]);
+
+        let incremental = aggregator.stats(today);
+        let rebuilt = StatsBuilder::new(meditations.clone()).today(today).build();
+
+        assert_eq!(
+            incremental.total_hours_meditated,
+            rebuilt.total_hours_meditated
+        );
+        assert_eq!(incremental.total_meditation_sessions, 6);
+        assert_eq!(
+            incremental.days_meditated_in_row,
+            rebuilt.days_meditated_in_row
+        );
+        assert_eq!(incremental.favorite_category, rebuilt.favorite_category);
+        assert_eq!(incremental.favorite_speaker, rebuilt.favorite_speaker);
+        assert_eq!(incremental.trends, rebuilt.trends);
+        assert_eq!(incremental.mood, rebuilt.mood);
+    }
+
+    #[test]
+    fn test_mood_impact() {
+        let session = |speaker: &str, duration: i32, before: u8, after: u8| MeditationData {
+            duration,
+            category: "Mindfulness".to_string(),
+            speaker: speaker.to_string(),
+            mood_before: Some(before),
+            mood_after: Some(after),
+            ..Default::default()
+        };
+        let mut aggregator = StatsAggregator::from_meditations(&[
+            session("Alice", 600, 2, 4),
+            session("Bob", 1500, 3, 3),
+        ]);
+        aggregator.record(&MeditationData {
+            mood_before: Some(1),
+            ..session("Bob", 1500, 1, 1)
+        });
+        aggregator.record(&MeditationData {
+            mood_after: None,
+            ..session("Bob", 1500, 1, 5)
+        });
+
+        let mood = aggregator
+            .stats(NaiveDate::from_ymd_opt(2024, 2, 10).unwrap())
+            .mood;
+
+        assert_eq!(mood.sessions, 3);
+        assert_eq!(mood.overall_delta, Some(2.0 / 3.0));
+        assert_eq!(mood.by_speaker[0].label, "Alice");
+        assert_eq!(mood.by_speaker[1].average_delta, 0.0);
+        assert_eq!(mood.by_category[0].sessions, 3);
+        let durations: Vec<&str> = mood
+            .by_duration
+            .iter()
+            .map(|group| group.label.as_str())
+            .collect();
+        assert_eq!(durations, vec!["10-20", "20-30"]);
+    }
+
+    #[test]
+    fn test_streak_counts_days_not_sessions() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+        let at = |day: u32, hour: u32| MeditationData {
+            datetime: NaiveDate::from_ymd_opt(2024, 2, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .timestamp(),
+            duration: 600,
+            category: "Mindfulness".to_string(),
+            speaker: "Alice".to_string(),
+            ..Default::default()
+        };
+
+        let aggregator =
+            StatsAggregator::from_meditations(&[at(7, 8), at(8, 8), at(9, 8), at(9, 20)]);
+
+        // Nothing logged today yet, so the streak still runs through yesterday.
+        assert_eq!(aggregator.stats(today).days_meditated_in_row, 3);
+        assert_eq!(aggregator.duration_buckets(), &[0, 4, 0, 0, 0, 0]);
+        assert_eq!(
+            aggregator
+                .stats(NaiveDate::from_ymd_opt(2024, 2, 12).unwrap())
+                .days_meditated_in_row,
+            0
+        );
+    }
+
+    #[test]
+    fn test_calendar_decides_days_and_weeks() {
+        // Sunday 2024-02-11 at 23:30 UTC is already Monday an hour east.
+        let late_sunday = MeditationData {
+            datetime: 1_707_694_200,
+            duration: 600,
+            ..Default::default()
+        };
+        let monday = NaiveDate::from_ymd_opt(2024, 2, 12).unwrap();
+
+        let utc = StatsAggregator::from_meditations(std::slice::from_ref(&late_sunday));
+        assert_eq!(utc.stats(monday).days_meditated_in_row, 1);
+        assert_eq!(utc.stats(monday).trends.week.current_minutes, 0.0);
+
+        let calendar = StatsCalendar {
+            timezone: TimeZoneSetting::UtcOffset(60),
+            week_start: WeekStart::Monday,
+        };
+        let east =
+            StatsAggregator::from_meditations_in(std::slice::from_ref(&late_sunday), calendar);
+        assert_eq!(east.daily_minutes().keys().next(), Some(&monday));
+        assert_eq!(east.stats(monday).trends.week.current_minutes, 10.0);
+
+        let sunday_weeks = StatsAggregator::from_meditations_in(
+            &[late_sunday],
+            StatsCalendar {
+                week_start: WeekStart::Sunday,
+                ..StatsCalendar::default()
+            },
+        );
+        assert_eq!(sunday_weeks.stats(monday).trends.week.current_minutes, 10.0);
+        assert_eq!(sunday_weeks.calendar().week_start, WeekStart::Sunday);
+    }
+
+    #[test]
+    fn test_favorite_ties_are_stable() {
+        let aggregator = StatsAggregator::from_meditations(&[
+            MeditationData {
+                category: "Relaxation".to_string(),
+                speaker: "Bob".to_string(),
+                ..Default::default()
+            },
+            MeditationData {
+                category: "Mindfulness".to_string(),
+                speaker: "Alice".to_string(),
+                ..Default::default()
+            },
+        ]);
+        let stats = aggregator.stats(NaiveDate::from_ymd_opt(2024, 2, 10).unwrap());
+
+        assert_eq!(stats.favorite_category, "Mindfulness");
+        assert_eq!(stats.favorite_speaker, "Alice");
+    }
+
+    #[test]
+    fn test_suggestions_by_use() {
+        let session = |category: &str| MeditationData {
+            category: category.to_string(),
+            speaker: "Alice".to_string(),
+            ..Default::default()
+        };
+        let aggregator = StatsAggregator::from_meditations(&[
+            session("Sleep"),
+            session("Relaxation"),
+            session("Sleep"),
+            session("Mindfulness"),
+        ]);
+
+        assert_eq!(
+            aggregator.categories_by_use(),
+            vec!["Sleep", "Mindfulness", "Relaxation"]
+        );
+        assert_eq!(aggregator.speakers_by_use(), vec!["Alice"]);
+    }
+
+    #[test]
+    fn test_tag_totals() {
+        let session = |tags: &[&str]| MeditationData {
+            duration: 600,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+        let aggregator = StatsAggregator::from_meditations(&[
+            session(&["walking"]),
+            session(&["morning", "walking"]),
+            session(&[]),
+        ]);
+
+        assert_eq!(aggregator.tags_by_use(), vec!["walking", "morning"]);
+        assert_eq!(aggregator.tag_summaries()[0].total_minutes, 20.0);
+    }
+}
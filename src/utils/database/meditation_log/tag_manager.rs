@@ -0,0 +1,142 @@
+use super::meditation_data_builder::MeditationData;
+
+/// Replaces several tags with one across the whole log.
+///
+/// The merged tag takes the place of the first source tag in each session, so tag order is
+/// kept, and a session never ends up with the same tag twice.
+///
+/// # Arguments
+///
+/// * `meditations` - The whole meditation log.
+/// * `sources` - The tags to replace.
+/// * `into` - The tag to replace them with; it may be one of the sources or an existing tag.
+///
+/// # Returns
+///
+/// The number of sessions that changed.
+pub fn merge_tags(meditations: &mut [MeditationData], sources: &[&str], into: &str) -> usize {
+    let into = into.trim();
+    if into.is_empty() {
+        return 0;
+    }
+
+    let mut changed = 0;
+    for meditation in meditations.iter_mut() {
+        if !meditation
+            .tags
+            .iter()
+            .any(|tag| sources.contains(&tag.as_str()))
+        {
+            continue;
+        }
+
+        let mut tags = Vec::with_capacity(meditation.tags.len());
+        for tag in &meditation.tags {
+            let tag = if sources.contains(&tag.as_str()) {
+                into
+            } else {
+                tag.as_str()
+            };
+            if !tags.iter().any(|existing: &String| existing == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+        if tags != meditation.tags {
+            meditation.tags = tags;
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// Renames a tag across the whole log, merging it into `to` where a session already has both.
+///
+/// # Returns
+///
+/// The number of sessions that changed.
+pub fn rename_tag(meditations: &mut [MeditationData], from: &str, to: &str) -> usize {
+    merge_tags(meditations, &[from], to)
+}
+
+/// Removes a tag from every session.
+///
+/// # Returns
+///
+/// The number of sessions that changed.
+pub fn remove_tag(meditations: &mut [MeditationData], tag: &str) -> usize {
+    let mut changed = 0;
+    for meditation in meditations.iter_mut() {
+        let before = meditation.tags.len();
+        meditation.tags.retain(|existing| existing != tag);
+        if meditation.tags.len() != before {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> Vec<MeditationData> {
+        [
+            vec!["morning", "calm"],
+            vec!["Morning", "walking"],
+            vec!["calm", "am", "morning"],
+            vec!["retreat"],
+        ]
+        .into_iter()
+        .map(|tags| MeditationData {
+            tags: tags.into_iter().map(String::from).collect(),
+            ..Default::default()
+        })
+        .collect()
+    }
+
+    fn tags(meditations: &[MeditationData]) -> Vec<Vec<&str>> {
+        meditations
+            .iter()
+            .map(|meditation| meditation.tags.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_rename_tag() {
+        let mut log = log();
+        assert_eq!(rename_tag(&mut log, "calm", "peaceful"), 2);
+        assert_eq!(
+            tags(&log),
+            vec![
+                vec!["morning", "peaceful"],
+                vec!["Morning", "walking"],
+                vec!["peaceful", "am", "morning"],
+                vec!["retreat"],
+            ]
+        );
+        assert_eq!(rename_tag(&mut log, "missing", "other"), 0);
+        assert_eq!(rename_tag(&mut log, "retreat", " "), 0);
+    }
+
+    #[test]
+    fn test_merge_tags_keeps_order_without_duplicates() {
+        let mut log = log();
+        assert_eq!(merge_tags(&mut log, &["Morning", "am"], "morning"), 2);
+        assert_eq!(
+            tags(&log),
+            vec![
+                vec!["morning", "calm"],
+                vec!["morning", "walking"],
+                vec!["calm", "morning"],
+                vec!["retreat"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_tag() {
+        let mut log = log();
+        assert_eq!(remove_tag(&mut log, "calm"), 2);
+        assert_eq!(tags(&log)[2], vec!["am", "morning"]);
+    }
+}
@@ -0,0 +1,89 @@
+use super::meditation_data_builder::MeditationData;
+use super::mood_analysis::MoodTotals;
+use std::collections::HashMap;
+
+/// Running totals for the sessions carrying one tag.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TagTotals {
+    sessions: u32,
+    total_duration: i64,
+    mood: MoodTotals,
+}
+
+impl TagTotals {
+    /// Adds a session carrying the tag.
+    pub fn record(&mut self, meditation: &MeditationData) {
+        self.sessions += 1;
+        self.total_duration += meditation.duration as i64;
+        self.mood.record(meditation);
+    }
+}
+
+/// What the sessions with one tag add up to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagSummary {
+    pub tag: String,
+    pub sessions: u32,
+    pub total_minutes: f32,
+    /// The average mood change, if any session with the tag recorded mood before and after.
+    pub average_mood_delta: Option<f32>,
+}
+
+/// Summarises tag totals, most used tags first, breaking ties alphabetically.
+pub fn tag_summaries(totals: &HashMap<String, TagTotals>) -> Vec<TagSummary> {
+    let mut summaries: Vec<TagSummary> = totals
+        .iter()
+        .map(|(tag, totals)| TagSummary {
+            tag: tag.clone(),
+            sessions: totals.sessions,
+            total_minutes: totals.total_duration as f32 / 60.0,
+            average_mood_delta: totals.mood.average_delta(),
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.sessions.cmp(&a.sessions).then_with(|| a.tag.cmp(&b.tag)));
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_summaries() {
+        let mut totals: HashMap<String, TagTotals> = HashMap::new();
+        let sessions = [
+            ("morning", 600, Some((2, 4))),
+            ("walking", 1200, None),
+            ("morning", 1200, None),
+        ];
+        for (tag, duration, mood) in sessions {
+            totals
+                .entry(tag.to_string())
+                .or_default()
+                .record(&MeditationData {
+                    duration,
+                    mood_before: mood.map(|(before, _)| before),
+                    mood_after: mood.map(|(_, after)| after),
+                    ..Default::default()
+                });
+        }
+
+        assert_eq!(
+            tag_summaries(&totals),
+            vec![
+                TagSummary {
+                    tag: "morning".to_string(),
+                    sessions: 2,
+                    total_minutes: 30.0,
+                    average_mood_delta: Some(2.0),
+                },
+                TagSummary {
+                    tag: "walking".to_string(),
+                    sessions: 1,
+                    total_minutes: 20.0,
+                    average_mood_delta: None,
+                },
+            ]
+        );
+    }
+}
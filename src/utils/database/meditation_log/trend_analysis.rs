@@ -0,0 +1,425 @@
+use super::meditation_data_builder::MeditationData;
+use crate::utils::settings::app_settings::{Settings, TimeZoneSetting, WeekStart};
+use chrono::prelude::*;
+use chrono::Duration;
+use std::collections::BTreeMap;
+
+/// Compares the minutes meditated in the current period against the previous one.
+///
+/// Both periods cover the same number of days, so a half-finished week is compared
+/// with the first half of last week rather than with all of it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeriodComparison {
+    pub current_minutes: f32,
+    pub previous_minutes: f32,
+}
+
+impl PeriodComparison {
+    /// Calculates the percentage change from the previous period to the current one.
+    ///
+    /// # Returns
+    ///
+    /// `Some(change)` in percent, or `None` if nothing was meditated in the previous period.
+    pub fn percent_change(&self) -> Option<f32> {
+        if self.previous_minutes <= 0.0 {
+            return None;
+        }
+        Some((self.current_minutes - self.previous_minutes) / self.previous_minutes * 100.0)
+    }
+
+    /// Describes the change in words, e.g. "up 23% from last week".
+    ///
+    /// # Arguments
+    ///
+    /// * `period` - The name of the previous period, e.g. "last week".
+    pub fn summary(&self, period: &str) -> String {
+        match self.percent_change() {
+            None if self.current_minutes > 0.0 => format!("no sessions {} to compare", period),
+            None => String::new(),
+            Some(change) if change.round() == 0.0 => format!("same as {}", period),
+            Some(change) if change > 0.0 => format!("up {:.0}% from {}", change, period),
+            Some(change) => format!("down {:.0}% from {}", change.abs(), period),
+        }
+    }
+}
+
+/// Period-over-period comparisons and moving averages of daily practice.
+///
+/// All averages are expressed in minutes per day and include days without a session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trends {
+    pub week: PeriodComparison,
+    pub month: PeriodComparison,
+    pub rolling_7_day_average: f32,
+    pub rolling_30_day_average: f32,
+    /// Slope of the least-squares line through the last 30 days, in minutes per day per day.
+    pub daily_minutes_trend: f32,
+}
+
+/// The time zone that decides which day a session counts for, and the day weeks start on.
+///
+/// Defaults to UTC and Monday; use `StatsCalendar::of` to count as the profile's settings say.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatsCalendar {
+    pub timezone: TimeZoneSetting,
+    pub week_start: WeekStart,
+}
+
+impl Default for StatsCalendar {
+    fn default() -> Self {
+        StatsCalendar {
+            timezone: TimeZoneSetting::UtcOffset(0),
+            week_start: WeekStart::Monday,
+        }
+    }
+}
+
+impl StatsCalendar {
+    /// The calendar of the time zone and week start in `settings`.
+    pub fn of(settings: &Settings) -> Self {
+        StatsCalendar {
+            timezone: settings.timezone,
+            week_start: settings.week_start,
+        }
+    }
+
+    /// Today's date in the calendar's time zone.
+    pub fn today(self) -> NaiveDate {
+        self.timezone.today()
+    }
+}
+
+/// Sums the minutes meditated on each calendar day.
+///
+/// # Arguments
+///
+/// * `meditations` - The meditation sessions to group by day.
+/// * `timezone` - The time zone whose days the sessions are counted on.
+///
+/// # Returns
+///
+/// A map from date to the total minutes meditated on that date.
+pub fn daily_minutes(
+    meditations: &[MeditationData],
+    timezone: TimeZoneSetting,
+) -> BTreeMap<NaiveDate, f32> {
+    let mut days = BTreeMap::new();
+    for meditation in meditations {
+        add_daily_minutes(&mut days, meditation, timezone);
+    }
+    days
+}
+
+/// Adds the minutes of a single session to its day in `timezone` in `daily`.
+pub fn add_daily_minutes(
+    daily: &mut BTreeMap<NaiveDate, f32>,
+    meditation: &MeditationData,
+    timezone: TimeZoneSetting,
+) {
+    if let Some(date) = timezone.local_date(meditation.datetime) {
+        *daily.entry(date).or_insert(0.0) += meditation.duration as f32 / 60.0;
+    }
+}
+
+/// Sums the minutes meditated between two dates, both inclusive.
+fn minutes_between(daily: &BTreeMap<NaiveDate, f32>, start: NaiveDate, end: NaiveDate) -> f32 {
+    if start > end {
+        return 0.0;
+    }
+    daily.range(start..=end).map(|(_, minutes)| minutes).sum()
+}
+
+/// Calculates the average minutes per day over the `days` days ending with `today`.
+pub fn rolling_average(daily: &BTreeMap<NaiveDate, f32>, today: NaiveDate, days: i64) -> f32 {
+    if days <= 0 {
+        return 0.0;
+    }
+    let start = today - Duration::days(days - 1);
+    minutes_between(daily, start, today) / days as f32
+}
+
+/// Fits a least-squares line through evenly spaced values and returns its slope.
+///
+/// # Returns
+///
+/// The change per step, or `0.0` if fewer than two values are given.
+pub fn linear_trend(values: &[f32]) -> f32 {
+    let n = values.len() as f32;
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f32>() / n;
+
+    let (covariance, variance) =
+        values
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(cov, var), (x, y)| {
+                let dx = x as f32 - mean_x;
+                (cov + dx * (y - mean_y), var + dx * dx)
+            });
+
+    covariance / variance
+}
+
+/// Compares the week so far, starting on `week_start`, with the same days of last week.
+fn compare_weeks(
+    daily: &BTreeMap<NaiveDate, f32>,
+    today: NaiveDate,
+    week_start: WeekStart,
+) -> PeriodComparison {
+    let elapsed = week_start.days_into_week(today);
+    let week_start = today - Duration::days(elapsed);
+    let last_week_start = week_start - Duration::days(7);
+
+    PeriodComparison {
+        current_minutes: minutes_between(daily, week_start, today),
+        previous_minutes: minutes_between(
+            daily,
+            last_week_start,
+            last_week_start + Duration::days(elapsed),
+        ),
+    }
+}
+
+/// Compares the month so far with the same days of last month.
+///
+/// If last month is shorter than the elapsed part of this month, the whole of last month is used.
+fn compare_months(daily: &BTreeMap<NaiveDate, f32>, today: NaiveDate) -> PeriodComparison {
+    let month_start = today.with_day(1).unwrap_or(today);
+    let last_month_end = month_start - Duration::days(1);
+    let last_month_start = last_month_end.with_day(1).unwrap_or(last_month_end);
+    let elapsed = (today - month_start).num_days();
+
+    PeriodComparison {
+        current_minutes: minutes_between(daily, month_start, today),
+        previous_minutes: minutes_between(
+            daily,
+            last_month_start,
+            (last_month_start + Duration::days(elapsed)).min(last_month_end),
+        ),
+    }
+}
+
+/// Lists the minutes meditated on each of the `days` days ending with `today`, oldest first.
+pub fn minutes_per_day(
+    daily: &BTreeMap<NaiveDate, f32>,
+    today: NaiveDate,
+    days: i64,
+) -> Vec<(NaiveDate, f32)> {
+    (0..days)
+        .rev()
+        .map(|offset| {
+            let date = today - Duration::days(offset);
+            (date, daily.get(&date).copied().unwrap_or(0.0))
+        })
+        .collect()
+}
+
+/// Lists the minutes meditated in each of the `weeks` weeks, starting on `week_start`, that
+/// end with the current week, oldest first, keyed by the first day of each week.
+pub fn minutes_per_week(
+    daily: &BTreeMap<NaiveDate, f32>,
+    today: NaiveDate,
+    weeks: i64,
+    week_start: WeekStart,
+) -> Vec<(NaiveDate, f32)> {
+    let week_start = week_start.week_of(today);
+    (0..weeks)
+        .rev()
+        .map(|offset| {
+            let start = week_start - Duration::weeks(offset);
+            (
+                start,
+                minutes_between(daily, start, start + Duration::days(6)),
+            )
+        })
+        .collect()
+}
+
+/// Calculates all trend figures relative to `today`.
+///
+/// # Arguments
+///
+/// * `daily` - Minutes meditated per day, as produced by `daily_minutes`.
+/// * `today` - The date the periods are measured against.
+/// * `week_start` - The first day of the week compared with last week.
+pub fn calculate_trends(
+    daily: &BTreeMap<NaiveDate, f32>,
+    today: NaiveDate,
+    week_start: WeekStart,
+) -> Trends {
+    let last_30_days: Vec<f32> = minutes_per_day(daily, today, 30)
+        .into_iter()
+        .map(|(_, minutes)| minutes)
+        .collect();
+
+    Trends {
+        week: compare_weeks(daily, today, week_start),
+        month: compare_months(daily, today),
+        rolling_7_day_average: rolling_average(daily, today, 7),
+        rolling_30_day_average: rolling_average(daily, today, 30),
+        daily_minutes_trend: linear_trend(&last_30_days),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UTC: TimeZoneSetting = TimeZoneSetting::UtcOffset(0);
+
+    fn session(date: NaiveDate, minutes: i32) -> MeditationData {
+        MeditationData {
+            datetime: date.and_hms_opt(8, 0, 0).unwrap().timestamp(),
+            duration: minutes * 60,
+            category: "Mindfulness".to_string(),
+            speaker: "Alice".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_week_over_week_compares_same_weekdays() {
+        // 2024-02-14 is a Wednesday.
+        let today = date(2024, 2, 14);
+        let meditations = vec![
+            session(date(2024, 2, 5), 10),  // last Monday
+            session(date(2024, 2, 7), 10),  // last Wednesday
+            session(date(2024, 2, 9), 100), // last Friday, outside the compared span
+            session(date(2024, 2, 12), 20), // this Monday
+            session(date(2024, 2, 14), 5),  // today
+        ];
+        let trends = calculate_trends(&daily_minutes(&meditations, UTC), today, WeekStart::Monday);
+
+        assert_eq!(trends.week.current_minutes, 25.0);
+        assert_eq!(trends.week.previous_minutes, 20.0);
+        assert_eq!(trends.week.percent_change(), Some(25.0));
+        assert_eq!(trends.week.summary("last week"), "up 25% from last week");
+    }
+
+    #[test]
+    fn test_month_over_month_clamps_to_shorter_month() {
+        let today = date(2024, 3, 31);
+        let meditations = vec![
+            session(date(2024, 2, 29), 30),
+            session(date(2024, 3, 1), 15),
+        ];
+        let trends = calculate_trends(&daily_minutes(&meditations, UTC), today, WeekStart::Monday);
+
+        assert_eq!(trends.month.current_minutes, 15.0);
+        assert_eq!(trends.month.previous_minutes, 30.0);
+        assert_eq!(
+            trends.month.summary("last month"),
+            "down 50% from last month"
+        );
+    }
+
+    #[test]
+    fn test_rolling_averages_include_rest_days() {
+        let today = date(2024, 2, 14);
+        let meditations = vec![session(today, 14), session(date(2024, 2, 1), 16)];
+        let daily = daily_minutes(&meditations, UTC);
+
+        assert_eq!(rolling_average(&daily, today, 7), 2.0);
+        assert_eq!(rolling_average(&daily, today, 30), 1.0);
+    }
+
+    #[test]
+    fn test_minutes_per_day_and_week() {
+        let today = date(2024, 2, 14);
+        let meditations = vec![
+            session(date(2024, 2, 4), 30), // Sunday of the week before last
+            session(date(2024, 2, 12), 20),
+            session(date(2024, 2, 14), 5),
+        ];
+        let daily = daily_minutes(&meditations, UTC);
+
+        let days = minutes_per_day(&daily, today, 3);
+        assert_eq!(
+            days,
+            vec![
+                (date(2024, 2, 12), 20.0),
+                (date(2024, 2, 13), 0.0),
+                (date(2024, 2, 14), 5.0)
+            ]
+        );
+
+        let weeks = minutes_per_week(&daily, today, 3, WeekStart::Monday);
+        assert_eq!(
+            weeks,
+            vec![
+                (date(2024, 1, 29), 30.0),
+                (date(2024, 2, 5), 0.0),
+                (date(2024, 2, 12), 25.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weeks_start_on_the_chosen_day() {
+        // 2024-02-14 is a Wednesday.
+        let today = date(2024, 2, 14);
+        let meditations = vec![
+            session(date(2024, 2, 3), 40),  // Saturday two weeks before
+            session(date(2024, 2, 10), 10), // last Saturday
+            session(date(2024, 2, 11), 20), // last Sunday
+        ];
+        let daily = daily_minutes(&meditations, UTC);
+
+        let sunday = calculate_trends(&daily, today, WeekStart::Sunday).week;
+        assert_eq!(sunday.current_minutes, 20.0);
+        assert_eq!(sunday.previous_minutes, 0.0);
+        let saturday = calculate_trends(&daily, today, WeekStart::Saturday).week;
+        assert_eq!(saturday.current_minutes, 30.0);
+        assert_eq!(saturday.previous_minutes, 40.0);
+
+        assert_eq!(
+            minutes_per_week(&daily, today, 2, WeekStart::Sunday),
+            vec![(date(2024, 2, 4), 10.0), (date(2024, 2, 11), 20.0)]
+        );
+    }
+
+    #[test]
+    fn test_sessions_count_on_their_day_in_the_time_zone() {
+        let late = MeditationData {
+            datetime: date(2024, 2, 10)
+                .and_hms_opt(23, 30, 0)
+                .unwrap()
+                .timestamp(),
+            duration: 600,
+            ..Default::default()
+        };
+
+        let utc = daily_minutes(std::slice::from_ref(&late), UTC);
+        assert_eq!(utc.keys().collect::<Vec<_>>(), vec![&date(2024, 2, 10)]);
+        let east = daily_minutes(&[late], TimeZoneSetting::UtcOffset(60));
+        assert_eq!(east.keys().collect::<Vec<_>>(), vec![&date(2024, 2, 11)]);
+    }
+
+    #[test]
+    fn test_linear_trend() {
+        assert_eq!(linear_trend(&[1.0, 2.0, 3.0, 4.0]), 1.0);
+        assert_eq!(linear_trend(&[5.0, 5.0, 5.0]), 0.0);
+        assert_eq!(linear_trend(&[7.0]), 0.0);
+    }
+
+    #[test]
+    fn test_summary_without_previous_period() {
+        let comparison = PeriodComparison {
+            current_minutes: 10.0,
+            previous_minutes: 0.0,
+        };
+        assert_eq!(comparison.percent_change(), None);
+        assert_eq!(
+            comparison.summary("last week"),
+            "no sessions last week to compare"
+        );
+        assert_eq!(PeriodComparison::default().summary("last week"), "");
+    }
+}
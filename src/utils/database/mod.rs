@@ -1,3 +1,8 @@
+pub mod compact_log;
 pub mod database_manager;
+pub mod encryption;
 pub mod json_array_handler;
+pub mod log_envelope;
 pub mod meditation_log;
+pub mod protected_storage;
+pub mod storage_quota;
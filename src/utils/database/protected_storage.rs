@@ -0,0 +1,340 @@
+use std::cell::RefCell;
+
+use serde::{de::DeserializeOwned, Serialize};
+use web_sys::wasm_bindgen::JsValue;
+
+use super::encryption::{
+    is_encrypted, EncryptionError, EncryptionKey, VaultHeader, NONCE_LEN, PBKDF2_ITERATIONS,
+    SALT_LEN,
+};
+use crate::{
+    services::{
+        local_storage::{LocalStorageService, StorageError},
+        secure_random::SecureRandom,
+    },
+    utils::constants::{
+        ACTIVE_TIMER_KEY, CATALOG_KEY, LOG_CRDT_KEY, MEDITATION_LOG_KEY, SETTINGS_KEY,
+        SYNC_STATE_KEY, VAULT_KEY,
+    },
+};
+
+/// The stored values that are encrypted when a passphrase is set: every value a profile
+/// keeps, including its settings and reminder times.
+///
+/// Only the list of profiles, with their names and colours, and the vault header itself
+/// stay in plain text.
+const PROTECTED_KEYS: [&str; 6] = [
+    MEDITATION_LOG_KEY,
+    LOG_CRDT_KEY,
+    SYNC_STATE_KEY,
+    CATALOG_KEY,
+    ACTIVE_TIMER_KEY,
+    SETTINGS_KEY,
+];
+
+thread_local! {
+    /// The key derived from the passphrase, held in memory only until the page is closed.
+    static UNLOCKED_KEY: RefCell<Option<EncryptionKey>> = const { RefCell::new(None) };
+    /// The stored vault header, `Some(None)` if no passphrase is set; read on first use and
+    /// kept in step with every change to it.
+    static HEADER: RefCell<Option<Option<VaultHeader>>> = const { RefCell::new(None) };
+}
+
+/// Whether stored data is encrypted, and whether it can be read right now.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockState {
+    /// No passphrase is set; data is stored as plain JSON.
+    Unencrypted,
+    /// Data is encrypted and the passphrase hasn't been entered yet.
+    Locked,
+    /// Data is encrypted and can be read and written.
+    Unlocked,
+}
+
+fn storage_error(error: StorageError) -> EncryptionError {
+    EncryptionError::Storage(error.to_string())
+}
+
+fn random_error(error: JsValue) -> EncryptionError {
+    EncryptionError::Storage(format!("no secure random numbers: {:?}", error))
+}
+
+fn to_storage_error(error: EncryptionError) -> StorageError {
+    match error {
+        EncryptionError::Unreadable(message) => StorageError::Unreadable(message),
+        other => StorageError::Failed(other.to_string()),
+    }
+}
+
+fn read_header() -> Result<Option<VaultHeader>, EncryptionError> {
+    if let Some(header) = HEADER.with(|cached| cached.borrow().clone()) {
+        return Ok(header);
+    }
+    let header = match LocalStorageService::read(VAULT_KEY).map_err(storage_error)? {
+        Some(value) => serde_json::from_str(&value)
+            .map(Some)
+            .map_err(|error| EncryptionError::Unreadable(error.to_string()))?,
+        None => None,
+    };
+    cache_header(header.clone());
+    Ok(header)
+}
+
+fn cache_header(header: Option<VaultHeader>) {
+    HEADER.with(|cached| *cached.borrow_mut() = Some(header));
+}
+
+/// The local storage keys of every protected value, in every profile.
+fn protected_keys() -> Result<Vec<String>, StorageError> {
+    Ok(LocalStorageService::keys()?
+        .into_iter()
+        .filter(|key| {
+            PROTECTED_KEYS.iter().any(|name| {
+                key == name
+                    || key
+                        .strip_suffix(name)
+                        .is_some_and(|prefix| prefix.ends_with(':'))
+            })
+        })
+        .collect())
+}
+
+fn unlocked_key() -> Option<EncryptionKey> {
+    UNLOCKED_KEY.with(|key| key.borrow().clone())
+}
+
+/// Reads every protected value as plain text, decrypting with `key` where needed.
+fn read_plaintexts(key: Option<&EncryptionKey>) -> Result<Vec<(String, String)>, EncryptionError> {
+    let mut values = Vec::new();
+    for name in protected_keys().map_err(storage_error)? {
+        let Some(stored) = LocalStorageService::read(&name).map_err(storage_error)? else {
+            continue;
+        };
+        let plaintext = match key {
+            Some(key) if is_encrypted(&stored) => key.decrypt(&stored)?,
+            None if is_encrypted(&stored) => return Err(EncryptionError::Locked),
+            _ => stored,
+        };
+        values.push((name, plaintext));
+    }
+    Ok(values)
+}
+
+/// Encrypts the protected values still stored in plain text, e.g. those written before they
+/// were protected.
+fn encrypt_plaintexts(key: &EncryptionKey) -> Result<(), EncryptionError> {
+    for name in protected_keys().map_err(storage_error)? {
+        let Some(stored) = LocalStorageService::read(&name).map_err(storage_error)? else {
+            continue;
+        };
+        if !is_encrypted(&stored) {
+            let nonce = SecureRandom::bytes::<NONCE_LEN>().map_err(random_error)?;
+            LocalStorageService::create(&name, &key.encrypt(&stored, nonce))
+                .map_err(storage_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes `values` and then the header, or restores everything as it was if any write fails,
+/// so data is never left encrypted with a key nobody knows.
+fn rewrite(
+    values: &[(String, String)],
+    header: Option<&VaultHeader>,
+) -> Result<(), EncryptionError> {
+    let mut previous = Vec::new();
+    let names = protected_keys().map_err(storage_error)?;
+    for name in names.into_iter().chain([VAULT_KEY.to_string()]) {
+        let value = LocalStorageService::read(&name).map_err(storage_error)?;
+        previous.push((name, value));
+    }
+
+    let serialized =
+        header.map(|header| serde_json::to_string(header).expect("a header always serializes"));
+    let result = values
+        .iter()
+        .try_for_each(|(name, value)| LocalStorageService::create(name, value))
+        .and_then(|_| match &serialized {
+            Some(serialized) => LocalStorageService::create(VAULT_KEY, serialized),
+            None => LocalStorageService::delete(VAULT_KEY),
+        });
+
+    result
+        .map(|_| cache_header(header.cloned()))
+        .map_err(|error| {
+            for (name, value) in &previous {
+                let _ = match value {
+                    Some(value) => LocalStorageService::create(name, value),
+                    None => LocalStorageService::delete(name),
+                };
+            }
+            storage_error(error)
+        })
+}
+
+/// Reads and writes the values that hold personal data, encrypting them when a passphrase
+/// is set.
+///
+/// Has the same interface as `LocalStorageService`, so storage code can use either.
+pub struct ProtectedStorage;
+
+impl ProtectedStorage {
+    /// Stores a value, encrypted if a passphrase is set.
+    ///
+    /// # Returns
+    /// A `Result<(), StorageError>`; `Err(StorageError)` if storage fails or the data is
+    /// locked, in which case nothing is written.
+    pub fn create(key: &str, value: &str) -> Result<(), StorageError> {
+        match (read_header().map_err(to_storage_error)?, unlocked_key()) {
+            (None, _) => LocalStorageService::create(key, value),
+            (Some(_), Some(encryption_key)) => {
+                let nonce = SecureRandom::bytes::<NONCE_LEN>()
+                    .map_err(|error| to_storage_error(random_error(error)))?;
+                LocalStorageService::create(key, &encryption_key.encrypt(value, nonce))
+            }
+            (Some(_), None) => Err(to_storage_error(EncryptionError::Locked)),
+        }
+    }
+
+    /// Reads a value, decrypting it if it is encrypted.
+    ///
+    /// Values are only checked for encryption while a passphrase is set.
+    ///
+    /// # Returns
+    /// A `Result<Option<String>, StorageError>`:
+    /// - `Ok(Some(String))` with the plain value.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StorageError)` if storage fails, the data is locked, or it can't be decrypted.
+    pub fn read(key: &str) -> Result<Option<String>, StorageError> {
+        let Some(stored) = LocalStorageService::read(key)? else {
+            return Ok(None);
+        };
+        if read_header().map_err(to_storage_error)?.is_none() || !is_encrypted(&stored) {
+            return Ok(Some(stored));
+        }
+        let encryption_key =
+            unlocked_key().ok_or_else(|| to_storage_error(EncryptionError::Locked))?;
+        encryption_key
+            .decrypt(&stored)
+            .map(Some)
+            .map_err(to_storage_error)
+    }
+
+    /// Retrieves a value stored as JSON, decrypting it if it is encrypted.
+    ///
+    /// # Returns
+    /// A `Result<Option<T>, StorageError>`:
+    /// - `Ok(Some(T))` if the key exists and holds a `T`.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(StorageError::Unreadable)` if the value isn't a `T`, or another error if reading fails.
+    pub fn get_json<T: DeserializeOwned>(key: &str) -> Result<Option<T>, StorageError> {
+        Self::read(key)?
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(|error| StorageError::Unreadable(error.to_string()))
+    }
+
+    /// Stores a value as JSON, encrypted if a passphrase is set.
+    ///
+    /// # Returns
+    /// A `Result<(), StorageError>`; `Err(StorageError)` if serialization or storing fails.
+    pub fn set_json<T: Serialize + ?Sized>(key: &str, value: &T) -> Result<(), StorageError> {
+        let value = serde_json::to_string(value)
+            .map_err(|error| StorageError::Failed(format!("serialization failed: {}", error)))?;
+        Self::create(key, &value)
+    }
+}
+
+/// Whether stored data is encrypted, and whether it has been unlocked.
+pub fn lock_state() -> LockState {
+    match (read_header(), unlocked_key()) {
+        (Ok(None), _) => LockState::Unencrypted,
+        (_, Some(_)) => LockState::Unlocked,
+        _ => LockState::Locked,
+    }
+}
+
+/// Checks `passphrase` and keeps its key in memory, so encrypted data can be read.
+///
+/// # Returns
+/// `Err(EncryptionError::WrongPassphrase)` if the passphrase doesn't match; nothing is
+/// unlocked then.
+pub fn unlock(passphrase: &str) -> Result<(), EncryptionError> {
+    let header = read_header()?.ok_or(EncryptionError::Unreadable(
+        "no passphrase is set".to_string(),
+    ))?;
+    let key = header.unlock(passphrase)?;
+    if let Err(error) = encrypt_plaintexts(&key) {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Some data is still stored in plain text: {}",
+            error
+        )));
+    }
+    UNLOCKED_KEY.with(|unlocked| *unlocked.borrow_mut() = Some(key));
+    Ok(())
+}
+
+/// Forgets the key, so the passphrase must be entered again.
+pub fn lock() {
+    UNLOCKED_KEY.with(|unlocked| *unlocked.borrow_mut() = None);
+}
+
+/// Turns encryption on, or changes the passphrase, re-encrypting all protected data.
+///
+/// # Arguments
+/// * `current` - The passphrase set now, if any; it must match.
+/// * `new` - The passphrase to encrypt with from now on.
+///
+/// # Returns
+/// An `EncryptionError` if `current` is wrong, `new` is too short, or the data can't be
+/// rewritten; stored data is unchanged then.
+pub fn set_passphrase(current: Option<&str>, new: &str) -> Result<(), EncryptionError> {
+    let current_key = match (read_header()?, current) {
+        (Some(header), Some(current)) => Some(header.unlock(current)?),
+        (Some(_), None) => return Err(EncryptionError::WrongPassphrase),
+        (None, _) => None,
+    };
+    let values = read_plaintexts(current_key.as_ref())?;
+
+    let salt = SecureRandom::bytes::<SALT_LEN>().map_err(random_error)?;
+    let nonce = SecureRandom::bytes::<NONCE_LEN>().map_err(random_error)?;
+    let (header, key) = VaultHeader::create(new, salt, PBKDF2_ITERATIONS, nonce)?;
+    let mut encrypted = Vec::new();
+    for (name, value) in values {
+        let nonce = SecureRandom::bytes::<NONCE_LEN>().map_err(random_error)?;
+        encrypted.push((name, key.encrypt(&value, nonce)));
+    }
+
+    rewrite(&encrypted, Some(&header))?;
+    UNLOCKED_KEY.with(|unlocked| *unlocked.borrow_mut() = Some(key));
+    Ok(())
+}
+
+/// Turns encryption off, storing all protected data as plain JSON again.
+///
+/// # Returns
+/// `Err(EncryptionError::WrongPassphrase)` if `passphrase` doesn't match; stored data is
+/// unchanged then.
+pub fn remove_passphrase(passphrase: &str) -> Result<(), EncryptionError> {
+    let Some(header) = read_header()? else {
+        return Ok(());
+    };
+    let key = header.unlock(passphrase)?;
+    rewrite(&read_plaintexts(Some(&key))?, None)?;
+    lock();
+    Ok(())
+}
+
+/// Deletes all protected data and the passphrase, for when the passphrase is forgotten.
+///
+/// Encrypted data can't be recovered without the passphrase, so this is the only way back
+/// to a working app.
+pub fn erase_protected_data() -> Result<(), StorageError> {
+    for name in protected_keys()? {
+        LocalStorageService::delete(&name)?;
+    }
+    LocalStorageService::delete(VAULT_KEY)?;
+    cache_header(None);
+    lock();
+    Ok(())
+}
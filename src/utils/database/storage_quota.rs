@@ -0,0 +1,112 @@
+use super::meditation_log::meditation_data_builder::MeditationData;
+
+/// What browsers let each site keep in local storage: 5 MiB in Chrome, Firefox and Safari.
+pub const LOCAL_STORAGE_QUOTA_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Share of the quota from which the user is warned.
+const WARNING_FRACTION: f64 = 0.8;
+/// Share of the quota from which the next sessions may not fit.
+const CRITICAL_FRACTION: f64 = 0.95;
+
+/// How urgently storage needs freeing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UsageLevel {
+    Normal,
+    /// Most of the quota is used; a good time to compress or archive.
+    Warning,
+    /// The quota is nearly used up; new sessions may not be saved.
+    Critical,
+}
+
+/// How much local storage the app uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StorageUsage {
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+impl StorageUsage {
+    /// Usage of the local storage quota.
+    pub fn of_local_storage(used_bytes: u64) -> Self {
+        StorageUsage {
+            used_bytes,
+            quota_bytes: LOCAL_STORAGE_QUOTA_BYTES,
+        }
+    }
+
+    /// The share of the quota in use, from 0.0; above 1.0 if the quota is larger than assumed.
+    pub fn fraction(&self) -> f64 {
+        if self.quota_bytes == 0 {
+            return 1.0;
+        }
+        self.used_bytes as f64 / self.quota_bytes as f64
+    }
+
+    pub fn level(&self) -> UsageLevel {
+        match self.fraction() {
+            fraction if fraction >= CRITICAL_FRACTION => UsageLevel::Critical,
+            fraction if fraction >= WARNING_FRACTION => UsageLevel::Warning,
+            _ => UsageLevel::Normal,
+        }
+    }
+}
+
+/// Splits the log into the sessions to keep and those from before `cutoff` to archive.
+///
+/// # Arguments
+/// * `meditations` - The log.
+/// * `cutoff` - A UNIX timestamp; sessions that started earlier are archived.
+///
+/// # Returns
+/// The sessions to keep and the sessions to archive, each in log order.
+pub fn split_archive(
+    meditations: &[MeditationData],
+    cutoff: i64,
+) -> (Vec<MeditationData>, Vec<MeditationData>) {
+    meditations
+        .iter()
+        .cloned()
+        .partition(|meditation| meditation.datetime >= cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        let usage = |used_bytes| StorageUsage {
+            used_bytes,
+            quota_bytes: 1_000,
+        };
+
+        assert_eq!(usage(0).level(), UsageLevel::Normal);
+        assert_eq!(usage(799).level(), UsageLevel::Normal);
+        assert_eq!(usage(800).level(), UsageLevel::Warning);
+        assert_eq!(usage(950).level(), UsageLevel::Critical);
+        assert_eq!(usage(2_000).level(), UsageLevel::Critical);
+        assert_eq!(StorageUsage::of_local_storage(0).quota_bytes, 5_242_880);
+        assert_eq!(
+            StorageUsage {
+                used_bytes: 0,
+                quota_bytes: 0
+            }
+            .level(),
+            UsageLevel::Critical
+        );
+    }
+
+    #[test]
+    fn test_split_archive() {
+        let session = |datetime| MeditationData {
+            datetime,
+            duration: 600,
+            ..Default::default()
+        };
+        let log = vec![session(300), session(100), session(200)];
+
+        let (keep, archive) = split_archive(&log, 200);
+        assert_eq!(keep, vec![session(300), session(200)]);
+        assert_eq!(archive, vec![session(100)]);
+    }
+}
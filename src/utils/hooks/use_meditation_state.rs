@@ -1,21 +1,115 @@
+use std::rc::Rc;
+
 use yew::prelude::*;
 
+use super::use_settings::use_settings;
 use crate::utils::database::meditation_log::{
-    calculate_meditation_stats::{Stats, StatsBuilder},
-    read_write_meditation_data::read_meditation_data,
+    calculate_meditation_stats::Stats, meditation_data_builder::MeditationData,
+    read_write_catalog::reconcile_meditation_log, read_write_log_crdt::track_meditation_log,
+    read_write_meditation_data::read_meditation_data, stats_aggregator::StatsAggregator,
+    trend_analysis::StatsCalendar,
 };
 
-#[hook]
-pub fn use_meditation_data() -> UseStateHandle<Stats> {
-    let data_state: UseStateHandle<Stats> = use_state(|| Stats {
-        ..StatsBuilder::new(vec![]).build()
-    });
-    let data_state_cl = data_state.clone();
-    use_effect_with((), move |_| {
-        let data = read_meditation_data();
-        let stats = StatsBuilder::new(data).build();
-        data_state_cl.set(stats);
+/// Actions that update the shared `StatsAggregator`.
+pub enum StatsAction {
+    /// Folds a newly logged session into the running totals.
+    Record(MeditationData),
+    /// Recomputes the totals from the whole log, e.g. after a session was edited.
+    Rebuild(Vec<MeditationData>),
+    /// Recomputes the totals from the whole log in another time zone or week start.
+    Recount(StatsCalendar, Vec<MeditationData>),
+}
+
+impl Reducible for StatsAggregator {
+    type Action = StatsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            StatsAction::Record(meditation) => {
+                let mut aggregator = Rc::unwrap_or_clone(self);
+                aggregator.record(&meditation);
+                Rc::new(aggregator)
+            }
+            StatsAction::Rebuild(meditations) => Rc::new(StatsAggregator::from_meditations_in(
+                &meditations,
+                self.calendar(),
+            )),
+            StatsAction::Recount(calendar, meditations) => {
+                Rc::new(StatsAggregator::from_meditations_in(&meditations, calendar))
+            }
+        }
+    }
+}
+
+pub type MeditationStatsContext = UseReducerHandle<StatsAggregator>;
+
+#[derive(PartialEq, Properties)]
+pub struct MeditationStatsProviderProps {
+    pub children: Html,
+}
+
+/// Reads the meditation log once and shares the running totals with all descendants,
+/// so pages can be mounted repeatedly without re-reading the whole log.
+///
+/// Days and weeks are counted in the time zone and week start of the settings; the log is
+/// read again when those change. Must be inside a `SettingsProvider`.
+#[function_component]
+pub fn MeditationStatsProvider(props: &MeditationStatsProviderProps) -> Html {
+    let MeditationStatsProviderProps { children } = props;
+
+    let calendar = StatsCalendar::of(&use_settings().settings);
+    let aggregator = use_reducer(|| {
+        // Older logs hold free-text categories and speakers; fold their spellings together first.
+        reconcile_meditation_log();
+        track_meditation_log();
+        StatsAggregator::from_meditations_in(&read_meditation_data(), calendar)
     });
+    {
+        let aggregator = aggregator.clone();
+        use_effect_with(calendar, move |calendar| {
+            if aggregator.calendar() != *calendar {
+                aggregator.dispatch(StatsAction::Recount(*calendar, read_meditation_data()));
+            }
+        });
+    }
+
+    html! {
+        <ContextProvider<MeditationStatsContext> context={aggregator}>
+            {children.clone()}
+        </ContextProvider<MeditationStatsContext>>
+    }
+}
+
+/// Returns the shared running totals, for views that need more than the summary `Stats`.
+#[hook]
+pub fn use_stats_aggregator() -> MeditationStatsContext {
+    use_context::<MeditationStatsContext>()
+        .expect("use_stats_aggregator must be used inside a MeditationStatsProvider")
+}
+
+#[hook]
+pub fn use_meditation_data() -> Stats {
+    let aggregator = use_stats_aggregator();
+
+    aggregator.stats(aggregator.today())
+}
+
+/// Returns a callback that folds a newly logged session into the shared stats.
+#[hook]
+pub fn use_record_meditation() -> Callback<MeditationData> {
+    let aggregator = use_stats_aggregator();
+
+    Callback::from(move |meditation: MeditationData| {
+        aggregator.dispatch(StatsAction::Record(meditation))
+    })
+}
+
+/// Returns a callback that re-reads the log and recomputes the shared stats.
+///
+/// Use it after changing sessions that were already recorded, which can't be folded in.
+#[hook]
+pub fn use_reload_meditations() -> Callback<()> {
+    let aggregator = use_stats_aggregator();
 
-    data_state
+    Callback::from(move |_: ()| aggregator.dispatch(StatsAction::Rebuild(read_meditation_data())))
 }
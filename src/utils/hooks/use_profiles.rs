@@ -0,0 +1,54 @@
+use yew::prelude::*;
+
+use super::{use_meditation_state::MeditationStatsProvider, use_settings::SettingsProvider};
+use crate::utils::profile::{profile_list::ProfileList, read_write_profiles::read_profiles};
+
+/// The profiles on this device, shared with all descendants.
+#[derive(Clone, PartialEq)]
+pub struct ProfilesContext {
+    pub profiles: ProfileList,
+    /// Re-reads the profiles; call after changing them.
+    pub refresh: Callback<()>,
+}
+
+#[derive(PartialEq, Properties)]
+pub struct ProfilesProviderProps {
+    pub children: Html,
+}
+
+/// Shares the profiles and gives the active profile its own meditation stats and settings.
+///
+/// Everything inside is mounted afresh when another profile is switched to, so no state of
+/// the previous profile is shown or written to the new one.
+#[function_component]
+pub fn ProfilesProvider(props: &ProfilesProviderProps) -> Html {
+    let ProfilesProviderProps { children } = props;
+
+    let profiles = use_state(read_profiles);
+    let refresh = {
+        let profiles = profiles.clone();
+        Callback::from(move |_: ()| profiles.set(read_profiles()))
+    };
+    let context = ProfilesContext {
+        profiles: (*profiles).clone(),
+        refresh,
+    };
+    let active = profiles.active().id.clone();
+
+    html! {
+        <ContextProvider<ProfilesContext> {context}>
+            <SettingsProvider key={active}>
+                // Counts days and weeks as the settings say, so it sits inside them.
+                <MeditationStatsProvider>
+                    {children.clone()}
+                </MeditationStatsProvider>
+            </SettingsProvider>
+        </ContextProvider<ProfilesContext>>
+    }
+}
+
+/// Returns the shared profiles.
+#[hook]
+pub fn use_profiles() -> ProfilesContext {
+    use_context::<ProfilesContext>().expect("use_profiles must be used inside a ProfilesProvider")
+}
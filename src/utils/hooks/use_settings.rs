@@ -0,0 +1,44 @@
+use yew::prelude::*;
+
+use crate::utils::settings::{app_settings::Settings, read_write_settings::read_settings};
+
+/// The active profile's settings, shared with all descendants.
+#[derive(Clone, PartialEq)]
+pub struct SettingsContext {
+    pub settings: Settings,
+    /// Shows settings everywhere; call with the settings returned by `save_settings`.
+    pub changed: Callback<Settings>,
+}
+
+#[derive(PartialEq, Properties)]
+pub struct SettingsProviderProps {
+    pub children: Html,
+}
+
+/// Reads the active profile's settings once and shares them with all descendants.
+#[function_component]
+pub fn SettingsProvider(props: &SettingsProviderProps) -> Html {
+    let SettingsProviderProps { children } = props;
+
+    let settings = use_state(read_settings);
+    let changed = {
+        let settings = settings.clone();
+        Callback::from(move |next: Settings| settings.set(next))
+    };
+    let context = SettingsContext {
+        settings: (*settings).clone(),
+        changed,
+    };
+
+    html! {
+        <ContextProvider<SettingsContext> {context}>
+            {children.clone()}
+        </ContextProvider<SettingsContext>>
+    }
+}
+
+/// Returns the shared settings.
+#[hook]
+pub fn use_settings() -> SettingsContext {
+    use_context::<SettingsContext>().expect("use_settings must be used inside a SettingsProvider")
+}
@@ -0,0 +1,147 @@
+/// A guided meditation that ships with the app.
+///
+/// # Fields
+/// - `id`: A stable slug used in URLs, e.g. `"body-scan-20"`.
+/// - `title`: The display title.
+/// - `speaker`: The guide's name; logged as the session's speaker.
+/// - `category`: A catalog category name; logged as the session's category.
+/// - `length_seconds`: How long the meditation runs; used as the timer length.
+/// - `description`: A sentence or two about the practice.
+/// - `audio_url`: Optional. A recording served from the app's own `dist`, e.g. `"/audio/body-scan-20.mp3"`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GuidedMeditation {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub speaker: &'static str,
+    pub category: &'static str,
+    pub length_seconds: i64,
+    pub description: &'static str,
+    pub audio_url: Option<&'static str>,
+}
+
+/// Recordings go in `assets/audio`, which Trunk copies to `dist/audio`.
+pub const LIBRARY: [GuidedMeditation; 8] = [
+    GuidedMeditation {
+        id: "arriving-5",
+        title: "Arriving",
+        speaker: "Maya Lindqvist",
+        category: "Mindfulness",
+        length_seconds: 5 * 60,
+        description: "A short pause to notice where you are, how you feel and what you can hear.",
+        audio_url: None,
+    },
+    GuidedMeditation {
+        id: "breath-counting-10",
+        title: "Counting the Breath",
+        speaker: "Maya Lindqvist",
+        category: "Breathing",
+        length_seconds: 10 * 60,
+        description: "Count each out-breath from one to ten and start again whenever the mind wanders.",
+        audio_url: None,
+    },
+    GuidedMeditation {
+        id: "body-scan-20",
+        title: "Full Body Scan",
+        speaker: "Daniel Okafor",
+        category: "Body Scan",
+        length_seconds: 20 * 60,
+        description: "Move your attention slowly from the toes to the crown, meeting each sensation as it is.",
+        audio_url: None,
+    },
+    GuidedMeditation {
+        id: "open-awareness-15",
+        title: "Open Awareness",
+        speaker: "Daniel Okafor",
+        category: "Mindfulness",
+        length_seconds: 15 * 60,
+        description: "Let go of any single focus and rest in whatever arises: sounds, thoughts and sensations.",
+        audio_url: None,
+    },
+    GuidedMeditation {
+        id: "loving-kindness-15",
+        title: "Circles of Kindness",
+        speaker: "Priya Raman",
+        category: "Loving-Kindness",
+        length_seconds: 15 * 60,
+        description: "Offer simple wishes of goodwill to yourself, someone close, a stranger and everyone.",
+        audio_url: None,
+    },
+    GuidedMeditation {
+        id: "walking-10",
+        title: "Ten Mindful Steps",
+        speaker: "Priya Raman",
+        category: "Walking",
+        length_seconds: 10 * 60,
+        description: "Walk slowly back and forth, feeling the lift, swing and placing of each foot.",
+        audio_url: None,
+    },
+    GuidedMeditation {
+        id: "unwind-10",
+        title: "Unwind After Work",
+        speaker: "Maya Lindqvist",
+        category: "Relaxation",
+        length_seconds: 10 * 60,
+        description: "Release the shoulders, jaw and hands, then let a longer out-breath settle the day.",
+        audio_url: None,
+    },
+    GuidedMeditation {
+        id: "sleep-30",
+        title: "Drifting Off",
+        speaker: "Daniel Okafor",
+        category: "Sleep",
+        length_seconds: 30 * 60,
+        description: "A gentle wind-down in bed, softening the body part by part until sleep comes.",
+        audio_url: None,
+    },
+];
+
+/// Finds a library meditation by its id.
+pub fn find_guided_meditation(id: &str) -> Option<&'static GuidedMeditation> {
+    LIBRARY.iter().find(|meditation| meditation.id == id)
+}
+
+/// The categories in the library, in the order they first appear.
+pub fn library_categories() -> Vec<&'static str> {
+    let mut categories: Vec<&'static str> = Vec::new();
+    for meditation in &LIBRARY {
+        if !categories.contains(&meditation.category) {
+            categories.push(meditation.category);
+        }
+    }
+    categories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::meditation_log::catalog::{Catalog, CatalogKind};
+
+    #[test]
+    fn test_ids_are_unique() {
+        for (i, meditation) in LIBRARY.iter().enumerate() {
+            assert!(
+                LIBRARY[i + 1..]
+                    .iter()
+                    .all(|other| other.id != meditation.id),
+                "duplicate id {}",
+                meditation.id
+            );
+        }
+        assert_eq!(
+            find_guided_meditation("body-scan-20")
+                .unwrap()
+                .length_seconds,
+            1200
+        );
+        assert_eq!(find_guided_meditation("missing"), None);
+    }
+
+    #[test]
+    fn test_categories_are_in_the_default_catalog() {
+        let catalog = Catalog::default();
+        for category in library_categories() {
+            let entry = catalog.resolve(CatalogKind::Category, category);
+            assert_eq!(entry.map(|entry| entry.name.as_str()), Some(category));
+        }
+    }
+}
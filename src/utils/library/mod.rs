@@ -0,0 +1 @@
+pub mod guided_meditations;
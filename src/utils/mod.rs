@@ -1,3 +1,10 @@
+pub mod breathing;
+pub mod constants;
 pub mod database;
 pub mod hooks;
+pub mod library;
+pub mod profile;
+pub mod settings;
 pub mod shared;
+pub mod sync;
+pub mod timer;
@@ -0,0 +1,2 @@
+pub mod profile_list;
+pub mod read_write_profiles;
@@ -0,0 +1,278 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The name of the profile existing data is moved into.
+pub const DEFAULT_PROFILE_NAME: &str = "Me";
+
+/// The colour of a profile's avatar.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AvatarColour {
+    #[default]
+    Emerald,
+    Sky,
+    Amber,
+    Rose,
+    Violet,
+    Slate,
+}
+
+impl AvatarColour {
+    /// Every colour, in the order new profiles get them.
+    pub const ALL: [AvatarColour; 6] = [
+        AvatarColour::Emerald,
+        AvatarColour::Sky,
+        AvatarColour::Amber,
+        AvatarColour::Rose,
+        AvatarColour::Violet,
+        AvatarColour::Slate,
+    ];
+
+    /// The Tailwind background class of the avatar.
+    pub fn class(self) -> &'static str {
+        match self {
+            AvatarColour::Emerald => "bg-emerald-500",
+            AvatarColour::Sky => "bg-sky-500",
+            AvatarColour::Amber => "bg-amber-500",
+            AvatarColour::Rose => "bg-rose-500",
+            AvatarColour::Violet => "bg-violet-500",
+            AvatarColour::Slate => "bg-slate-500",
+        }
+    }
+}
+
+/// Someone who uses the app on this device, with their own log and settings.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub colour: AvatarColour,
+}
+
+impl Profile {
+    /// The name of the storage namespace that holds this profile's data.
+    pub fn namespace(&self) -> String {
+        format!("profile-{}", self.id)
+    }
+
+    /// The letter shown in the avatar.
+    pub fn initial(&self) -> char {
+        self.name.chars().next().map_or('?', |initial| {
+            initial.to_uppercase().next().unwrap_or(initial)
+        })
+    }
+}
+
+/// Reasons a change to the profiles was refused.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProfileError {
+    /// The name is empty or only whitespace.
+    BlankName,
+    /// Another profile already has the name.
+    DuplicateName,
+    /// The only profile can't be removed.
+    LastProfile,
+    /// No profile has the id.
+    NotFound,
+    /// The change couldn't be stored.
+    Storage(String),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::BlankName => f.write_str("enter a name"),
+            ProfileError::DuplicateName => f.write_str("another profile has that name"),
+            ProfileError::LastProfile => f.write_str("the last profile can't be removed"),
+            ProfileError::NotFound => f.write_str("that profile doesn't exist"),
+            ProfileError::Storage(message) => write!(f, "profiles not stored: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// The profiles on this device and which one is in use.
+///
+/// There is always at least one profile.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ProfileList {
+    profiles: Vec<Profile>,
+    active: String,
+}
+
+impl ProfileList {
+    /// A list with only the default profile, which is active.
+    pub fn with_default(id: impl Into<String>) -> Self {
+        let id = id.into();
+        ProfileList {
+            profiles: vec![Profile {
+                id: id.clone(),
+                name: DEFAULT_PROFILE_NAME.to_string(),
+                colour: AvatarColour::default(),
+            }],
+            active: id,
+        }
+    }
+
+    pub fn profiles(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    /// The profile in use; the first one if the stored choice no longer exists.
+    pub fn active(&self) -> &Profile {
+        self.profile(&self.active).unwrap_or(&self.profiles[0])
+    }
+
+    pub fn profile(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.id == id)
+    }
+
+    /// Checks a name for a profile, ignoring the profile `except` when it is renamed.
+    fn check_name(&self, name: &str, except: Option<&str>) -> Result<String, ProfileError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ProfileError::BlankName);
+        }
+        let taken = self.profiles.iter().any(|profile| {
+            Some(profile.id.as_str()) != except && profile.name.eq_ignore_ascii_case(name)
+        });
+        if taken {
+            return Err(ProfileError::DuplicateName);
+        }
+        Ok(name.to_string())
+    }
+
+    /// Adds a profile with the first avatar colour no other profile has.
+    ///
+    /// # Returns
+    /// The new profile, or why the name can't be used.
+    pub fn add(&mut self, id: impl Into<String>, name: &str) -> Result<&Profile, ProfileError> {
+        let name = self.check_name(name, None)?;
+        let colour = AvatarColour::ALL
+            .into_iter()
+            .find(|colour| {
+                self.profiles
+                    .iter()
+                    .all(|profile| profile.colour != *colour)
+            })
+            .unwrap_or_default();
+        self.profiles.push(Profile {
+            id: id.into(),
+            name,
+            colour,
+        });
+        Ok(&self.profiles[self.profiles.len() - 1])
+    }
+
+    /// Changes the name and avatar colour of a profile.
+    pub fn edit(&mut self, id: &str, name: &str, colour: AvatarColour) -> Result<(), ProfileError> {
+        let name = self.check_name(name, Some(id))?;
+        let profile = self
+            .profiles
+            .iter_mut()
+            .find(|profile| profile.id == id)
+            .ok_or(ProfileError::NotFound)?;
+        profile.name = name;
+        profile.colour = colour;
+        Ok(())
+    }
+
+    /// Removes a profile; if it was in use, the first remaining one is used instead.
+    ///
+    /// # Returns
+    /// The removed profile, whose data should be deleted.
+    pub fn remove(&mut self, id: &str) -> Result<Profile, ProfileError> {
+        let index = self
+            .profiles
+            .iter()
+            .position(|profile| profile.id == id)
+            .ok_or(ProfileError::NotFound)?;
+        if self.profiles.len() == 1 {
+            return Err(ProfileError::LastProfile);
+        }
+        let removed = self.profiles.remove(index);
+        if self.active == removed.id {
+            self.active = self.profiles[0].id.clone();
+        }
+        Ok(removed)
+    }
+
+    /// Makes a profile the one in use.
+    pub fn activate(&mut self, id: &str) -> Result<(), ProfileError> {
+        if self.profile(id).is_none() {
+            return Err(ProfileError::NotFound);
+        }
+        self.active = id.to_string();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_profile() {
+        let list = ProfileList::with_default("a1");
+
+        assert_eq!(list.profiles().len(), 1);
+        assert_eq!(list.active().name, DEFAULT_PROFILE_NAME);
+        assert_eq!(list.active().namespace(), "profile-a1");
+        assert_eq!(list.active().initial(), 'M');
+    }
+
+    #[test]
+    fn test_add_picks_unused_colour() {
+        let mut list = ProfileList::with_default("a1");
+        let added = list.add("b2", "  Sam ").unwrap();
+
+        assert_eq!(added.name, "Sam");
+        assert_eq!(added.colour, AvatarColour::Sky);
+        assert_eq!(list.active().id, "a1");
+    }
+
+    #[test]
+    fn test_names_must_be_unique_and_not_blank() {
+        let mut list = ProfileList::with_default("a1");
+        list.add("b2", "Sam").unwrap();
+
+        assert_eq!(list.add("c3", " "), Err(ProfileError::BlankName));
+        assert_eq!(list.add("c3", "sam"), Err(ProfileError::DuplicateName));
+        assert_eq!(
+            list.edit("a1", "SAM", AvatarColour::Rose),
+            Err(ProfileError::DuplicateName)
+        );
+        assert_eq!(list.edit("b2", "Samantha", AvatarColour::Rose), Ok(()));
+        assert_eq!(list.profile("b2").unwrap().colour, AvatarColour::Rose);
+        // Keeping its own name is fine.
+        assert_eq!(list.edit("b2", "samantha", AvatarColour::Rose), Ok(()));
+    }
+
+    #[test]
+    fn test_activate_and_remove() {
+        let mut list = ProfileList::with_default("a1");
+        list.add("b2", "Sam").unwrap();
+
+        assert_eq!(list.activate("zz"), Err(ProfileError::NotFound));
+        list.activate("b2").unwrap();
+        assert_eq!(list.active().id, "b2");
+
+        assert_eq!(list.remove("b2").unwrap().name, "Sam");
+        assert_eq!(list.active().id, "a1");
+        assert_eq!(list.remove("a1"), Err(ProfileError::LastProfile));
+        assert_eq!(list.remove("b2"), Err(ProfileError::NotFound));
+    }
+
+    #[test]
+    fn test_missing_active_profile_falls_back_to_first() {
+        let list: ProfileList =
+            serde_json::from_str(r#"{"profiles":[{"id":"a1","name":"Me"}],"active":"gone"}"#)
+                .unwrap();
+
+        assert_eq!(list.active().id, "a1");
+        assert_eq!(list.active().colour, AvatarColour::Emerald);
+    }
+}
@@ -0,0 +1,193 @@
+use std::cell::{Cell, RefCell};
+
+use web_sys::{js_sys::Math, wasm_bindgen::JsValue};
+
+use super::profile_list::{AvatarColour, Profile, ProfileError, ProfileList};
+use crate::{
+    services::local_storage::{LocalStorageService, StorageError},
+    utils::{
+        constants::{
+            ACTIVE_TIMER_KEY, CATALOG_KEY, LOG_CRDT_KEY, MEDITATION_LOG_KEY, PROFILES_KEY,
+            SYNC_STATE_KEY,
+        },
+        database::meditation_log::read_write_meditation_data::{
+            pending_meditation_count, save_pending_meditations,
+        },
+    },
+};
+
+/// The id of the profile that existing data is moved into.
+const FIRST_PROFILE_ID: &str = "default";
+
+/// The values each profile keeps for itself, stored without a namespace before there were
+/// profiles.
+pub const PROFILE_KEYS: [&str; 5] = [
+    MEDITATION_LOG_KEY,
+    LOG_CRDT_KEY,
+    SYNC_STATE_KEY,
+    CATALOG_KEY,
+    ACTIVE_TIMER_KEY,
+];
+
+thread_local! {
+    /// The stored profiles, read on first use.
+    static PROFILES: RefCell<Option<ProfileList>> = const { RefCell::new(None) };
+    /// Set when existing data couldn't be moved into the first profile, so it is used where
+    /// it is for this visit.
+    static UNMIGRATED: Cell<bool> = const { Cell::new(false) };
+}
+
+fn new_profile_id() -> String {
+    format!("{:08x}", (Math::random() * u32::MAX as f64) as u32)
+}
+
+/// Moves the values stored before there were profiles into the first profile.
+///
+/// The values are copied before the profile list is stored and only deleted afterwards, so
+/// they are never lost; if copying fails, the copies are removed again.
+fn migrate_into_first_profile(list: &ProfileList) -> Result<(), StorageError> {
+    let namespace = LocalStorageService::namespace(&list.active().namespace());
+    let mut moved = Vec::new();
+    let copied = PROFILE_KEYS.iter().try_for_each(|key| {
+        if let Some(value) = LocalStorageService::read(key)? {
+            namespace.create(key, &value)?;
+            moved.push(*key);
+        }
+        Ok(())
+    });
+    if let Err(error) = copied.and_then(|_| LocalStorageService::set_json(PROFILES_KEY, list)) {
+        let _ = namespace.clear();
+        return Err(error);
+    }
+
+    for key in moved {
+        // A leftover copy is only wasted space; the profile's own copy is used from now on.
+        if LocalStorageService::delete(key).is_err() {
+            web_sys::console::log_1(&JsValue::from_str("Failed to remove moved data"));
+        }
+    }
+    Ok(())
+}
+
+fn load_profiles() -> ProfileList {
+    match LocalStorageService::get_json::<ProfileList>(PROFILES_KEY) {
+        Ok(Some(list)) => list,
+        Ok(None) => {
+            let list = ProfileList::with_default(FIRST_PROFILE_ID);
+            if let Err(error) = migrate_into_first_profile(&list) {
+                web_sys::console::log_1(&JsValue::from_str(&format!(
+                    "Existing data not moved into a profile: {}",
+                    error
+                )));
+                UNMIGRATED.with(|unmigrated| unmigrated.set(true));
+            }
+            list
+        }
+        Err(error) => {
+            web_sys::console::log_1(&JsValue::from_str(&format!(
+                "Profiles can't be read, using the first one: {}",
+                error
+            )));
+            ProfileList::with_default(FIRST_PROFILE_ID)
+        }
+    }
+}
+
+/// Reads the profiles on this device.
+///
+/// The first time, the data stored before there were profiles is moved into a profile
+/// named "Me".
+///
+/// # Returns
+/// The stored `ProfileList`, or one with only the first profile if it can't be read.
+pub fn read_profiles() -> ProfileList {
+    PROFILES.with(|profiles| {
+        profiles
+            .borrow_mut()
+            .get_or_insert_with(load_profiles)
+            .clone()
+    })
+}
+
+/// The profile whose data is read and written.
+pub fn active_profile() -> Profile {
+    read_profiles().active().clone()
+}
+
+/// The local storage key of `key` for the active profile.
+///
+/// All values listed in `PROFILE_KEYS` must be read and written through this.
+pub fn profile_key(key: &str) -> String {
+    // Reading the profiles first also moves existing data into the first profile.
+    let namespace = active_profile().namespace();
+    if UNMIGRATED.with(Cell::get) {
+        return key.to_string();
+    }
+    LocalStorageService::namespace(&namespace).key(key)
+}
+
+/// Applies `change` to the profiles and stores them.
+///
+/// # Returns
+/// What `change` returned, or a `ProfileError` if it failed or the profiles can't be stored;
+/// nothing changes then.
+fn change_profiles<T>(
+    change: impl FnOnce(&mut ProfileList) -> Result<T, ProfileError>,
+) -> Result<T, ProfileError> {
+    if UNMIGRATED.with(Cell::get) {
+        return Err(ProfileError::Storage(
+            "existing data couldn't be moved into a profile".to_string(),
+        ));
+    }
+    let mut list = read_profiles();
+    let result = change(&mut list)?;
+    LocalStorageService::set_json(PROFILES_KEY, &list)
+        .map_err(|error| ProfileError::Storage(error.to_string()))?;
+    PROFILES.with(|profiles| *profiles.borrow_mut() = Some(list));
+    Ok(result)
+}
+
+/// Adds a profile with an empty log; it isn't switched to.
+///
+/// # Returns
+/// The new profile, or why it can't be added.
+pub fn add_profile(name: &str) -> Result<Profile, ProfileError> {
+    change_profiles(|list| list.add(new_profile_id(), name).cloned())
+}
+
+/// Changes the name and avatar colour of a profile.
+pub fn edit_profile(id: &str, name: &str, colour: AvatarColour) -> Result<(), ProfileError> {
+    change_profiles(|list| list.edit(id, name, colour))
+}
+
+/// Stores the sessions of the active profile that are waiting for space, so they don't end
+/// up in another profile's log.
+fn save_pending_before_leaving() -> Result<(), ProfileError> {
+    if save_pending_meditations().is_err() || pending_meditation_count() > 0 {
+        return Err(ProfileError::Storage(
+            "sessions are waiting for space in storage".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Makes another profile the active one.
+pub fn switch_profile(id: &str) -> Result<(), ProfileError> {
+    save_pending_before_leaving()?;
+    change_profiles(|list| list.activate(id))
+}
+
+/// Removes a profile and deletes all its data.
+///
+/// # Returns
+/// A `ProfileError` if it is the last profile or the profiles can't be stored.
+pub fn remove_profile(id: &str) -> Result<(), ProfileError> {
+    if active_profile().id == id {
+        save_pending_before_leaving()?;
+    }
+    let removed = change_profiles(|list| list.remove(id))?;
+    LocalStorageService::namespace(&removed.namespace())
+        .clear()
+        .map_err(|error| ProfileError::Storage(error.to_string()))?;
+    Ok(())
+}
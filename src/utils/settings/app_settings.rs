@@ -0,0 +1,575 @@
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The settings version written by this build.
+pub const CURRENT_SETTINGS_VERSION: u32 = 1;
+/// The shortest and longest default session, in minutes.
+pub const SESSION_MINUTES_RANGE: std::ops::RangeInclusive<u32> = 1..=240;
+/// The most reminders a profile can have.
+pub const MAX_REMINDERS: usize = 6;
+/// The largest UTC offset, in minutes, any time zone uses.
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Upgrades the stored fields of one settings version to the next.
+pub type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades settings of version `n + 1` to version `n + 2`, so stored settings
+/// are brought up to date one version at a time. Version 1 is the first.
+const MIGRATIONS: [Migration; CURRENT_SETTINGS_VERSION as usize - 1] = [];
+
+/// Which clock dates and times are shown in.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeZoneSetting {
+    /// Whatever time zone the device is set to.
+    #[default]
+    Device,
+    /// A fixed offset from UTC, in minutes.
+    UtcOffset(i32),
+}
+
+impl fmt::Display for TimeZoneSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeZoneSetting::Device => f.write_str("Device time zone"),
+            TimeZoneSetting::UtcOffset(minutes) => write!(
+                f,
+                "UTC{}{:02}:{:02}",
+                if *minutes < 0 { '-' } else { '+' },
+                minutes.abs() / 60,
+                minutes.abs() % 60
+            ),
+        }
+    }
+}
+
+impl TimeZoneSetting {
+    /// The date and time on the clock of this time zone at the UNIX timestamp `seconds`.
+    pub fn local_datetime(self, seconds: i64) -> Option<NaiveDateTime> {
+        let utc = DateTime::<Utc>::from_timestamp(seconds, 0)?;
+        match self {
+            TimeZoneSetting::Device => Some(utc.with_timezone(&Local).naive_local()),
+            TimeZoneSetting::UtcOffset(minutes) => utc
+                .naive_utc()
+                .checked_add_signed(Duration::minutes(minutes as i64)),
+        }
+    }
+
+    /// The date on the clock of this time zone at the UNIX timestamp `seconds`.
+    pub fn local_date(self, seconds: i64) -> Option<NaiveDate> {
+        self.local_datetime(seconds).map(|datetime| datetime.date())
+    }
+
+    /// The current date and time on the clock of this time zone.
+    pub fn now(self) -> NaiveDateTime {
+        let now = Utc::now();
+        self.local_datetime(now.timestamp())
+            .unwrap_or_else(|| now.naive_utc())
+    }
+
+    /// Today's date in this time zone.
+    pub fn today(self) -> NaiveDate {
+        self.now().date()
+    }
+}
+
+/// The first day of the week in calendars and weekly totals.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Saturday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub const ALL: [WeekStart; 3] = [WeekStart::Monday, WeekStart::Saturday, WeekStart::Sunday];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WeekStart::Monday => "Monday",
+            WeekStart::Saturday => "Saturday",
+            WeekStart::Sunday => "Sunday",
+        }
+    }
+
+    pub fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Saturday => Weekday::Sat,
+            WeekStart::Sunday => Weekday::Sun,
+        }
+    }
+
+    /// How many days into its week `date` is, from 0 on the first day to 6 on the last.
+    pub fn days_into_week(self, date: NaiveDate) -> i64 {
+        let start = self.weekday().num_days_from_monday();
+        ((date.weekday().num_days_from_monday() + 7 - start) % 7) as i64
+    }
+
+    /// The first day of the week `date` is in.
+    pub fn week_of(self, date: NaiveDate) -> NaiveDate {
+        date - Duration::days(self.days_into_week(date))
+    }
+}
+
+/// How lengths of time are shown.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationUnits {
+    /// Everything in minutes, e.g. "90 min".
+    #[default]
+    Minutes,
+    /// Hours and minutes once past an hour, e.g. "1 h 30 min".
+    Hours,
+}
+
+impl DurationUnits {
+    pub const ALL: [DurationUnits; 2] = [DurationUnits::Minutes, DurationUnits::Hours];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DurationUnits::Minutes => "Minutes",
+            DurationUnits::Hours => "Hours and minutes",
+        }
+    }
+}
+
+/// The colour scheme of the app.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    /// Light or dark, following the device.
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::System => "Same as device",
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+        }
+    }
+
+    /// The Tailwind classes of the page background and text.
+    pub fn class(self) -> &'static str {
+        match self {
+            Theme::System => "bg-gray-50 text-gray-900 dark:bg-gray-900 dark:text-gray-100",
+            Theme::Light => "bg-gray-50 text-gray-900",
+            Theme::Dark => "bg-gray-900 text-gray-100",
+        }
+    }
+}
+
+/// A time of day to be reminded to meditate, stored as "HH:MM".
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(try_from = "String", into = "String")]
+pub struct ReminderTime {
+    hour: u8,
+    minute: u8,
+}
+
+impl ReminderTime {
+    /// The time `hour:minute`, or `None` if it isn't a time of day.
+    pub fn new(hour: u8, minute: u8) -> Option<Self> {
+        (hour < 24 && minute < 60).then_some(ReminderTime { hour, minute })
+    }
+
+    pub fn hour(self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(self) -> u8 {
+        self.minute
+    }
+}
+
+/// The latest reminder that came due after `since` and no later than `now`, both read off the
+/// same clock, so one that came due while the app was asleep isn't missed.
+pub fn due_reminder(
+    reminders: &[ReminderTime],
+    since: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Option<ReminderTime> {
+    since
+        .date()
+        .iter_days()
+        .take_while(|date| *date <= now.date())
+        .flat_map(|date| {
+            reminders.iter().filter_map(move |time| {
+                let at = date.and_hms_opt(time.hour.into(), time.minute.into(), 0)?;
+                (since < at && at <= now).then_some((at, *time))
+            })
+        })
+        .max()
+        .map(|(_, time)| time)
+}
+
+impl fmt::Display for ReminderTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+impl TryFrom<String> for ReminderTime {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value
+            .split_once(':')
+            .and_then(|(hour, minute)| ReminderTime::new(hour.parse().ok()?, minute.parse().ok()?))
+            .ok_or_else(|| format!("{:?} is not a time of day", value))
+    }
+}
+
+impl From<ReminderTime> for String {
+    fn from(time: ReminderTime) -> Self {
+        time.to_string()
+    }
+}
+
+/// The preferences of a profile.
+///
+/// Fields missing from stored settings take their default, so new settings can be added
+/// without a migration.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    version: u32,
+    pub timezone: TimeZoneSetting,
+    pub week_start: WeekStart,
+    /// The length the timer starts with, in minutes.
+    pub default_session_minutes: u32,
+    /// The category new sessions start with; empty for none.
+    pub default_category: String,
+    pub units: DurationUnits,
+    pub theme: Theme,
+    /// When to be reminded to meditate while the app is open. Sorted, without duplicates.
+    pub reminder_times: Vec<ReminderTime>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: CURRENT_SETTINGS_VERSION,
+            timezone: TimeZoneSetting::Device,
+            week_start: WeekStart::Monday,
+            default_session_minutes: 10,
+            default_category: String::new(),
+            units: DurationUnits::Minutes,
+            theme: Theme::System,
+            reminder_times: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Brings every field into its allowed range, e.g. after editing.
+    pub fn normalise(&mut self) {
+        if let TimeZoneSetting::UtcOffset(minutes) = &mut self.timezone {
+            *minutes = (*minutes).clamp(-MAX_UTC_OFFSET_MINUTES, MAX_UTC_OFFSET_MINUTES);
+        }
+        self.default_session_minutes = self
+            .default_session_minutes
+            .clamp(*SESSION_MINUTES_RANGE.start(), *SESSION_MINUTES_RANGE.end());
+        self.default_category = self.default_category.trim().to_string();
+        self.reminder_times.sort();
+        self.reminder_times.dedup();
+        self.reminder_times.truncate(MAX_REMINDERS);
+    }
+
+    /// Formats a length of time in the chosen units.
+    ///
+    /// # Arguments
+    /// * `minutes` - The length of time, rounded down to whole minutes.
+    pub fn format_minutes(&self, minutes: i64) -> String {
+        match self.units {
+            DurationUnits::Hours if minutes >= 60 => match minutes % 60 {
+                0 => format!("{} h", minutes / 60),
+                rest => format!("{} h {} min", minutes / 60, rest),
+            },
+            _ => format!("{} min", minutes),
+        }
+    }
+}
+
+/// Reasons stored settings can't be used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SettingsError {
+    /// The settings were written by a newer version of the app.
+    NewerVersion(u32),
+    /// The stored value isn't settings at all.
+    Unreadable(String),
+    /// The settings couldn't be stored.
+    Storage(String),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::NewerVersion(version) => write!(
+                f,
+                "settings version {} needs a newer version of the app",
+                version
+            ),
+            SettingsError::Unreadable(message) => write!(f, "settings can't be read: {}", message),
+            SettingsError::Storage(message) => write!(f, "settings not stored: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Reads stored settings, upgrading them from older versions.
+///
+/// A field that can't be read, e.g. a theme this version doesn't know, takes its default
+/// instead of losing all settings.
+///
+/// # Returns
+/// - `Ok(Settings)`: The settings, at the current version.
+/// - `Err(SettingsError)`: If the value isn't settings or was written by a newer version.
+pub fn migrate_settings(stored: Value) -> Result<Settings, SettingsError> {
+    migrate_with(stored, &MIGRATIONS)
+}
+
+fn migrate_with(stored: Value, migrations: &[Migration]) -> Result<Settings, SettingsError> {
+    let Value::Object(mut fields) = stored else {
+        return Err(SettingsError::Unreadable("not an object".to_string()));
+    };
+    let version = match fields.get("version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| SettingsError::Unreadable("invalid version".to_string()))?,
+    };
+    let latest = migrations.len() as u32 + 1;
+    if version > latest {
+        return Err(SettingsError::NewerVersion(version));
+    }
+    for migration in &migrations[version as usize - 1..] {
+        migration(&mut fields);
+    }
+    fields.insert("version".to_string(), Value::from(latest));
+
+    let mut settings = match serde_json::from_value(Value::Object(fields.clone())) {
+        Ok(settings) => settings,
+        // Keep every field that can be read on its own.
+        Err(_) => fields
+            .into_iter()
+            .fold(Settings::default(), |settings, (name, value)| {
+                let mut candidate = match serde_json::to_value(&settings) {
+                    Ok(Value::Object(candidate)) => candidate,
+                    _ => return settings,
+                };
+                candidate.insert(name, value);
+                serde_json::from_value(Value::Object(candidate)).unwrap_or(settings)
+            }),
+    };
+    settings.normalise();
+    settings.version = latest;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip() {
+        let mut settings = Settings {
+            timezone: TimeZoneSetting::UtcOffset(-210),
+            week_start: WeekStart::Sunday,
+            default_session_minutes: 25,
+            default_category: "Breathing".to_string(),
+            units: DurationUnits::Hours,
+            theme: Theme::Dark,
+            reminder_times: vec![ReminderTime::new(7, 30).unwrap()],
+            ..Settings::default()
+        };
+        settings.normalise();
+
+        let stored = serde_json::to_value(&settings).unwrap();
+        assert_eq!(stored["reminder_times"], json!(["07:30"]));
+        assert_eq!(stored["timezone"], json!({"utc_offset": -210}));
+        assert_eq!(migrate_settings(stored), Ok(settings));
+    }
+
+    #[test]
+    fn test_missing_fields_take_defaults() {
+        let settings = migrate_settings(json!({"theme": "dark"})).unwrap();
+
+        assert_eq!(settings.theme, Theme::Dark);
+        assert_eq!(settings.default_session_minutes, 10);
+        assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn test_unreadable_fields_are_dropped() {
+        let settings = migrate_settings(json!({
+            "version": 1,
+            "theme": "neon",
+            "week_start": "sunday",
+            "reminder_times": ["25:00"],
+        }))
+        .unwrap();
+
+        assert_eq!(settings.theme, Theme::System);
+        assert_eq!(settings.week_start, WeekStart::Sunday);
+        assert!(settings.reminder_times.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_newer_and_invalid_settings() {
+        assert_eq!(
+            migrate_settings(json!({"version": 99})),
+            Err(SettingsError::NewerVersion(99))
+        );
+        assert!(matches!(
+            migrate_settings(json!([1, 2])),
+            Err(SettingsError::Unreadable(_))
+        ));
+        assert!(matches!(
+            migrate_settings(json!({"version": 0})),
+            Err(SettingsError::Unreadable(_))
+        ));
+    }
+
+    #[test]
+    fn test_migrations_run_in_order_from_the_stored_version() {
+        fn rename_length(fields: &mut Map<String, Value>) {
+            if let Some(length) = fields.remove("session_length") {
+                fields.insert("default_session_minutes".to_string(), length);
+            }
+        }
+        fn double_length(fields: &mut Map<String, Value>) {
+            let minutes = fields["default_session_minutes"].as_u64().unwrap();
+            fields.insert("default_session_minutes".to_string(), json!(minutes * 2));
+        }
+        let migrations: [Migration; 2] = [rename_length, double_length];
+
+        let from_first = migrate_with(json!({"session_length": 15}), &migrations).unwrap();
+        assert_eq!(from_first.default_session_minutes, 30);
+        assert_eq!(from_first.version, 3);
+
+        let from_second = migrate_with(
+            json!({"version": 2, "default_session_minutes": 15}),
+            &migrations,
+        )
+        .unwrap();
+        assert_eq!(from_second.default_session_minutes, 30);
+    }
+
+    #[test]
+    fn test_local_dates_follow_the_time_zone() {
+        // 2024-02-10 23:30 UTC, a Saturday.
+        let seconds = 1_707_607_800;
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(
+            TimeZoneSetting::UtcOffset(0).local_date(seconds),
+            Some(date(2024, 2, 10))
+        );
+        assert_eq!(
+            TimeZoneSetting::UtcOffset(60).local_date(seconds),
+            Some(date(2024, 2, 11))
+        );
+        assert_eq!(
+            TimeZoneSetting::UtcOffset(-210)
+                .local_datetime(seconds)
+                .unwrap()
+                .to_string(),
+            "2024-02-10 20:00:00"
+        );
+    }
+
+    #[test]
+    fn test_week_of() {
+        let saturday = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+        let week_of = |start: WeekStart| start.week_of(saturday).to_string();
+
+        assert_eq!(week_of(WeekStart::Monday), "2024-02-05");
+        assert_eq!(week_of(WeekStart::Sunday), "2024-02-04");
+        assert_eq!(week_of(WeekStart::Saturday), "2024-02-10");
+        assert_eq!(WeekStart::Sunday.days_into_week(saturday), 6);
+    }
+
+    #[test]
+    fn test_due_reminder() {
+        let reminders =
+            ["07:30", "21:00"].map(|time| ReminderTime::try_from(time.to_string()).unwrap());
+        let at = |day: u32, hour: u32, minute: u32| {
+            NaiveDate::from_ymd_opt(2024, 2, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap()
+        };
+
+        assert_eq!(
+            due_reminder(&reminders, at(10, 7, 29), at(10, 7, 30)),
+            Some(reminders[0])
+        );
+        assert_eq!(due_reminder(&reminders, at(10, 7, 30), at(10, 7, 31)), None);
+        // Asleep overnight: only the latest one missed is shown.
+        assert_eq!(
+            due_reminder(&reminders, at(9, 20, 0), at(10, 8, 0)),
+            Some(reminders[0])
+        );
+        assert_eq!(
+            due_reminder(&reminders, at(9, 20, 0), at(9, 23, 0)),
+            Some(reminders[1])
+        );
+        assert_eq!(due_reminder(&[], at(9, 0, 0), at(10, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_normalise() {
+        let mut settings = Settings {
+            timezone: TimeZoneSetting::UtcOffset(20 * 60),
+            default_session_minutes: 0,
+            default_category: "  Sleep ".to_string(),
+            reminder_times: ["21:00", "07:15", "21:00"]
+                .map(|time| ReminderTime::try_from(time.to_string()).unwrap())
+                .to_vec(),
+            ..Settings::default()
+        };
+        settings.normalise();
+
+        assert_eq!(settings.timezone, TimeZoneSetting::UtcOffset(14 * 60));
+        assert_eq!(settings.default_session_minutes, 1);
+        assert_eq!(settings.default_category, "Sleep");
+        assert_eq!(
+            settings
+                .reminder_times
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["07:15", "21:00"]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(TimeZoneSetting::UtcOffset(330).to_string(), "UTC+05:30");
+        assert_eq!(TimeZoneSetting::UtcOffset(-60).to_string(), "UTC-01:00");
+
+        let mut settings = Settings::default();
+        assert_eq!(settings.format_minutes(90), "90 min");
+        settings.units = DurationUnits::Hours;
+        assert_eq!(settings.format_minutes(90), "1 h 30 min");
+        assert_eq!(settings.format_minutes(120), "2 h");
+        assert_eq!(settings.format_minutes(45), "45 min");
+    }
+}
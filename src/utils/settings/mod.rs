@@ -0,0 +1,2 @@
+pub mod app_settings;
+pub mod read_write_settings;
@@ -0,0 +1,43 @@
+use serde_json::Value;
+use web_sys::wasm_bindgen::JsValue;
+
+use super::app_settings::{migrate_settings, Settings, SettingsError};
+use crate::utils::{
+    constants::SETTINGS_KEY, database::protected_storage::ProtectedStorage,
+    profile::read_write_profiles::profile_key,
+};
+
+/// Reads the settings of the active profile.
+///
+/// Logs an error message to the web console if stored settings can't be used.
+///
+/// # Returns
+/// The stored `Settings`, upgraded to the current version, or the defaults if none are
+/// stored or they can't be read.
+pub fn read_settings() -> Settings {
+    let stored = ProtectedStorage::get_json::<Value>(&profile_key(SETTINGS_KEY))
+        .map_err(|error| SettingsError::Unreadable(error.to_string()))
+        .and_then(|stored| stored.map(migrate_settings).transpose());
+    match stored {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(error) => {
+            web_sys::console::log_1(&JsValue::from_str(&format!(
+                "Using default settings: {}",
+                error
+            )));
+            Settings::default()
+        }
+    }
+}
+
+/// Stores the settings of the active profile, after bringing them into range.
+///
+/// # Returns
+/// The settings as stored, or a `SettingsError` if they couldn't be.
+pub fn save_settings(settings: &Settings) -> Result<Settings, SettingsError> {
+    let mut settings = settings.clone();
+    settings.normalise();
+    ProtectedStorage::set_json(&profile_key(SETTINGS_KEY), &settings)
+        .map_err(|error| SettingsError::Storage(error.to_string()))?;
+    Ok(settings)
+}
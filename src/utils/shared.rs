@@ -1 +1,73 @@
+use chrono::NaiveDateTime;
 
+/// Formats a number of seconds as a clock, e.g. `"05:07"` or `"1:02:03"`.
+///
+/// # Arguments
+/// - `seconds`: The number of seconds to format; negative values are shown as zero.
+pub fn format_clock(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Formats a duration for reading, e.g. `"45s"`, `"12m 30s"` or `"1h 5m"`.
+///
+/// Seconds are dropped once a duration reaches an hour.
+///
+/// # Arguments
+/// - `seconds`: The number of seconds to format; negative values are shown as zero.
+pub fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    match (hours, minutes, seconds) {
+        (0, 0, seconds) => format!("{}s", seconds),
+        (0, minutes, 0) => format!("{}m", minutes),
+        (0, minutes, seconds) => format!("{}m {}s", minutes, seconds),
+        (hours, 0, _) => format!("{}h", hours),
+        (hours, minutes, _) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+/// Formats a date and time for reading, e.g. `"Sat 10 Feb 2024, 08:05"`.
+///
+/// # Arguments
+/// - `datetime`: The date and time, already converted to the timezone it should be shown in.
+pub fn format_date_time(datetime: NaiveDateTime) -> String {
+    datetime.format("%a %-d %b %Y, %H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock(0), "00:00");
+        assert_eq!(format_clock(307), "05:07");
+        assert_eq!(format_clock(3723), "1:02:03");
+        assert_eq!(format_clock(-5), "00:00");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(720), "12m");
+        assert_eq!(format_duration(750), "12m 30s");
+        assert_eq!(format_duration(3600), "1h");
+        assert_eq!(format_duration(3930), "1h 5m");
+        assert_eq!(format_duration(-1), "0s");
+    }
+
+    #[test]
+    fn test_format_date_time() {
+        let datetime = chrono::NaiveDate::from_ymd_opt(2024, 2, 10)
+            .unwrap()
+            .and_hms_opt(8, 5, 0)
+            .unwrap();
+        assert_eq!(format_date_time(datetime), "Sat 10 Feb 2024, 08:05");
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// Exponential backoff between retries of a failing sync.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Backoff {
+    base_ms: u32,
+    max_ms: u32,
+    failures: u32,
+}
+
+impl Backoff {
+    /// Creates a backoff that waits `base_ms` after the first failure and never longer
+    /// than `max_ms`.
+    pub fn new(base_ms: u32, max_ms: u32) -> Self {
+        Backoff {
+            base_ms,
+            max_ms,
+            failures: 0,
+        }
+    }
+
+    /// Records a failure and returns how long to wait before retrying, in milliseconds.
+    ///
+    /// The wait doubles with every failure in a row. `jitter`, in `0.0..1.0`, shortens it by
+    /// up to half, so devices that failed together don't all retry at once.
+    pub fn fail(&mut self, jitter: f64) -> u32 {
+        let exponent = self.failures.min(31);
+        self.failures = self.failures.saturating_add(1);
+        let delay = (self.base_ms as u64)
+            .saturating_mul(1 << exponent)
+            .min(self.max_ms as u64);
+        (delay as f64 * (1.0 - jitter.clamp(0.0, 1.0) / 2.0)) as u32
+    }
+
+    /// Records a success, so the next failure waits the base time again.
+    pub fn succeed(&mut self) {
+        self.failures = 0;
+    }
+
+    /// The number of failures in a row.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let mut backoff = Backoff::new(1_000, 5_000);
+        let delays: Vec<u32> = (0..5).map(|_| backoff.fail(0.0)).collect();
+        assert_eq!(delays, vec![1_000, 2_000, 4_000, 5_000, 5_000]);
+        assert_eq!(backoff.failures(), 5);
+
+        backoff.succeed();
+        assert_eq!(backoff.fail(1.0), 500);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod backoff;
+pub mod outbox;
+pub mod read_write_sync_state;
+pub mod sync_engine;
+pub mod sync_record;
+pub mod sync_runner;
+pub mod sync_server;
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use super::sync_record::SyncRecord;
+
+/// Changes made on this device that the sync server hasn't confirmed yet.
+///
+/// Changes to the same session are merged into one record, since the server only needs
+/// the latest state.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Outbox {
+    records: Vec<SyncRecord>,
+}
+
+impl Outbox {
+    /// Queues a change, merging it into any queued change to the same session.
+    pub fn push(&mut self, record: SyncRecord) {
+        match self
+            .records
+            .iter_mut()
+            .find(|queued| queued.id == record.id)
+        {
+            Some(queued) => {
+                queued.session.merge(&record.session);
+            }
+            None => self.records.push(record),
+        }
+    }
+
+    /// Up to `limit` of the oldest queued changes.
+    pub fn batch(&self, limit: usize) -> &[SyncRecord] {
+        &self.records[..self.records.len().min(limit)]
+    }
+
+    /// Removes changes the server has received.
+    ///
+    /// Sessions that changed again after `sent` was taken stay queued.
+    pub fn acknowledge(&mut self, sent: &[SyncRecord]) {
+        self.records.retain(|queued| !sent.contains(queued));
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::meditation_log::{
+        log_crdt::LogCrdt, meditation_data_builder::MeditationData,
+    };
+
+    fn record(id: &str, duration: i32, at: i64) -> SyncRecord {
+        let mut log = LogCrdt::default();
+        let meditation = MeditationData {
+            id: id.to_string(),
+            duration,
+            modified_at: at,
+            ..Default::default()
+        };
+        log.observe(&[meditation], "phone", at);
+        SyncRecord::from_log(&log, id).unwrap()
+    }
+
+    #[test]
+    fn test_outbox_merges_changes_per_session() {
+        let mut outbox = Outbox::default();
+        outbox.push(record("a", 60, 1));
+        outbox.push(record("b", 60, 2));
+        outbox.push(record("a", 90, 3));
+
+        assert_eq!(outbox.len(), 2);
+        let queued = &outbox.batch(1)[0];
+        assert_eq!(queued.id, "a");
+        assert_eq!(queued.session.to_meditation("a").duration, 90);
+    }
+
+    #[test]
+    fn test_acknowledge_keeps_newer_changes() {
+        let mut outbox = Outbox::default();
+        outbox.push(record("a", 60, 1));
+        outbox.push(record("b", 60, 2));
+        let sent = outbox.batch(10).to_vec();
+        outbox.push(record("b", 90, 5));
+
+        outbox.acknowledge(&sent);
+        assert_eq!(outbox.len(), 1);
+        assert_eq!(outbox.batch(10)[0].id, "b");
+    }
+}
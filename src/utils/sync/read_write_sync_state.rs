@@ -0,0 +1,127 @@
+use std::cell::Cell;
+
+use chrono::Utc;
+use web_sys::{js_sys::Math, wasm_bindgen::JsValue};
+
+use super::{
+    sync_engine::SyncState,
+    sync_record::{new_record_id, stamp, SyncRecord},
+};
+use crate::{
+    services::local_storage::StorageError,
+    utils::{
+        constants::SYNC_STATE_KEY,
+        database::{
+            meditation_log::meditation_data_builder::MeditationData,
+            protected_storage::ProtectedStorage,
+        },
+        profile::read_write_profiles::profile_key,
+    },
+};
+
+thread_local! {
+    static NEXT_SEQUENCE: Cell<u32> = const { Cell::new(0) };
+}
+
+fn new_device_id() -> String {
+    format!("{:08x}", (Math::random() * u32::MAX as f64) as u32)
+}
+
+/// Reads the sync settings and progress of this device.
+///
+/// A device id is created and stored the first time, so sessions get ids even while sync
+/// is off.
+///
+/// # Returns
+/// The stored `SyncState`, a fresh one with sync off if none is stored, or a `StorageError`
+/// if the stored one can't be read; it is left untouched then, so its queued changes
+/// aren't lost.
+pub fn try_read_sync_state() -> Result<SyncState, StorageError> {
+    let mut state: SyncState = match ProtectedStorage::read(&profile_key(SYNC_STATE_KEY))? {
+        Some(value) => serde_json::from_str(&value)
+            .map_err(|error| StorageError::Unreadable(error.to_string()))?,
+        None => SyncState::default(),
+    };
+    if state.device_id.is_empty() {
+        state.device_id = new_device_id();
+        save_sync_state(&state)?;
+    }
+    Ok(state)
+}
+
+/// Reads the sync settings and progress of this device for display.
+///
+/// Never store what this returns; change the state read by `try_read_sync_state` instead.
+///
+/// # Returns
+/// The stored `SyncState`, or a fresh one with sync off if none is stored or it can't be
+/// read; the error is logged to the web console then.
+pub fn read_sync_state() -> SyncState {
+    try_read_sync_state().unwrap_or_else(|error| {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Sync state can't be read: {}",
+            error
+        )));
+        SyncState::default()
+    })
+}
+
+/// Stores the sync settings and progress of this device.
+///
+/// # Returns
+/// A `StorageError` if the state couldn't be written.
+pub fn save_sync_state(state: &SyncState) -> Result<(), StorageError> {
+    let value =
+        serde_json::to_string(state).map_err(|error| StorageError::Failed(error.to_string()))?;
+    ProtectedStorage::create(&profile_key(SYNC_STATE_KEY), &value)
+}
+
+/// Sets the id and modification time of a session that is about to be stored.
+///
+/// # Arguments
+/// * `meditation` - The session to store.
+/// * `previous` - The stored version it replaces, if any.
+pub fn stamp_change(meditation: &mut MeditationData, previous: Option<&MeditationData>) {
+    let now_ms = Utc::now().timestamp_millis();
+    stamp(
+        meditation,
+        previous,
+        || {
+            let sequence = NEXT_SEQUENCE.with(|next| next.replace(next.get().wrapping_add(1)));
+            // While the stored device id can't be read, a random one keeps ids unique.
+            let device_id = try_read_sync_state()
+                .map(|state| state.device_id)
+                .unwrap_or_else(|_| new_device_id());
+            new_record_id(&device_id, now_ms, sequence)
+        },
+        now_ms,
+    );
+}
+
+/// Queues changes made on this device for the next sync. Does nothing while sync is off.
+///
+/// Logs an error message to the web console if the sync state can't be read or written.
+pub fn record_sync_changes(records: impl IntoIterator<Item = SyncRecord>) {
+    let mut state = match try_read_sync_state() {
+        Ok(state) => state,
+        Err(error) => {
+            web_sys::console::log_1(&JsValue::from_str(&format!(
+                "Changes not queued for sync: {}",
+                error
+            )));
+            return;
+        }
+    };
+    if !state.is_enabled() {
+        return;
+    }
+    for record in records {
+        state.record_change(record);
+    }
+    if let Err(error) = save_sync_state(&state) {
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "Changes not queued for sync: {}",
+            error
+        )));
+    }
+}
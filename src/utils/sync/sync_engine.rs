@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+
+use super::{outbox::Outbox, sync_record::SyncRecord};
+use crate::utils::database::meditation_log::log_crdt::LogCrdt;
+
+/// The most changes sent in one push, to keep requests small after a long time offline.
+pub const PUSH_BATCH_SIZE: usize = 100;
+
+/// Body of `POST {endpoint}/push`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct PushRequest {
+    pub device_id: String,
+    pub records: Vec<SyncRecord>,
+}
+
+/// Response to a push.
+///
+/// # Fields
+/// - `merged`: The server's merged state of pushed sessions that had changed elsewhere.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct PushResponse {
+    #[serde(default)]
+    pub merged: Vec<SyncRecord>,
+}
+
+/// Response to `GET {endpoint}/pull?since={cursor}`.
+///
+/// # Fields
+/// - `records`: Every session the server changed after `cursor`.
+/// - `cursor`: The position to pull from next time.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct PullResponse {
+    pub records: Vec<SyncRecord>,
+    pub cursor: u64,
+}
+
+/// The sync settings and progress of this device.
+///
+/// Sync works without a connection to the server: changes are queued in the outbox and
+/// pushed with the next sync, and the server's changes since `cursor` are pulled after.
+/// Both sides merge sessions as `LogCrdt` states, so no change is lost to a conflict.
+///
+/// # Fields
+/// - `endpoint`: The base URL of the sync server; `None` while sync is off.
+/// - `device_id`: Identifies this device in session ids and pushes.
+/// - `cursor`: How far the server's changes have been pulled.
+/// - `outbox`: Changes not yet confirmed by the server.
+/// - `last_synced_at`: When the last sync finished, in UNIX milliseconds.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct SyncState {
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub device_id: String,
+    #[serde(default)]
+    pub cursor: u64,
+    #[serde(default)]
+    pub outbox: Outbox,
+    #[serde(default)]
+    pub last_synced_at: Option<i64>,
+}
+
+impl SyncState {
+    pub fn is_enabled(&self) -> bool {
+        self.endpoint.is_some()
+    }
+
+    /// Turns sync on and queues the whole log, so the server gets the sessions made before.
+    pub fn enable(&mut self, endpoint: impl Into<String>, log: &LogCrdt) {
+        self.endpoint = Some(endpoint.into());
+        self.cursor = 0;
+        self.last_synced_at = None;
+        self.outbox = Outbox::default();
+        for id in log.ids() {
+            if let Some(record) = SyncRecord::from_log(log, id) {
+                self.outbox.push(record);
+            }
+        }
+    }
+
+    /// Turns sync off and forgets unsent changes; the local log is kept.
+    pub fn disable(&mut self) {
+        self.endpoint = None;
+        self.outbox = Outbox::default();
+    }
+
+    /// Queues a change made on this device. Ignored while sync is off.
+    pub fn record_change(&mut self, record: SyncRecord) {
+        if self.is_enabled() && !record.id.is_empty() {
+            self.outbox.push(record);
+        }
+    }
+
+    /// The next batch of queued changes, or `None` if there is nothing to push.
+    pub fn push_request(&self) -> Option<PushRequest> {
+        (!self.outbox.is_empty()).then(|| PushRequest {
+            device_id: self.device_id.clone(),
+            records: self.outbox.batch(PUSH_BATCH_SIZE).to_vec(),
+        })
+    }
+
+    /// Handles the server's answer to `sent`: delivered changes leave the outbox and the
+    /// server's merged sessions are merged into `log`.
+    ///
+    /// # Returns
+    /// The number of local sessions that changed.
+    pub fn apply_push(
+        &mut self,
+        sent: &PushRequest,
+        response: PushResponse,
+        log: &mut LogCrdt,
+    ) -> usize {
+        self.outbox.acknowledge(&sent.records);
+        merge(&response.merged, log)
+    }
+
+    /// Merges pulled changes into `log` and moves the cursor past them.
+    ///
+    /// # Returns
+    /// The number of local sessions that changed.
+    pub fn apply_pull(&mut self, response: PullResponse, log: &mut LogCrdt, now_ms: i64) -> usize {
+        let changed = merge(&response.records, log);
+        self.cursor = response.cursor;
+        self.last_synced_at = Some(now_ms);
+        changed
+    }
+}
+
+fn merge(records: &[SyncRecord], log: &mut LogCrdt) -> usize {
+    records
+        .iter()
+        .filter(|record| log.merge_session(&record.id, &record.session))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{
+        database::meditation_log::meditation_data_builder::MeditationData,
+        sync::sync_server::SyncServer,
+    };
+    use serde::de::DeserializeOwned;
+
+    /// Sends a value through its JSON form, as the HTTP transport does.
+    fn wire<T: Serialize + DeserializeOwned>(value: T) -> T {
+        serde_json::from_str(&serde_json::to_string(&value).unwrap()).unwrap()
+    }
+
+    struct Device {
+        state: SyncState,
+        log: LogCrdt,
+    }
+
+    impl Device {
+        fn new(device_id: &str) -> Self {
+            let mut state = SyncState {
+                device_id: device_id.to_string(),
+                ..Default::default()
+            };
+            state.enable("http://localhost", &LogCrdt::default());
+            Device {
+                state,
+                log: LogCrdt::default(),
+            }
+        }
+
+        /// Changes the local log as the app does, then records what changed for sync.
+        fn change(&mut self, now_ms: i64, change: impl FnOnce(&mut Vec<MeditationData>)) {
+            let mut view = self.log.meditations();
+            change(&mut view);
+            for meditation in view.iter_mut() {
+                meditation.modified_at = meditation.modified_at.max(now_ms);
+            }
+            for id in self.log.observe(&view, &self.state.device_id, now_ms) {
+                let record = SyncRecord::from_log(&self.log, &id).unwrap();
+                self.state.record_change(record);
+            }
+        }
+
+        fn add(&mut self, duration: i32, now_ms: i64) -> String {
+            let id = format!("{}-{}", self.state.device_id, now_ms);
+            let meditation = MeditationData {
+                id: id.clone(),
+                datetime: now_ms / 1000,
+                duration,
+                ..Default::default()
+            };
+            self.change(now_ms, |view| view.push(meditation));
+            id
+        }
+
+        fn edit(&mut self, id: &str, now_ms: i64, edit: impl FnOnce(&mut MeditationData)) {
+            self.change(now_ms, |view| {
+                edit(
+                    view.iter_mut()
+                        .find(|meditation| meditation.id == id)
+                        .unwrap(),
+                )
+            });
+        }
+
+        fn delete(&mut self, id: &str, now_ms: i64) {
+            self.change(now_ms, |view| view.retain(|meditation| meditation.id != id));
+        }
+
+        fn get(&self, id: &str) -> Option<MeditationData> {
+            self.log
+                .meditations()
+                .into_iter()
+                .find(|meditation| meditation.id == id)
+        }
+
+        fn sync(&mut self, server: &mut SyncServer, now_ms: i64) {
+            while let Some(request) = self.state.push_request() {
+                let response = wire(server.push(&wire(request.clone())));
+                self.state.apply_push(&request, response, &mut self.log);
+            }
+            let response = wire(server.pull(self.state.cursor));
+            self.state.apply_pull(response, &mut self.log, now_ms);
+        }
+    }
+
+    /// Two devices that have both synced one session.
+    fn shared_session() -> (SyncServer, Device, Device, String) {
+        let mut server = SyncServer::default();
+        let mut phone = Device::new("phone");
+        let mut laptop = Device::new("laptop");
+        let id = phone.add(600, 1_000);
+        phone.sync(&mut server, 1_500);
+        laptop.sync(&mut server, 1_500);
+        (server, phone, laptop, id)
+    }
+
+    #[test]
+    fn test_offline_changes_reach_other_devices() {
+        let mut server = SyncServer::default();
+        let mut phone = Device::new("phone");
+        let mut laptop = Device::new("laptop");
+
+        // Logged on a flight, synced on landing.
+        let first = phone.add(600, 1_000);
+        let second = phone.add(900, 2_000);
+        assert_eq!(phone.state.outbox.len(), 2);
+        phone.sync(&mut server, 3_000);
+        assert!(phone.state.outbox.is_empty());
+
+        laptop.sync(&mut server, 4_000);
+        assert_eq!(laptop.get(&first).unwrap().duration, 600);
+        assert_eq!(laptop.get(&second).unwrap().duration, 900);
+        assert_eq!(laptop.state.last_synced_at, Some(4_000));
+    }
+
+    #[test]
+    fn test_concurrent_edits_of_different_fields_are_merged() {
+        let (mut server, mut phone, mut laptop, id) = shared_session();
+
+        phone.edit(&id, 2_000, |meditation| meditation.duration = 900);
+        laptop.edit(&id, 3_000, |meditation| meditation.rating = Some(4));
+        laptop.sync(&mut server, 4_000);
+        phone.sync(&mut server, 5_000);
+        laptop.sync(&mut server, 6_000);
+
+        let merged = phone.get(&id).unwrap();
+        assert_eq!(merged.duration, 900);
+        assert_eq!(merged.rating, Some(4));
+        assert_eq!(phone.log, laptop.log);
+    }
+
+    #[test]
+    fn test_last_writer_wins_for_the_same_field() {
+        let (mut server, mut phone, mut laptop, id) = shared_session();
+
+        laptop.edit(&id, 3_000, |meditation| meditation.duration = 1_200);
+        phone.edit(&id, 2_000, |meditation| meditation.duration = 900);
+        laptop.sync(&mut server, 4_000);
+        phone.sync(&mut server, 5_000);
+
+        assert_eq!(phone.get(&id).unwrap().duration, 1_200);
+    }
+
+    #[test]
+    fn test_deletions_propagate() {
+        let (mut server, mut phone, mut laptop, id) = shared_session();
+
+        phone.delete(&id, 2_000);
+        phone.sync(&mut server, 3_000);
+        laptop.sync(&mut server, 4_000);
+
+        assert_eq!(laptop.get(&id), None);
+        assert_eq!(phone.log, laptop.log);
+    }
+
+    #[test]
+    fn test_edit_made_without_seeing_a_deletion_is_kept() {
+        let (mut server, mut phone, mut laptop, id) = shared_session();
+
+        phone.delete(&id, 3_000);
+        laptop.edit(&id, 2_000, |meditation| {
+            meditation.notes = Some("calm".to_string())
+        });
+        phone.sync(&mut server, 4_000);
+        laptop.sync(&mut server, 5_000);
+        phone.sync(&mut server, 6_000);
+
+        assert_eq!(phone.get(&id).unwrap().notes, Some("calm".to_string()));
+        assert_eq!(phone.log, laptop.log);
+    }
+
+    #[test]
+    fn test_failed_push_keeps_changes_and_retries_are_idempotent() {
+        let mut server = SyncServer::default();
+        let mut phone = Device::new("phone");
+        let id = phone.add(600, 1_000);
+
+        // The request reaches the server but the response is lost.
+        let request = phone.state.push_request().unwrap();
+        server.push(&request);
+        assert_eq!(phone.state.outbox.len(), 1);
+
+        phone.sync(&mut server, 2_000);
+        assert!(phone.state.outbox.is_empty());
+        assert_eq!(server.pull(0).records.len(), 1);
+        assert_eq!(phone.get(&id).unwrap().duration, 600);
+    }
+
+    #[test]
+    fn test_enable_queues_existing_sessions() {
+        let mut state = SyncState {
+            device_id: "phone".to_string(),
+            ..Default::default()
+        };
+        let mut log = LogCrdt::default();
+        let meditations: Vec<MeditationData> = (0..PUSH_BATCH_SIZE + 1)
+            .map(|i| MeditationData {
+                id: i.to_string(),
+                datetime: i as i64 + 1,
+                ..Default::default()
+            })
+            .collect();
+        for id in log.observe(&meditations, "phone", 7) {
+            state.record_change(SyncRecord::from_log(&log, &id).unwrap());
+        }
+        assert!(state.push_request().is_none());
+
+        state.enable("http://localhost", &log);
+        assert_eq!(state.outbox.len(), PUSH_BATCH_SIZE + 1);
+        assert_eq!(state.push_request().unwrap().records.len(), PUSH_BATCH_SIZE);
+    }
+}
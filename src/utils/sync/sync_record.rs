@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::database::meditation_log::{
+    log_crdt::{LogCrdt, SessionCrdt},
+    meditation_data_builder::MeditationData,
+};
+
+/// One session's replicated state, as exchanged with the sync server.
+///
+/// Both sides merge what they receive into what they have, so records can be sent more than
+/// once and in any order. A deleted session is still sent, so the deletion reaches other devices.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SyncRecord {
+    pub id: String,
+    pub session: SessionCrdt,
+}
+
+impl SyncRecord {
+    /// The current state of the session `id` in `log`.
+    pub fn from_log(log: &LogCrdt, id: &str) -> Option<Self> {
+        log.session(id).map(|session| SyncRecord {
+            id: id.to_string(),
+            session: session.clone(),
+        })
+    }
+}
+
+/// Creates a session id that is unique across devices.
+///
+/// # Arguments
+/// * `device_id` - The id of the device creating the session.
+/// * `now_ms` - The current time in UNIX milliseconds.
+/// * `sequence` - Distinguishes ids created on the device in the same millisecond.
+pub fn new_record_id(device_id: &str, now_ms: i64, sequence: u32) -> String {
+    format!("{}-{:x}-{:x}", device_id, now_ms, sequence)
+}
+
+/// Sets the id and modification time of a session that is about to be stored.
+///
+/// An edited session keeps the id of the version it replaces, and its modification time is
+/// moved past that version's, so the edit wins even when the device clock is behind.
+///
+/// # Arguments
+/// * `meditation` - The session to store.
+/// * `previous` - The stored version it replaces, if any.
+/// * `new_id` - Creates an id for a session that has none yet.
+/// * `now_ms` - The current time in UNIX milliseconds.
+pub fn stamp(
+    meditation: &mut MeditationData,
+    previous: Option<&MeditationData>,
+    new_id: impl FnOnce() -> String,
+    now_ms: i64,
+) {
+    let previous_id = previous
+        .map(|previous| previous.id.clone())
+        .unwrap_or_default();
+    meditation.id = match (previous_id.is_empty(), meditation.id.is_empty()) {
+        (false, _) => previous_id,
+        (true, true) => new_id(),
+        (true, false) => std::mem::take(&mut meditation.id),
+    };
+    let previous_modified = previous.map_or(0, |previous| previous.modified_at);
+    meditation.modified_at = now_ms.max(previous_modified + 1);
+}
+
+/// Gives ids to sessions stored before sessions had them, e.g. when sync is first enabled.
+///
+/// Their modification time becomes their start time, so any real edit made elsewhere wins.
+///
+/// # Returns
+/// The number of sessions that were given an id.
+pub fn assign_missing_ids(
+    meditations: &mut [MeditationData],
+    device_id: &str,
+    now_ms: i64,
+) -> usize {
+    let mut assigned = 0;
+    for (sequence, meditation) in meditations.iter_mut().enumerate() {
+        if meditation.id.is_empty() {
+            meditation.id = new_record_id(device_id, now_ms, sequence as u32);
+            if meditation.modified_at == 0 {
+                meditation.modified_at = meditation.datetime * 1000;
+            }
+            assigned += 1;
+        }
+    }
+    assigned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, modified_at: i64, duration: i32) -> MeditationData {
+        MeditationData {
+            id: id.to_string(),
+            modified_at,
+            duration,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_stamp_keeps_id_and_moves_time_forward() {
+        let previous = session("device-1", 5_000, 600);
+        let mut edited = session("", 0, 900);
+        stamp(&mut edited, Some(&previous), || unreachable!(), 1_000);
+        assert_eq!(edited.id, "device-1");
+        assert_eq!(edited.modified_at, 5_001);
+
+        let mut added = session("", 0, 900);
+        stamp(&mut added, None, || new_record_id("d", 255, 1), 1_000);
+        assert_eq!(added.id, "d-ff-1");
+        assert_eq!(added.modified_at, 1_000);
+    }
+
+    #[test]
+    fn test_assign_missing_ids() {
+        let mut meditations = vec![
+            MeditationData {
+                datetime: 100,
+                ..Default::default()
+            },
+            session("kept", 7, 60),
+        ];
+        assert_eq!(assign_missing_ids(&mut meditations, "d", 16), 1);
+        assert_eq!(meditations[0].id, "d-10-0");
+        assert_eq!(meditations[0].modified_at, 100_000);
+        assert_eq!(meditations[1].id, "kept");
+    }
+}
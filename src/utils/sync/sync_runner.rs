@@ -0,0 +1,198 @@
+use std::fmt;
+
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+
+use super::{
+    read_write_sync_state::{save_sync_state, try_read_sync_state},
+    sync_engine::{PullResponse, PushResponse, SyncState},
+};
+use crate::{
+    services::{
+        local_storage::StorageError,
+        sync_http::{HttpError, SyncHttp},
+    },
+    utils::{
+        database::meditation_log::read_write_log_crdt::{
+            store_merged_log, try_track_meditation_log,
+        },
+        profile::read_write_profiles::active_profile,
+    },
+};
+
+/// Reasons a sync didn't complete. Queued changes are kept and sent with the next sync.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyncError {
+    /// Sync is off, or was turned off or another profile was switched to while syncing.
+    Disabled,
+    /// The request failed.
+    Http(HttpError),
+    /// The server's answer isn't a valid sync response.
+    InvalidResponse(String),
+    /// The received changes couldn't be stored.
+    Storage(StorageError),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncError::Disabled => f.write_str("sync is off"),
+            SyncError::Http(error) => write!(f, "{}", error),
+            SyncError::InvalidResponse(message) => {
+                write!(f, "unexpected answer from the server: {}", message)
+            }
+            SyncError::Storage(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+impl From<HttpError> for SyncError {
+    fn from(error: HttpError) -> Self {
+        SyncError::Http(error)
+    }
+}
+
+/// What a completed sync did.
+///
+/// # Fields
+/// - `pushed`: The number of local changes the server received.
+/// - `changed`: The number of local sessions that were replaced, added or deleted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub changed: usize,
+}
+
+type OnDone = Box<dyn FnOnce(Result<SyncReport, SyncError>)>;
+
+/// The server and profile a sync started with; it stops if either changes meanwhile.
+#[derive(Clone, PartialEq)]
+struct SyncTarget {
+    endpoint: String,
+    profile_id: String,
+}
+
+impl SyncTarget {
+    /// The server the active profile syncs with, if sync is on.
+    fn of(state: &SyncState) -> Option<Self> {
+        state.endpoint.clone().map(|endpoint| SyncTarget {
+            endpoint,
+            profile_id: active_profile().id,
+        })
+    }
+
+    /// Reads the sync state again, checking that it still syncs with this target.
+    fn read_state(&self) -> Result<SyncState, SyncError> {
+        let state = try_read_sync_state().map_err(SyncError::Storage)?;
+        match SyncTarget::of(&state) {
+            Some(current) if current == *self => Ok(state),
+            _ => Err(SyncError::Disabled),
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: Result<String, HttpError>) -> Result<T, SyncError> {
+    serde_json::from_str(&body?).map_err(|error| SyncError::InvalidResponse(error.to_string()))
+}
+
+/// Pushes the queued changes, then pulls the server's changes into the log.
+///
+/// The replicated log and sync state are read again when each response arrives, so sessions
+/// logged while a request is in flight are kept and queued for the next sync.
+///
+/// # Arguments
+/// * `on_done` - Called once the sync finished or failed.
+pub fn run_sync(on_done: impl FnOnce(Result<SyncReport, SyncError>) + 'static) {
+    match try_read_sync_state().map(|state| SyncTarget::of(&state)) {
+        Ok(Some(target)) => push_next(target, SyncReport::default(), Box::new(on_done)),
+        Ok(None) => on_done(Err(SyncError::Disabled)),
+        Err(error) => on_done(Err(SyncError::Storage(error))),
+    }
+}
+
+fn push_next(target: SyncTarget, report: SyncReport, on_done: OnDone) {
+    let state = match target.read_state() {
+        Ok(state) => state,
+        Err(error) => return on_done(Err(error)),
+    };
+    let Some(request) = state.push_request() else {
+        return pull(target, report, on_done);
+    };
+    let body = match serde_json::to_string(&request) {
+        Ok(body) => body,
+        Err(error) => return on_done(Err(SyncError::InvalidResponse(error.to_string()))),
+    };
+
+    let url = format!("{}/push", target.endpoint);
+    SyncHttp::send("POST", &url, Some(&body), move |body| {
+        let response: PushResponse = match parse(body) {
+            Ok(response) => response,
+            Err(error) => return on_done(Err(error)),
+        };
+        // Tracked first: it may queue changes in the sync state read after it.
+        let mut log = match try_track_meditation_log() {
+            Ok(log) => log,
+            Err(error) => return on_done(Err(SyncError::Storage(error))),
+        };
+        let mut state = match target.read_state() {
+            Ok(state) => state,
+            Err(error) => return on_done(Err(error)),
+        };
+        let changed = state.apply_push(&request, response, &mut log);
+        // Keep the old sync state if the changes can't be stored, so they are fetched again.
+        if changed > 0 {
+            if let Err(error) = store_merged_log(&log) {
+                return on_done(Err(SyncError::Storage(error)));
+            }
+        }
+        if let Err(error) = save_sync_state(&state) {
+            return on_done(Err(SyncError::Storage(error)));
+        }
+
+        let report = SyncReport {
+            pushed: report.pushed + request.records.len(),
+            changed: report.changed + changed,
+        };
+        push_next(target, report, on_done);
+    });
+}
+
+fn pull(target: SyncTarget, report: SyncReport, on_done: OnDone) {
+    let cursor = match target.read_state() {
+        Ok(state) => state.cursor,
+        Err(error) => return on_done(Err(error)),
+    };
+    let url = format!("{}/pull?since={}", target.endpoint, cursor);
+    SyncHttp::send("GET", &url, None, move |body| {
+        let response: PullResponse = match parse(body) {
+            Ok(response) => response,
+            Err(error) => return on_done(Err(error)),
+        };
+        // Tracked first: it may queue changes in the sync state read after it.
+        let mut log = match try_track_meditation_log() {
+            Ok(log) => log,
+            Err(error) => return on_done(Err(SyncError::Storage(error))),
+        };
+        let mut state = match target.read_state() {
+            Ok(state) => state,
+            Err(error) => return on_done(Err(error)),
+        };
+        let changed = state.apply_pull(response, &mut log, Utc::now().timestamp_millis());
+        // Keep the old sync state if the changes can't be stored, so they are fetched again.
+        if changed > 0 {
+            if let Err(error) = store_merged_log(&log) {
+                return on_done(Err(SyncError::Storage(error)));
+            }
+        }
+        if let Err(error) = save_sync_state(&state) {
+            return on_done(Err(SyncError::Storage(error)));
+        }
+
+        on_done(Ok(SyncReport {
+            changed: report.changed + changed,
+            ..report
+        }));
+    });
+}
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use super::{
+    sync_engine::{PullResponse, PushRequest, PushResponse},
+    sync_record::SyncRecord,
+};
+use crate::utils::database::meditation_log::log_crdt::LogCrdt;
+
+/// A reference implementation of the sync backend, kept in memory.
+///
+/// A self-hosted server has to behave the same way: merge every pushed session into its
+/// `LogCrdt`, number each session in the order it last changed, and hand out every session
+/// that changed after a client's cursor. The tests run it in-process to sync without a network.
+#[derive(Clone, Debug, Default)]
+pub struct SyncServer {
+    log: LogCrdt,
+    /// The sequence number each session last changed under.
+    changed_at: BTreeMap<String, u64>,
+    sequence: u64,
+}
+
+impl SyncServer {
+    /// Handles `POST {endpoint}/push`.
+    ///
+    /// Returns the merged state of pushed sessions that had also changed elsewhere.
+    /// Pushing the same record again changes nothing.
+    pub fn push(&mut self, request: &PushRequest) -> PushResponse {
+        let mut response = PushResponse::default();
+        for record in &request.records {
+            if self.log.merge_session(&record.id, &record.session) {
+                self.sequence += 1;
+                self.changed_at.insert(record.id.clone(), self.sequence);
+            }
+            match SyncRecord::from_log(&self.log, &record.id) {
+                Some(merged) if merged != *record => response.merged.push(merged),
+                _ => {}
+            }
+        }
+        response
+    }
+
+    /// Handles `GET {endpoint}/pull?since={cursor}`.
+    pub fn pull(&self, since: u64) -> PullResponse {
+        let mut changes: Vec<(&u64, &String)> = self
+            .changed_at
+            .iter()
+            .filter(|(_, sequence)| **sequence > since)
+            .map(|(id, sequence)| (sequence, id))
+            .collect();
+        changes.sort();
+        PullResponse {
+            records: changes
+                .into_iter()
+                .filter_map(|(_, id)| SyncRecord::from_log(&self.log, id))
+                .collect(),
+            cursor: self.sequence,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::database::meditation_log::meditation_data_builder::MeditationData;
+
+    fn record(id: &str, duration: i32, device: &str, at: i64) -> SyncRecord {
+        let mut log = LogCrdt::default();
+        let meditation = MeditationData {
+            id: id.to_string(),
+            duration,
+            modified_at: at,
+            ..Default::default()
+        };
+        log.observe(&[meditation], device, at);
+        SyncRecord::from_log(&log, id).unwrap()
+    }
+
+    fn push(server: &mut SyncServer, records: Vec<SyncRecord>) -> PushResponse {
+        server.push(&PushRequest {
+            device_id: "test".to_string(),
+            records,
+        })
+    }
+
+    #[test]
+    fn test_server_merges_and_returns_changed_sessions() {
+        let mut server = SyncServer::default();
+        assert!(push(&mut server, vec![record("a", 60, "phone", 5)])
+            .merged
+            .is_empty());
+
+        let response = push(&mut server, vec![record("a", 90, "laptop", 3)]);
+        assert_eq!(response.merged.len(), 1);
+        assert_eq!(response.merged[0].session.to_meditation("a").duration, 60);
+        assert_eq!(server.pull(0).cursor, 2);
+    }
+
+    #[test]
+    fn test_pull_returns_changes_after_cursor_in_order() {
+        let mut server = SyncServer::default();
+        push(
+            &mut server,
+            vec![record("b", 60, "d", 1), record("a", 60, "d", 1)],
+        );
+        push(&mut server, vec![record("b", 90, "d", 2)]);
+        push(&mut server, vec![record("b", 90, "d", 2)]);
+
+        let pulled = server.pull(1);
+        let ids: Vec<&str> = pulled
+            .records
+            .iter()
+            .map(|record| record.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(pulled.cursor, 3);
+        assert!(server.pull(3).records.is_empty());
+    }
+}
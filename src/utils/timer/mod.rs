@@ -0,0 +1,2 @@
+pub mod read_write_timer_state;
+pub mod timer_state;
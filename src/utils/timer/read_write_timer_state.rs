@@ -0,0 +1,39 @@
+use super::timer_state::{TimerState, TimerStatus};
+use crate::utils::{
+    constants::ACTIVE_TIMER_KEY, database::protected_storage::ProtectedStorage,
+    profile::read_write_profiles::profile_key,
+};
+use web_sys::wasm_bindgen::JsValue;
+
+/// Persists the timer so a session in progress survives reloads and tab suspension.
+///
+/// Only timestamps are stored, so the elapsed time is reconstructed exactly on load.
+/// Logs an error message to the web console on failure.
+///
+/// # Arguments
+/// * `timer` - The timer state to store.
+pub fn save_timer_state(timer: &TimerState) {
+    match serde_json::to_string(timer) {
+        Ok(value) => ProtectedStorage::create(&profile_key(ACTIVE_TIMER_KEY), &value)
+            .unwrap_or_else(|_| {
+                web_sys::console::log_1(&JsValue::from_str("Failed to write timer state"));
+            }),
+        Err(_) => web_sys::console::log_1(&JsValue::from_str("Serialization failed")),
+    }
+}
+
+/// Reads the stored timer, if any.
+///
+/// A finished timer is returned reset to idle, keeping its plan, category and speaker,
+/// since its session has already been logged.
+///
+/// # Returns
+/// The stored `TimerState`, or `None` if nothing is stored or the stored value can't be read.
+pub fn read_timer_state() -> Option<TimerState> {
+    let value = ProtectedStorage::read(&profile_key(ACTIVE_TIMER_KEY)).ok()??;
+    let mut timer: TimerState = serde_json::from_str(&value).ok()?;
+    if timer.status() == TimerStatus::Finished {
+        timer.reset();
+    }
+    Some(timer)
+}
@@ -0,0 +1,375 @@
+use crate::utils::database::meditation_log::{
+    meditation_data_builder::{MeditationDataBuilder, MeditationDataError, MeditationField},
+    session_plan::SessionPlan,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The phase a meditation timer is in.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimerStatus {
+    #[default]
+    Idle,
+    Running,
+    Paused,
+    Finished,
+}
+
+/// The state of a meditation timer, derived entirely from wall-clock timestamps.
+///
+/// Nothing here counts ticks: elapsed time is always `now - started_at - paused time`,
+/// so a late or skipped tick never makes the timer drift.
+/// All timestamps are UNIX epoch milliseconds.
+///
+/// # Fields
+/// - `plan`: The structure of the session; its total length is the timer's target. Timers
+///   stored before sessions had plans hold a `target_seconds` instead, read as a simple plan.
+/// - `category`: The category the session will be logged with.
+/// - `speaker`: The speaker or guide the session will be logged with.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct TimerState {
+    #[serde(
+        default,
+        alias = "target_seconds",
+        deserialize_with = "plan_or_target_seconds"
+    )]
+    pub plan: SessionPlan,
+    pub category: String,
+    pub speaker: String,
+    status: TimerStatus,
+    started_at: Option<i64>,
+    paused_ms: i64,
+    paused_since: Option<i64>,
+    finished_at: Option<i64>,
+}
+
+/// Reads a stored plan, or the length that timers stored before they followed a plan.
+fn plan_or_target_seconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SessionPlan, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredPlan {
+        Plan(SessionPlan),
+        TargetSeconds(i64),
+    }
+
+    Ok(match StoredPlan::deserialize(deserializer)? {
+        StoredPlan::Plan(plan) => plan,
+        StoredPlan::TargetSeconds(seconds) => SessionPlan::simple(seconds),
+    })
+}
+
+impl TimerState {
+    /// Constructs an idle timer for a session following `plan`.
+    pub fn new(plan: SessionPlan, category: impl Into<String>, speaker: impl Into<String>) -> Self {
+        TimerState {
+            plan,
+            category: category.into(),
+            speaker: speaker.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn status(&self) -> TimerStatus {
+        self.status
+    }
+
+    /// The total length of the planned session in seconds.
+    pub fn target_seconds(&self) -> i64 {
+        self.plan.total_seconds()
+    }
+
+    /// Starts the timer. Does nothing unless the timer is idle.
+    pub fn start(&mut self, now: i64) {
+        if self.status == TimerStatus::Idle {
+            self.status = TimerStatus::Running;
+            self.started_at = Some(now);
+        }
+    }
+
+    /// Pauses a running timer.
+    pub fn pause(&mut self, now: i64) {
+        if self.status == TimerStatus::Running {
+            self.status = TimerStatus::Paused;
+            self.paused_since = Some(now);
+        }
+    }
+
+    /// Resumes a paused timer, adding the pause to the total paused time.
+    pub fn resume(&mut self, now: i64) {
+        if self.status == TimerStatus::Paused {
+            if let Some(paused_since) = self.paused_since.take() {
+                self.paused_ms += (now - paused_since).max(0);
+            }
+            self.status = TimerStatus::Running;
+        }
+    }
+
+    /// Finishes a running or paused timer.
+    ///
+    /// # Returns
+    /// The meditated time in whole seconds, excluding pauses and capped at the target length,
+    /// or `None` if the timer was never started or is already finished.
+    pub fn finish(&mut self, now: i64) -> Option<i64> {
+        match self.status {
+            TimerStatus::Running | TimerStatus::Paused => {
+                let seconds = self.meditated_seconds(now);
+                self.resume(now);
+                self.status = TimerStatus::Finished;
+                self.finished_at = Some(now);
+                Some(seconds)
+            }
+            TimerStatus::Idle | TimerStatus::Finished => None,
+        }
+    }
+
+    /// The session this timer logs after `seconds` of meditation.
+    ///
+    /// Once started, the session is dated when those seconds were reached, not when it is
+    /// logged: a timer finished after a reload, or completed on a late tick, keeps its time.
+    pub fn session(&self, seconds: i64) -> MeditationDataBuilder {
+        let mut builder = MeditationDataBuilder::new();
+        builder
+            .duration(seconds as i32)
+            .category(self.category.clone())
+            .speaker(self.speaker.clone())
+            .plan(self.plan.clone());
+        if let Some(started_at) = self.started_at {
+            builder.datetime_at((started_at + self.paused_ms + seconds * 1000) / 1000);
+        }
+        builder
+    }
+
+    /// Problems with the category and speaker that would stop the session from being logged.
+    ///
+    /// The timer should only be started once there are none.
+    pub fn field_errors(&self) -> Vec<MeditationDataError> {
+        self.session(self.target_seconds())
+            .validate()
+            .into_iter()
+            .filter(|error| {
+                matches!(
+                    error.field(),
+                    MeditationField::Category | MeditationField::Speaker
+                )
+            })
+            .collect()
+    }
+
+    /// Finishes a running or paused timer once `log` has stored its session.
+    ///
+    /// If `log` fails, the timer is paused instead of finished, so the session isn't lost: the
+    /// category or speaker can be corrected and finishing tried again.
+    ///
+    /// # Arguments
+    /// * `now` - The current time.
+    /// * `log` - Stores the session; it is given the builder from `session`, already dated.
+    ///
+    /// # Returns
+    /// The meditated seconds and what `log` returned, `log`'s error, or `None` if the timer
+    /// was never started or is already finished.
+    pub fn finish_with<T, E>(
+        &mut self,
+        now: i64,
+        log: impl FnOnce(&mut MeditationDataBuilder) -> Result<T, E>,
+    ) -> Option<Result<(i64, T), E>> {
+        if !matches!(self.status, TimerStatus::Running | TimerStatus::Paused) {
+            return None;
+        }
+        let seconds = self.meditated_seconds(now);
+        match log(&mut self.session(seconds)) {
+            Ok(logged) => {
+                self.finish(now);
+                Some(Ok((seconds, logged)))
+            }
+            Err(error) => {
+                self.pause(now);
+                Some(Err(error))
+            }
+        }
+    }
+
+    /// Returns the timer to idle, keeping its plan, category and speaker.
+    pub fn reset(&mut self) {
+        *self = TimerState::new(
+            self.plan.clone(),
+            self.category.clone(),
+            self.speaker.clone(),
+        );
+    }
+
+    /// Milliseconds meditated so far, excluding pauses.
+    pub fn elapsed_ms(&self, now: i64) -> i64 {
+        let Some(started_at) = self.started_at else {
+            return 0;
+        };
+        // A paused or finished timer is frozen at the moment it stopped.
+        let until = self.paused_since.or(self.finished_at).unwrap_or(now);
+        (until - started_at - self.paused_ms).max(0)
+    }
+
+    /// Milliseconds left until the target length is reached.
+    pub fn remaining_ms(&self, now: i64) -> i64 {
+        (self.target_seconds() * 1000 - self.elapsed_ms(now)).max(0)
+    }
+
+    /// Whether a running timer has reached its target length.
+    pub fn is_complete(&self, now: i64) -> bool {
+        self.status == TimerStatus::Running && self.remaining_ms(now) == 0
+    }
+
+    /// Whole seconds meditated so far, capped at the target length.
+    pub fn meditated_seconds(&self, now: i64) -> i64 {
+        (self.elapsed_ms(now) / 1000).min(self.target_seconds())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: i64 = 60_000;
+
+    #[test]
+    fn test_pauses_are_excluded() {
+        let mut timer = TimerState::new(SessionPlan::simple(600), "Mindfulness", "Alice");
+        timer.start(0);
+        timer.pause(2 * MINUTE);
+        assert_eq!(timer.elapsed_ms(5 * MINUTE), 2 * MINUTE);
+
+        timer.resume(5 * MINUTE);
+        assert_eq!(timer.elapsed_ms(6 * MINUTE), 3 * MINUTE);
+        assert_eq!(timer.remaining_ms(6 * MINUTE), 7 * MINUTE);
+
+        assert_eq!(timer.finish(7 * MINUTE + 500), Some(240));
+        assert_eq!(timer.status(), TimerStatus::Finished);
+        assert_eq!(timer.elapsed_ms(20 * MINUTE), 4 * MINUTE + 500);
+    }
+
+    #[test]
+    fn test_finish_while_paused() {
+        let mut timer = TimerState::new(SessionPlan::simple(600), "Mindfulness", "Alice");
+        timer.start(0);
+        timer.pause(MINUTE);
+        assert_eq!(timer.finish(9 * MINUTE), Some(60));
+    }
+
+    #[test]
+    fn test_completion_caps_at_target() {
+        let mut timer = TimerState::new(SessionPlan::simple(60), "Mindfulness", "Alice");
+        timer.start(1_000);
+        assert!(!timer.is_complete(30_000));
+        assert!(timer.is_complete(61_000));
+        assert_eq!(timer.finish(90_000), Some(60));
+    }
+
+    #[test]
+    fn test_restored_timer_keeps_counting_from_timestamps() {
+        let mut timer = TimerState::new(SessionPlan::simple(1200), "Mindfulness", "Alice");
+        timer.start(0);
+        timer.pause(MINUTE);
+        timer.resume(3 * MINUTE);
+
+        let stored = serde_json::to_string(&timer).unwrap();
+        // The tab is reloaded ten minutes later.
+        let restored: TimerState = serde_json::from_str(&stored).unwrap();
+
+        assert_eq!(restored, timer);
+        assert_eq!(restored.status(), TimerStatus::Running);
+        assert_eq!(restored.elapsed_ms(13 * MINUTE), 11 * MINUTE);
+    }
+
+    #[test]
+    fn test_blank_fields_are_reported_before_starting() {
+        let timer = TimerState::new(SessionPlan::simple(600), "Mindfulness", " ");
+        assert_eq!(
+            timer.field_errors(),
+            vec![MeditationDataError::Blank(MeditationField::Speaker)]
+        );
+
+        let timer = TimerState::new(SessionPlan::simple(600), "Mindfulness", "Alice");
+        assert!(timer.field_errors().is_empty());
+    }
+
+    #[test]
+    fn test_failed_log_keeps_the_session() {
+        let mut timer = TimerState::new(SessionPlan::simple(600), "Mindfulness", "");
+        timer.start(0);
+
+        let failed = timer.finish_with(4 * MINUTE, |builder| builder.datetime_at(1).build());
+        assert_eq!(
+            failed,
+            Some(Err(MeditationDataError::Blank(MeditationField::Speaker)))
+        );
+        assert_eq!(timer.status(), TimerStatus::Paused);
+        assert_eq!(timer.elapsed_ms(9 * MINUTE), 4 * MINUTE);
+
+        timer.speaker = "Alice".to_string();
+        let logged = timer.finish_with(9 * MINUTE, |builder| {
+            builder
+                .datetime_at(1)
+                .build()
+                .map(|meditation| meditation.duration)
+        });
+        assert_eq!(logged, Some(Ok((240, 240))));
+        assert_eq!(timer.status(), TimerStatus::Finished);
+        assert_eq!(timer.finish_with(10 * MINUTE, |_| Ok::<_, ()>(())), None);
+    }
+
+    #[test]
+    fn test_session_is_dated_when_it_ended() {
+        let start = 1_700_000_000_000;
+        let mut timer = TimerState::new(SessionPlan::simple(600), "Mindfulness", "Alice");
+        timer.start(start);
+        timer.pause(start + 2 * MINUTE);
+        timer.resume(start + 5 * MINUTE);
+
+        // Completed on a tick an hour after the target was reached, e.g. after a reload.
+        let logged = timer.finish_with(start + 60 * MINUTE, |builder| builder.build());
+        let (seconds, meditation) = logged.unwrap().unwrap();
+        assert_eq!(seconds, 600);
+        assert_eq!(meditation.datetime, (start + 13 * MINUTE) / 1000);
+
+        // Finished while paused, it ends where the pause began.
+        let mut timer = TimerState::new(SessionPlan::simple(600), "Mindfulness", "Alice");
+        timer.start(start);
+        timer.pause(start + 4 * MINUTE);
+        let logged = timer.finish_with(start + 30 * MINUTE, |builder| builder.build());
+        assert_eq!(
+            logged.unwrap().unwrap().1.datetime,
+            (start + 4 * MINUTE) / 1000
+        );
+    }
+
+    #[test]
+    fn test_reads_timers_stored_before_plans() {
+        let stored = r#"{"target_seconds":1200,"category":"Mindfulness","speaker":"Alice",
+            "status":"Paused","started_at":0,"paused_ms":60000,"paused_since":300000,
+            "finished_at":null}"#;
+        let restored: TimerState = serde_json::from_str(stored).unwrap();
+
+        assert_eq!(restored.plan, SessionPlan::simple(1200));
+        assert_eq!(restored.status(), TimerStatus::Paused);
+        assert_eq!(restored.elapsed_ms(10 * MINUTE), 4 * MINUTE);
+        assert_eq!(restored.remaining_ms(10 * MINUTE), 16 * MINUTE);
+    }
+
+    #[test]
+    fn test_invalid_transitions_are_ignored() {
+        let mut timer = TimerState::new(SessionPlan::simple(60), "Mindfulness", "Alice");
+        timer.pause(0);
+        timer.resume(0);
+        assert_eq!(timer.status(), TimerStatus::Idle);
+        assert_eq!(timer.finish(0), None);
+
+        timer.start(0);
+        timer.start(MINUTE);
+        assert_eq!(timer.elapsed_ms(1_000), 1_000);
+
+        timer.reset();
+        assert_eq!(
+            timer,
+            TimerState::new(SessionPlan::simple(60), "Mindfulness", "Alice")
+        );
+    }
+}
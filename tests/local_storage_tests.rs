@@ -1,5 +1,18 @@
 use wasm_bindgen_test::*;
-use yew_app::services::local_storage::LocalStorageService;
+use yew_app::services::local_storage::{stored_bytes, LocalStorageService, StorageError};
+use yew_app::utils::{
+    constants::{CATALOG_KEY, LOG_CRDT_KEY, MEDITATION_LOG_KEY, SYNC_STATE_KEY},
+    database::meditation_log::{
+        meditation_data_builder::MeditationData,
+        read_write_catalog::{read_catalog, reconcile_meditation_log},
+        read_write_log_crdt::{track_meditation_log, try_track_meditation_log},
+        read_write_meditation_data::{
+            delete_meditation, find_meditation, read_meditation_data, replace_meditations,
+        },
+    },
+    profile::read_write_profiles::profile_key,
+    sync::read_write_sync_state::try_read_sync_state,
+};
 wasm_bindgen_test_configure!(run_in_browser);
 
 #[wasm_bindgen_test]
@@ -22,3 +35,250 @@ fn test_delete() {
 
     assert_eq!(read_value, None);
 }
+
+#[wasm_bindgen_test]
+fn test_used_bytes() {
+    let key = "test_usage_key";
+
+    LocalStorageService::delete(key).expect("Failed to delete item");
+    let before = LocalStorageService::used_bytes().expect("Failed to measure usage");
+    LocalStorageService::create(key, "calm 🧘").expect("Failed to create item");
+    let after = LocalStorageService::used_bytes().expect("Failed to measure usage");
+    LocalStorageService::delete(key).expect("Failed to delete item");
+
+    // UTF-16: 14 units for the key, 7 for the value, whose emoji takes two.
+    assert_eq!(after - before, 42);
+    assert_eq!(stored_bytes(key, "calm 🧘"), 42);
+}
+
+#[wasm_bindgen_test]
+fn test_available_in_browser() {
+    assert_eq!(LocalStorageService::check_available(), Ok(()));
+}
+
+#[wasm_bindgen_test]
+fn test_keys() {
+    LocalStorageService::create("test_keys_b", "2").expect("Failed to create item");
+    LocalStorageService::create("test_keys_a", "1").expect("Failed to create item");
+
+    let keys = LocalStorageService::keys().expect("Failed to list keys");
+    let ours: Vec<&String> = keys
+        .iter()
+        .filter(|key| key.starts_with("test_keys_"))
+        .collect();
+    assert_eq!(ours, vec!["test_keys_a", "test_keys_b"]);
+
+    LocalStorageService::clear_namespace("test_keys_").expect("Failed to clear");
+}
+
+#[wasm_bindgen_test]
+fn test_clear_namespace() {
+    LocalStorageService::create("test_clear:one", "1").expect("Failed to create item");
+    LocalStorageService::create("test_clear:two", "2").expect("Failed to create item");
+    LocalStorageService::create("test_clear_other", "3").expect("Failed to create item");
+
+    let removed = LocalStorageService::clear_namespace("test_clear:").expect("Failed to clear");
+
+    assert_eq!(removed, 2);
+    assert_eq!(LocalStorageService::read("test_clear:one").unwrap(), None);
+    assert_eq!(
+        LocalStorageService::read("test_clear_other").unwrap(),
+        Some("3".to_string())
+    );
+    LocalStorageService::delete("test_clear_other").expect("Failed to delete item");
+}
+
+#[wasm_bindgen_test]
+fn test_namespaces_do_not_collide() {
+    let first = LocalStorageService::namespace("test_profile_1");
+    let second = LocalStorageService::namespace("test_profile_10");
+
+    first
+        .create("meditationLog", "first")
+        .expect("Failed to create item");
+    second
+        .create("meditationLog", "second")
+        .expect("Failed to create item");
+
+    assert_eq!(first.key("meditationLog"), "test_profile_1:meditationLog");
+    assert_eq!(
+        first.read("meditationLog").unwrap(),
+        Some("first".to_string())
+    );
+    assert_eq!(
+        second.read("meditationLog").unwrap(),
+        Some("second".to_string())
+    );
+    assert_eq!(first.keys().unwrap(), vec!["meditationLog"]);
+
+    assert_eq!(first.clear().unwrap(), 1);
+    assert_eq!(first.read("meditationLog").unwrap(), None);
+    assert_eq!(
+        second.read("meditationLog").unwrap(),
+        Some("second".to_string())
+    );
+
+    second
+        .delete("meditationLog")
+        .expect("Failed to delete item");
+    assert!(second.keys().unwrap().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn test_json_round_trip() {
+    let namespace = LocalStorageService::namespace("test_json");
+    let value = vec![(1, "calm".to_string()), (2, "focus".to_string())];
+
+    namespace
+        .set_json("pairs", &value)
+        .expect("Failed to store");
+    let read: Option<Vec<(i32, String)>> = namespace.get_json("pairs").expect("Failed to read");
+    assert_eq!(read, Some(value));
+
+    let missing: Option<Vec<(i32, String)>> = namespace.get_json("missing").unwrap();
+    assert_eq!(missing, None);
+
+    namespace.clear().expect("Failed to clear");
+}
+
+#[wasm_bindgen_test]
+fn test_json_of_wrong_type_is_unreadable() {
+    LocalStorageService::create("test_json_wrong", "not json").expect("Failed to create item");
+
+    let read = LocalStorageService::get_json::<Vec<i32>>("test_json_wrong");
+    assert!(matches!(read, Err(StorageError::Unreadable(_))));
+
+    LocalStorageService::delete("test_json_wrong").expect("Failed to delete item");
+}
+
+#[wasm_bindgen_test]
+fn test_unreadable_sync_state_is_left_untouched() {
+    let key = profile_key(SYNC_STATE_KEY);
+    LocalStorageService::create(&key, "not json").expect("Failed to create item");
+
+    let read = try_read_sync_state();
+    assert!(matches!(read, Err(StorageError::Unreadable(_))));
+    assert_eq!(
+        LocalStorageService::read(&key).unwrap(),
+        Some("not json".to_string())
+    );
+
+    LocalStorageService::delete(&key).expect("Failed to delete item");
+    assert!(!try_read_sync_state().unwrap().device_id.is_empty());
+    LocalStorageService::delete(&key).expect("Failed to delete item");
+}
+
+#[wasm_bindgen_test]
+fn test_archiving_frees_space() {
+    let sessions: Vec<MeditationData> = (0..200)
+        .map(|i| MeditationData {
+            datetime: 1_700_000_000 + i * 86_400,
+            duration: 600,
+            category: "Mindfulness".to_string(),
+            speaker: "Alice".to_string(),
+            notes: Some(format!("Session {} felt calm", i)),
+            ..Default::default()
+        })
+        .collect();
+    replace_meditations(&sessions).expect("Failed to store sessions");
+    track_meditation_log();
+    let before = LocalStorageService::used_bytes().expect("Failed to measure usage");
+
+    // What archiving leaves behind: the newest sessions, the others deleted.
+    let stored = read_meditation_data();
+    replace_meditations(&stored[180..]).expect("Failed to store sessions");
+    track_meditation_log();
+    let after = LocalStorageService::used_bytes().expect("Failed to measure usage");
+
+    // The replicated log keeps deleted sessions, so only the log itself shrinks.
+    assert!(after < before, "{} bytes before, {} after", before, after);
+    for key in [MEDITATION_LOG_KEY, LOG_CRDT_KEY, SYNC_STATE_KEY] {
+        LocalStorageService::delete(&profile_key(key)).expect("Failed to delete item");
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_unreadable_replicated_log_is_never_merged_into() {
+    let session = MeditationData {
+        datetime: 1_700_000_000,
+        duration: 600,
+        category: "Mindfulness".to_string(),
+        speaker: "Alice".to_string(),
+        ..Default::default()
+    };
+    replace_meditations(&[session]).expect("Failed to store sessions");
+    LocalStorageService::create(&profile_key(LOG_CRDT_KEY), "not json")
+        .expect("Failed to create item");
+
+    assert!(matches!(
+        try_track_meditation_log(),
+        Err(StorageError::Unreadable(_))
+    ));
+    assert_eq!(read_meditation_data().len(), 1);
+    assert_eq!(
+        LocalStorageService::read(&profile_key(LOG_CRDT_KEY)).unwrap(),
+        Some("not json".to_string())
+    );
+
+    for key in [MEDITATION_LOG_KEY, LOG_CRDT_KEY, SYNC_STATE_KEY] {
+        LocalStorageService::delete(&profile_key(key)).expect("Failed to delete item");
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_unreadable_log_is_reconciled_later() {
+    let key = profile_key(MEDITATION_LOG_KEY);
+    LocalStorageService::create(&key, "not json").expect("Failed to create item");
+
+    assert_eq!(reconcile_meditation_log(), 0);
+    assert!(!read_catalog().reconciled);
+
+    let session = MeditationData {
+        datetime: 1_700_000_000,
+        duration: 600,
+        category: " mindfulness ".to_string(),
+        speaker: "Alice".to_string(),
+        ..Default::default()
+    };
+    replace_meditations(&[session]).expect("Failed to store sessions");
+    reconcile_meditation_log();
+    assert!(read_catalog().reconciled);
+
+    for key in [
+        MEDITATION_LOG_KEY,
+        LOG_CRDT_KEY,
+        SYNC_STATE_KEY,
+        CATALOG_KEY,
+    ] {
+        LocalStorageService::delete(&profile_key(key)).expect("Failed to delete item");
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_sessions_are_deleted_by_id_after_reordering() {
+    let session = |id: &str| MeditationData {
+        datetime: 1_700_000_000,
+        duration: 600,
+        category: "Mindfulness".to_string(),
+        speaker: "Alice".to_string(),
+        id: id.to_string(),
+        ..Default::default()
+    };
+    // As after a merge, the sessions are no longer in the order they were added.
+    replace_meditations(&[session("b"), session("a")]).expect("Failed to store sessions");
+
+    assert_eq!(
+        delete_meditation("a").map(|removed| removed.id),
+        Some("a".to_string())
+    );
+    assert_eq!(delete_meditation("a"), None);
+    assert_eq!(
+        find_meditation("b").map(|found| found.id),
+        Some("b".to_string())
+    );
+    assert_eq!(find_meditation(""), None);
+
+    for key in [MEDITATION_LOG_KEY, LOG_CRDT_KEY, SYNC_STATE_KEY] {
+        LocalStorageService::delete(&profile_key(key)).expect("Failed to delete item");
+    }
+}